@@ -1,12 +1,44 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20220101_000002_add_song_file_hash;
+mod m20220101_000003_add_song_track_position;
+mod m20220101_000004_add_song_file_stat;
+mod m20220101_000005_add_user_subsonic_password;
+mod m20220101_000006_create_playlist;
+mod m20220101_000007_add_playlist_rules;
+mod m20220101_000008_create_star;
+mod m20220101_000009_create_rating;
+mod m20220101_000010_create_play_event;
+mod m20220101_000011_create_listening_stats;
+mod m20220101_000012_create_chart_entry;
+mod m20220101_000013_create_item_neighbor;
+mod m20220101_000014_create_play_queue;
+mod m20220101_000015_create_song_version;
+mod m20220101_000016_create_credit;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20220101_000002_add_song_file_hash::Migration),
+            Box::new(m20220101_000003_add_song_track_position::Migration),
+            Box::new(m20220101_000004_add_song_file_stat::Migration),
+            Box::new(m20220101_000005_add_user_subsonic_password::Migration),
+            Box::new(m20220101_000006_create_playlist::Migration),
+            Box::new(m20220101_000007_add_playlist_rules::Migration),
+            Box::new(m20220101_000008_create_star::Migration),
+            Box::new(m20220101_000009_create_rating::Migration),
+            Box::new(m20220101_000010_create_play_event::Migration),
+            Box::new(m20220101_000011_create_listening_stats::Migration),
+            Box::new(m20220101_000012_create_chart_entry::Migration),
+            Box::new(m20220101_000013_create_item_neighbor::Migration),
+            Box::new(m20220101_000014_create_play_queue::Migration),
+            Box::new(m20220101_000015_create_song_version::Migration),
+            Box::new(m20220101_000016_create_credit::Migration),
+        ]
     }
 }
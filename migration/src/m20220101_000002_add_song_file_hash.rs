@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add content hash column to songs table
+        manager.alter_table(
+            Table::alter()
+                .table(Song::Table)
+                .add_column(ColumnDef::new(Song::FileHash).string().null())
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_song_file_hash")
+                .table(Song::Table)
+                .col(Song::FileHash)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(Index::drop().name("idx_song_file_hash").table(Song::Table).to_owned()).await?;
+
+        manager.alter_table(
+            Table::alter()
+                .table(Song::Table)
+                .drop_column(Song::FileHash)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Song {
+    Table,
+    FileHash,
+}
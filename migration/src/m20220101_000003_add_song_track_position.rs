@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add track and disc number columns to songs table
+        manager.alter_table(
+            Table::alter()
+                .table(Song::Table)
+                .add_column(ColumnDef::new(Song::TrackNumber).integer().null())
+                .add_column(ColumnDef::new(Song::DiscNumber).integer().null())
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Song::Table)
+                .drop_column(Song::TrackNumber)
+                .drop_column(Song::DiscNumber)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Song {
+    Table,
+    TrackNumber,
+    DiscNumber,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add file size and modification time used by the incremental library scan
+        manager.alter_table(
+            Table::alter()
+                .table(Song::Table)
+                .add_column(ColumnDef::new(Song::FileSize).big_integer().null())
+                .add_column(ColumnDef::new(Song::FileModifiedAt).timestamp_with_time_zone().null())
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_song_file_path")
+                .table(Song::Table)
+                .col(Song::FilePath)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(Index::drop().name("idx_song_file_path").table(Song::Table).to_owned()).await?;
+
+        manager.alter_table(
+            Table::alter()
+                .table(Song::Table)
+                .drop_column(Song::FileSize)
+                .drop_column(Song::FileModifiedAt)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Song {
+    Table,
+    FilePath,
+    FileSize,
+    FileModifiedAt,
+}
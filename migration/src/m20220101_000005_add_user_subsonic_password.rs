@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Subsonic token auth needs a recoverable password, stored encrypted and separate from the login password
+        manager.alter_table(
+            Table::alter()
+                .table(User::Table)
+                .add_column(ColumnDef::new(User::SubsonicPassword).text().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(User::Table)
+                .drop_column(User::SubsonicPassword)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    SubsonicPassword,
+}
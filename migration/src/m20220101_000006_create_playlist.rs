@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create playlists table
+        manager.create_table(
+            Table::create()
+                .table(Playlist::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(Playlist::Id).uuid().primary_key())
+                .col(ColumnDef::new(Playlist::OwnerId).uuid().not_null())
+                .col(ColumnDef::new(Playlist::Name).string().not_null())
+                .col(ColumnDef::new(Playlist::Description).string().null())
+                .col(ColumnDef::new(Playlist::Public).boolean().not_null().default(false))
+                .col(ColumnDef::new(Playlist::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .col(ColumnDef::new(Playlist::UpdatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .col(ColumnDef::new(Playlist::CreatedBy).string().not_null())
+                .col(ColumnDef::new(Playlist::UpdatedBy).string().not_null())
+                .col(ColumnDef::new(Playlist::DeleteFlag).boolean().not_null().default(false))
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_playlist_owner_id")
+                .table(Playlist::Table)
+                .col(Playlist::OwnerId)
+                .to_owned()
+        ).await?;
+
+        // Create playlist entries table, position is the 0-based order inside the playlist
+        manager.create_table(
+            Table::create()
+                .table(PlaylistEntry::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(PlaylistEntry::Id).uuid().primary_key())
+                .col(ColumnDef::new(PlaylistEntry::PlaylistId).uuid().not_null())
+                .col(ColumnDef::new(PlaylistEntry::SongId).uuid().not_null())
+                .col(ColumnDef::new(PlaylistEntry::Position).integer().not_null())
+                .col(ColumnDef::new(PlaylistEntry::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .col(ColumnDef::new(PlaylistEntry::CreatedBy).string().not_null())
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_playlist_entry_playlist_position")
+                .table(PlaylistEntry::Table)
+                .col(PlaylistEntry::PlaylistId)
+                .col(PlaylistEntry::Position)
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_playlist_entry_song_id")
+                .table(PlaylistEntry::Table)
+                .col(PlaylistEntry::SongId)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(PlaylistEntry::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Playlist::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Playlist {
+    Table,
+    Id,
+    OwnerId,
+    Name,
+    Description,
+    Public,
+    CreatedAt,
+    UpdatedAt,
+    CreatedBy,
+    UpdatedBy,
+    DeleteFlag,
+}
+
+#[derive(DeriveIden)]
+enum PlaylistEntry {
+    Table,
+    Id,
+    PlaylistId,
+    SongId,
+    Position,
+    CreatedAt,
+    CreatedBy,
+}
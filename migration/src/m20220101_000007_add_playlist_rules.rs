@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Smart playlists store their rule tree instead of entries; NULL means a regular playlist
+        manager.alter_table(
+            Table::alter()
+                .table(Playlist::Table)
+                .add_column(ColumnDef::new(Playlist::Rules).json_binary().null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.alter_table(
+            Table::alter()
+                .table(Playlist::Table)
+                .drop_column(Playlist::Rules)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Playlist {
+    Table,
+    Rules,
+}
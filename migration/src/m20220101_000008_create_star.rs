@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create stars table, item_type is one of song/album/artist
+        manager.create_table(
+            Table::create()
+                .table(Star::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(Star::Id).uuid().primary_key())
+                .col(ColumnDef::new(Star::UserId).uuid().not_null())
+                .col(ColumnDef::new(Star::ItemType).string().not_null())
+                .col(ColumnDef::new(Star::ItemId).uuid().not_null())
+                .col(ColumnDef::new(Star::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .to_owned()
+        ).await?;
+
+        // A user stars an item at most once
+        manager.create_index(
+            Index::create()
+                .name("idx_star_user_item")
+                .table(Star::Table)
+                .col(Star::UserId)
+                .col(Star::ItemType)
+                .col(Star::ItemId)
+                .unique()
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_star_item")
+                .table(Star::Table)
+                .col(Star::ItemType)
+                .col(Star::ItemId)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Star::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Star {
+    Table,
+    Id,
+    UserId,
+    ItemType,
+    ItemId,
+    CreatedAt,
+}
@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create ratings table, item_type is one of song/album and rating is 1-5
+        manager.create_table(
+            Table::create()
+                .table(Rating::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(Rating::Id).uuid().primary_key())
+                .col(ColumnDef::new(Rating::UserId).uuid().not_null())
+                .col(ColumnDef::new(Rating::ItemType).string().not_null())
+                .col(ColumnDef::new(Rating::ItemId).uuid().not_null())
+                .col(ColumnDef::new(Rating::Rating).small_integer().not_null().check(Expr::col(Rating::Rating).between(1, 5)))
+                .col(ColumnDef::new(Rating::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .col(ColumnDef::new(Rating::UpdatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .to_owned()
+        ).await?;
+
+        // A user rates an item at most once
+        manager.create_index(
+            Index::create()
+                .name("idx_rating_user_item")
+                .table(Rating::Table)
+                .col(Rating::UserId)
+                .col(Rating::ItemType)
+                .col(Rating::ItemId)
+                .unique()
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_rating_item")
+                .table(Rating::Table)
+                .col(Rating::ItemType)
+                .col(Rating::ItemId)
+                .to_owned()
+        ).await?;
+
+        // Aggregated ratings per item, the row is locked while a rating changes
+        manager.create_table(
+            Table::create()
+                .table(RatingSummary::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(RatingSummary::ItemType).string().not_null())
+                .col(ColumnDef::new(RatingSummary::ItemId).uuid().not_null())
+                .col(ColumnDef::new(RatingSummary::RatingCount).big_integer().default(0).not_null())
+                .col(ColumnDef::new(RatingSummary::RatingSum).big_integer().default(0).not_null())
+                .col(ColumnDef::new(RatingSummary::AverageRating).double())
+                .col(ColumnDef::new(RatingSummary::UpdatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .primary_key(Index::create().col(RatingSummary::ItemType).col(RatingSummary::ItemId))
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_rating_summary_average")
+                .table(RatingSummary::Table)
+                .col(RatingSummary::ItemType)
+                .col(RatingSummary::AverageRating)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(RatingSummary::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(Rating::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Rating {
+    Table,
+    Id,
+    UserId,
+    ItemType,
+    ItemId,
+    Rating,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum RatingSummary {
+    Table,
+    ItemType,
+    ItemId,
+    RatingCount,
+    RatingSum,
+    AverageRating,
+    UpdatedAt,
+}
@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create play events table, event_type is now_playing or completed
+        manager.create_table(
+            Table::create()
+                .table(PlayEvent::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(PlayEvent::Id).uuid().primary_key())
+                .col(ColumnDef::new(PlayEvent::UserId).uuid().not_null())
+                .col(ColumnDef::new(PlayEvent::SongId).uuid().not_null())
+                .col(ColumnDef::new(PlayEvent::EventType).string().not_null())
+                .col(ColumnDef::new(PlayEvent::PositionSeconds).integer())
+                // When the song was played, may be backdated by offline clients
+                .col(ColumnDef::new(PlayEvent::PlayedAt).timestamp_with_time_zone().not_null())
+                .col(ColumnDef::new(PlayEvent::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .to_owned()
+        ).await?;
+
+        // Resubmitting the same event is ignored
+        manager.create_index(
+            Index::create()
+                .name("idx_play_event_unique")
+                .table(PlayEvent::Table)
+                .col(PlayEvent::UserId)
+                .col(PlayEvent::SongId)
+                .col(PlayEvent::EventType)
+                .col(PlayEvent::PlayedAt)
+                .unique()
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_play_event_user_played_at")
+                .table(PlayEvent::Table)
+                .col(PlayEvent::UserId)
+                .col(PlayEvent::PlayedAt)
+                .to_owned()
+        ).await?;
+
+        // Per-song play counters, updated together with completed events
+        manager.create_table(
+            Table::create()
+                .table(SongPlayStat::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(SongPlayStat::SongId).uuid().primary_key())
+                .col(ColumnDef::new(SongPlayStat::PlayCount).big_integer().default(0).not_null())
+                .col(ColumnDef::new(SongPlayStat::LastPlayedAt).timestamp_with_time_zone().not_null())
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_song_play_stat_play_count")
+                .table(SongPlayStat::Table)
+                .col(SongPlayStat::PlayCount)
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_song_play_stat_last_played_at")
+                .table(SongPlayStat::Table)
+                .col(SongPlayStat::LastPlayedAt)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(SongPlayStat::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(PlayEvent::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlayEvent {
+    Table,
+    Id,
+    UserId,
+    SongId,
+    EventType,
+    PositionSeconds,
+    PlayedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum SongPlayStat {
+    Table,
+    SongId,
+    PlayCount,
+    LastPlayedAt,
+}
@@ -0,0 +1,126 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::Statement;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // IANA timezone name used for listening statistics, falls back to DEFAULT_TIMEZONE when empty
+        manager.alter_table(
+            Table::alter()
+                .table(User::Table)
+                .add_column(ColumnDef::new(User::Timezone).string().null())
+                .to_owned()
+        ).await?;
+
+        // Daily plays per song, day is the local date in the user's timezone
+        manager.create_table(
+            Table::create()
+                .table(ListeningDailySong::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(ListeningDailySong::UserId).uuid().not_null())
+                .col(ColumnDef::new(ListeningDailySong::Day).date().not_null())
+                .col(ColumnDef::new(ListeningDailySong::SongId).uuid().not_null())
+                .col(ColumnDef::new(ListeningDailySong::PlayCount).big_integer().default(0).not_null())
+                .col(ColumnDef::new(ListeningDailySong::ListenedSeconds).big_integer().default(0).not_null())
+                .primary_key(
+                    Index::create()
+                        .col(ListeningDailySong::UserId)
+                        .col(ListeningDailySong::Day)
+                        .col(ListeningDailySong::SongId)
+                )
+                .to_owned()
+        ).await?;
+
+        // Daily plays per local hour, used for totals, hour/weekday distribution and streaks
+        manager.create_table(
+            Table::create()
+                .table(ListeningDailyHour::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(ListeningDailyHour::UserId).uuid().not_null())
+                .col(ColumnDef::new(ListeningDailyHour::Day).date().not_null())
+                .col(ColumnDef::new(ListeningDailyHour::Hour).small_integer().not_null())
+                .col(ColumnDef::new(ListeningDailyHour::PlayCount).big_integer().default(0).not_null())
+                .col(ColumnDef::new(ListeningDailyHour::ListenedSeconds).big_integer().default(0).not_null())
+                .primary_key(
+                    Index::create()
+                        .col(ListeningDailyHour::UserId)
+                        .col(ListeningDailyHour::Day)
+                        .col(ListeningDailyHour::Hour)
+                )
+                .to_owned()
+        ).await?;
+
+        // Backfill from completed play events in each user's timezone, falling back to the
+        // DEFAULT_TIMEZONE the server is configured with
+        let default_timezone = std::env::var("DEFAULT_TIMEZONE").unwrap_or_else(|_| "Asia/Shanghai".to_string());
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        db.execute(Statement::from_sql_and_values(
+            backend,
+            r#"INSERT INTO "listening_daily_song" ("user_id", "day", "song_id", "play_count", "listened_seconds")
+            SELECT e."user_id", (e."played_at" AT TIME ZONE COALESCE(NULLIF(u."timezone", ''), $1))::date, e."song_id", COUNT(*),
+                SUM(COALESCE(NULLIF(e."position_seconds", 0), s."duration"))
+            FROM "play_event" e
+            JOIN "song" s ON s."id" = e."song_id"
+            JOIN "user" u ON u."id" = e."user_id"
+            WHERE e."event_type" = 'completed'
+            GROUP BY 1, 2, 3"#,
+            [default_timezone.clone().into()],
+        )).await?;
+        db.execute(Statement::from_sql_and_values(
+            backend,
+            r#"INSERT INTO "listening_daily_hour" ("user_id", "day", "hour", "play_count", "listened_seconds")
+            SELECT e."user_id", (e."played_at" AT TIME ZONE COALESCE(NULLIF(u."timezone", ''), $1))::date,
+                EXTRACT(HOUR FROM e."played_at" AT TIME ZONE COALESCE(NULLIF(u."timezone", ''), $1))::smallint, COUNT(*),
+                SUM(COALESCE(NULLIF(e."position_seconds", 0), s."duration"))
+            FROM "play_event" e
+            JOIN "song" s ON s."id" = e."song_id"
+            JOIN "user" u ON u."id" = e."user_id"
+            WHERE e."event_type" = 'completed'
+            GROUP BY 1, 2, 3"#,
+            [default_timezone.into()],
+        )).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ListeningDailyHour::Table).to_owned()).await?;
+        manager.drop_table(Table::drop().table(ListeningDailySong::Table).to_owned()).await?;
+        manager.alter_table(
+            Table::alter()
+                .table(User::Table)
+                .drop_column(User::Timezone)
+                .to_owned()
+        ).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Timezone,
+}
+
+#[derive(DeriveIden)]
+enum ListeningDailySong {
+    Table,
+    UserId,
+    Day,
+    SongId,
+    PlayCount,
+    ListenedSeconds,
+}
+
+#[derive(DeriveIden)]
+enum ListeningDailyHour {
+    Table,
+    UserId,
+    Day,
+    Hour,
+    PlayCount,
+    ListenedSeconds,
+}
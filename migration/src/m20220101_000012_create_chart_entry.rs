@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Charts scan play events of all users by time window
+        manager.create_index(
+            Index::create()
+                .name("idx_play_event_played_at")
+                .table(PlayEvent::Table)
+                .col(PlayEvent::PlayedAt)
+                .to_owned()
+        ).await?;
+
+        // Precomputed server-wide charts, replaced by the scheduled refresh.
+        // item_type is song/album/artist, period is day/week/month, genre is '' for the overall chart
+        manager.create_table(
+            Table::create()
+                .table(ChartEntry::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(ChartEntry::ItemType).string().not_null())
+                .col(ColumnDef::new(ChartEntry::Period).string().not_null())
+                .col(ColumnDef::new(ChartEntry::Genre).string().not_null())
+                .col(ColumnDef::new(ChartEntry::ItemId).uuid().not_null())
+                // Rank by plays in the current window and in the previous window of the same length
+                .col(ColumnDef::new(ChartEntry::Position).integer().not_null())
+                .col(ColumnDef::new(ChartEntry::PreviousPosition).integer())
+                // Rank by trending score in this and in the previous refresh
+                .col(ColumnDef::new(ChartEntry::TrendingPosition).integer().not_null())
+                .col(ColumnDef::new(ChartEntry::PreviousTrendingPosition).integer())
+                .col(ColumnDef::new(ChartEntry::PlayCount).big_integer().not_null())
+                .col(ColumnDef::new(ChartEntry::PreviousPlayCount).big_integer().not_null())
+                .col(ColumnDef::new(ChartEntry::ListenerCount).big_integer().not_null())
+                .col(ColumnDef::new(ChartEntry::TrendingScore).double().not_null())
+                .col(ColumnDef::new(ChartEntry::ComputedAt).timestamp_with_time_zone().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(ChartEntry::ItemType)
+                        .col(ChartEntry::Period)
+                        .col(ChartEntry::Genre)
+                        .col(ChartEntry::ItemId)
+                )
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_chart_entry_position")
+                .table(ChartEntry::Table)
+                .col(ChartEntry::ItemType)
+                .col(ChartEntry::Period)
+                .col(ChartEntry::Genre)
+                .col(ChartEntry::Position)
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_chart_entry_trending_position")
+                .table(ChartEntry::Table)
+                .col(ChartEntry::ItemType)
+                .col(ChartEntry::Period)
+                .col(ChartEntry::Genre)
+                .col(ChartEntry::TrendingPosition)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ChartEntry::Table).to_owned()).await?;
+        manager.drop_index(Index::drop().name("idx_play_event_played_at").table(PlayEvent::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlayEvent {
+    Table,
+    PlayedAt,
+}
+
+#[derive(DeriveIden)]
+enum ChartEntry {
+    Table,
+    ItemType,
+    Period,
+    Genre,
+    ItemId,
+    Position,
+    PreviousPosition,
+    TrendingPosition,
+    PreviousTrendingPosition,
+    PlayCount,
+    PreviousPlayCount,
+    ListenerCount,
+    TrendingScore,
+    ComputedAt,
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Item-item similarity model for recommendations, rebuilt by the scheduled job.
+        // item_type is album or artist, only the top-N neighbours of each item are kept
+        manager.create_table(
+            Table::create()
+                .table(ItemNeighbor::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(ItemNeighbor::ItemType).string().not_null())
+                .col(ColumnDef::new(ItemNeighbor::ItemId).uuid().not_null())
+                .col(ColumnDef::new(ItemNeighbor::NeighborId).uuid().not_null())
+                .col(ColumnDef::new(ItemNeighbor::Score).double().not_null())
+                .col(ColumnDef::new(ItemNeighbor::Rank).integer().not_null())
+                .col(ColumnDef::new(ItemNeighbor::ComputedAt).timestamp_with_time_zone().not_null())
+                .primary_key(
+                    Index::create()
+                        .col(ItemNeighbor::ItemType)
+                        .col(ItemNeighbor::ItemId)
+                        .col(ItemNeighbor::NeighborId)
+                )
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_item_neighbor_rank")
+                .table(ItemNeighbor::Table)
+                .col(ItemNeighbor::ItemType)
+                .col(ItemNeighbor::ItemId)
+                .col(ItemNeighbor::Rank)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(ItemNeighbor::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ItemNeighbor {
+    Table,
+    ItemType,
+    ItemId,
+    NeighborId,
+    Score,
+    Rank,
+    ComputedAt,
+}
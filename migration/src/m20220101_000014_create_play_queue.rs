@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Current play queue per user, shared between the user's devices.
+        // version is incremented on every save, clients send the version they based their queue on
+        manager.create_table(
+            Table::create()
+                .table(PlayQueue::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(PlayQueue::UserId).uuid().primary_key())
+                // Ordered array of song ids
+                .col(ColumnDef::new(PlayQueue::SongIds).json_binary().not_null())
+                .col(ColumnDef::new(PlayQueue::CurrentIndex).integer().default(0).not_null())
+                .col(ColumnDef::new(PlayQueue::PositionMs).big_integer().default(0).not_null())
+                .col(ColumnDef::new(PlayQueue::ClientName).string().null())
+                .col(ColumnDef::new(PlayQueue::Version).big_integer().default(1).not_null())
+                .col(ColumnDef::new(PlayQueue::UpdatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .to_owned()
+        ).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(PlayQueue::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlayQueue {
+    Table,
+    UserId,
+    SongIds,
+    CurrentIndex,
+    PositionMs,
+    ClientName,
+    Version,
+    UpdatedAt,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Previous audio files of a song, archived when the file is replaced (e.g. with a remaster).
+        // Restoring a version swaps it with the song's current file
+        manager.create_table(
+            Table::create()
+                .table(SongVersion::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(SongVersion::Id).uuid().primary_key())
+                .col(ColumnDef::new(SongVersion::SongId).uuid().not_null())
+                // Increasing per song, the latest archived file has the highest version
+                .col(ColumnDef::new(SongVersion::Version).integer().not_null())
+                .col(ColumnDef::new(SongVersion::FilePath).string().not_null())
+                .col(ColumnDef::new(SongVersion::FileHash).string().null())
+                .col(ColumnDef::new(SongVersion::FileSize).big_integer().null())
+                .col(ColumnDef::new(SongVersion::FileModifiedAt).timestamp_with_time_zone().null())
+                .col(ColumnDef::new(SongVersion::Duration).integer().not_null())
+                .col(ColumnDef::new(SongVersion::Quality).string().not_null())
+                .col(ColumnDef::new(SongVersion::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .col(ColumnDef::new(SongVersion::CreatedBy).string().not_null())
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_song_version_song")
+                .table(SongVersion::Table)
+                .col(SongVersion::SongId)
+                .col(SongVersion::Version)
+                .unique()
+                .to_owned()
+        ).await?;
+
+        // The library scan skips archived files
+        manager.create_index(
+            Index::create()
+                .name("idx_song_version_file_path")
+                .table(SongVersion::Table)
+                .col(SongVersion::FilePath)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(SongVersion::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SongVersion {
+    Table,
+    Id,
+    SongId,
+    Version,
+    FilePath,
+    FileHash,
+    FileSize,
+    FileModifiedAt,
+    Duration,
+    Quality,
+    CreatedAt,
+    CreatedBy,
+}
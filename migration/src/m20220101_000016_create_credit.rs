@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Additional artists credited on a song or an album (featuring, composer, producer, ...).
+        // The primary artist stays in song.artist_id / album.artist_id
+        manager.create_table(
+            Table::create()
+                .table(Credit::Table)
+                .if_not_exists()
+                .col(ColumnDef::new(Credit::Id).uuid().primary_key())
+                // song or album
+                .col(ColumnDef::new(Credit::ItemType).string().not_null())
+                .col(ColumnDef::new(Credit::ItemId).uuid().not_null())
+                .col(ColumnDef::new(Credit::ArtistId).uuid().not_null())
+                .col(ColumnDef::new(Credit::Role).string().not_null())
+                // Display order within the item's credits, starting at 0
+                .col(ColumnDef::new(Credit::Position).integer().not_null())
+                .col(ColumnDef::new(Credit::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()).not_null())
+                .col(ColumnDef::new(Credit::CreatedBy).string().not_null())
+                .to_owned()
+        ).await?;
+
+        manager.create_index(
+            Index::create()
+                .name("idx_credit_item_artist_role")
+                .table(Credit::Table)
+                .col(Credit::ItemType)
+                .col(Credit::ItemId)
+                .col(Credit::ArtistId)
+                .col(Credit::Role)
+                .unique()
+                .to_owned()
+        ).await?;
+
+        // Song and album lists filtered by a credited artist
+        manager.create_index(
+            Index::create()
+                .name("idx_credit_artist")
+                .table(Credit::Table)
+                .col(Credit::ArtistId)
+                .col(Credit::ItemType)
+                .col(Credit::Role)
+                .to_owned()
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Credit::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Credit {
+    Table,
+    Id,
+    ItemType,
+    ItemId,
+    ArtistId,
+    Role,
+    Position,
+    CreatedAt,
+    CreatedBy,
+}
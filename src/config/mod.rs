@@ -5,16 +5,72 @@ use crate::models::artist::{ArtistRepository, SeaOrmArtistRepository};
 use crate::models::song::{SongRepository, SeaOrmSongRepository};
 use crate::models::album::{AlbumRepository, SeaOrmAlbumRepository};
 use crate::models::user::{UserRepository, SeaOrmUserRepository};
+use crate::models::playlist::{PlaylistRepository, SeaOrmPlaylistRepository};
+use crate::models::star::{StarRepository, SeaOrmStarRepository};
+use crate::models::rating::{RatingRepository, SeaOrmRatingRepository};
+use crate::models::play_event::{PlayEventRepository, SeaOrmPlayEventRepository};
+use crate::models::listening_stat::{ListeningStatRepository, SeaOrmListeningStatRepository};
+use crate::models::chart_entry::{ChartRepository, SeaOrmChartRepository};
+use crate::models::similarity::{SimilarityRepository, SeaOrmSimilarityRepository};
+use crate::models::item_neighbor::{RecommendationRepository, SeaOrmRecommendationRepository};
+use crate::models::play_queue::{PlayQueueRepository, SeaOrmPlayQueueRepository};
+use crate::models::song_version::{SongVersionRepository, SeaOrmSongVersionRepository};
+use crate::models::trash::{TrashRepository, SeaOrmTrashRepository};
+use crate::models::credit::{CreditRepository, SeaOrmCreditRepository};
+use crate::services::library::ScanStatus;
+use crate::services::transcode::Transcoder;
+use crate::services::transcode::cache::TranscodeCache;
 
 #[derive(Clone)]
 pub struct AppConfig {
     pub db: DatabaseConnection,
     pub jwt_secret: String,
     pub port: String,
+    // 媒体文件根目录
+    pub media_root: String,
+    // 上传文件大小上限(字节)
+    pub max_upload_size: u64,
     pub artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
     pub song_repo: Arc<dyn SongRepository + Send + Sync>,
     pub album_repo: Arc<dyn AlbumRepository + Send + Sync>,
     pub user_repo: Arc<dyn UserRepository + Send + Sync>,
+    pub playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+    pub star_repo: Arc<dyn StarRepository + Send + Sync>,
+    pub rating_repo: Arc<dyn RatingRepository + Send + Sync>,
+    pub play_event_repo: Arc<dyn PlayEventRepository + Send + Sync>,
+    pub listening_stat_repo: Arc<dyn ListeningStatRepository + Send + Sync>,
+    pub chart_repo: Arc<dyn ChartRepository + Send + Sync>,
+    pub similarity_repo: Arc<dyn SimilarityRepository + Send + Sync>,
+    pub recommendation_repo: Arc<dyn RecommendationRepository + Send + Sync>,
+    pub play_queue_repo: Arc<dyn PlayQueueRepository + Send + Sync>,
+    pub song_version_repo: Arc<dyn SongVersionRepository + Send + Sync>,
+    pub trash_repo: Arc<dyn TrashRepository + Send + Sync>,
+    pub credit_repo: Arc<dyn CreditRepository + Send + Sync>,
+    // 用户未设置时区时，听歌统计使用的默认IANA时区
+    pub default_timezone: String,
+    // 排行榜刷新间隔(分钟)
+    pub chart_refresh_minutes: u64,
+    // 推荐模型重建间隔(分钟)
+    pub recommendation_refresh_minutes: u64,
+    // 回收站保留天数，超过后永久删除(为0时不自动清理)
+    pub trash_retention_days: u64,
+    // 回收站清理间隔(分钟)
+    pub trash_purge_minutes: u64,
+    // 媒体库扫描状态
+    pub scan_status: ScanStatus,
+    // 媒体库写入锁（全量扫描与目录监听互斥）
+    pub library_lock: Arc<tokio::sync::Mutex<()>>,
+    // 是否监听媒体目录变化
+    pub watch_media_root: bool,
+    // 目录监听防抖时间(毫秒)
+    pub watch_debounce_ms: u64,
+    // 外部编码器路径(ffmpeg兼容)
+    pub transcoder_path: String,
+    // 同时进行的转码任务上限
+    pub max_concurrent_transcodes: usize,
+    pub transcoder: Transcoder,
+    // 转码结果磁盘缓存
+    pub transcode_cache: TranscodeCache,
 }
 
 impl AppConfig {
@@ -25,6 +81,47 @@ impl AppConfig {
         let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
         let port = env::var("PORT").unwrap_or_else(|_| "8080".to_string());
         println!("Using port: {}", port);
+        let media_root = canonical_media_root(&env::var("MEDIA_ROOT").unwrap_or_else(|_| "./media".to_string())).await;
+        let max_upload_size = env::var("MAX_UPLOAD_SIZE")
+            .ok()
+            .and_then(|size| size.parse::<u64>().ok())
+            .unwrap_or(200 * 1024 * 1024);
+        let watch_media_root = env::var("WATCH_MEDIA_ROOT")
+            .map(|value| value == "true" || value == "1")
+            .unwrap_or(false);
+        let watch_debounce_ms = env::var("WATCH_DEBOUNCE_MS")
+            .ok()
+            .and_then(|ms| ms.parse::<u64>().ok())
+            .unwrap_or(2000);
+        let transcoder_path = env::var("TRANSCODER_PATH").unwrap_or_else(|_| "ffmpeg".to_string());
+        let max_concurrent_transcodes = env::var("MAX_CONCURRENT_TRANSCODES")
+            .ok()
+            .and_then(|count| count.parse::<usize>().ok())
+            .unwrap_or(4);
+        let transcode_cache_dir = env::var("TRANSCODE_CACHE_DIR").unwrap_or_else(|_| "./cache/transcode".to_string());
+        let transcode_cache_size = env::var("TRANSCODE_CACHE_SIZE")
+            .ok()
+            .and_then(|size| size.parse::<u64>().ok())
+            .unwrap_or(2 * 1024 * 1024 * 1024);
+        let transcode_cache = TranscodeCache::open(&transcode_cache_dir, transcode_cache_size)
+            .expect("Failed to open transcode cache directory");
+        let default_timezone = env::var("DEFAULT_TIMEZONE").unwrap_or_else(|_| "Asia/Shanghai".to_string());
+        let chart_refresh_minutes = env::var("CHART_REFRESH_MINUTES")
+            .ok()
+            .and_then(|minutes| minutes.parse::<u64>().ok())
+            .unwrap_or(60);
+        let recommendation_refresh_minutes = env::var("RECOMMENDATION_REFRESH_MINUTES")
+            .ok()
+            .and_then(|minutes| minutes.parse::<u64>().ok())
+            .unwrap_or(360);
+        let trash_retention_days = env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|days| days.parse::<u64>().ok())
+            .unwrap_or(30);
+        let trash_purge_minutes = env::var("TRASH_PURGE_MINUTES")
+            .ok()
+            .and_then(|minutes| minutes.parse::<u64>().ok())
+            .unwrap_or(60);
         
         
         
@@ -57,15 +154,80 @@ impl AppConfig {
         let album_repo = Arc::new(SeaOrmAlbumRepository::new(Arc::new(db.clone())));
         // 创建用户仓库实例
         let user_repo = Arc::new(SeaOrmUserRepository::new(Arc::new(db.clone())));
+        // 创建歌单仓库实例
+        let playlist_repo = Arc::new(SeaOrmPlaylistRepository::new(Arc::new(db.clone())));
+        // 创建收藏仓库实例
+        let star_repo = Arc::new(SeaOrmStarRepository::new(Arc::new(db.clone())));
+        // 创建评分仓库实例
+        let rating_repo = Arc::new(SeaOrmRatingRepository::new(Arc::new(db.clone())));
+        // 创建播放事件仓库实例
+        let play_event_repo = Arc::new(SeaOrmPlayEventRepository::new(Arc::new(db.clone())));
+        // 创建听歌统计仓库实例
+        let listening_stat_repo = Arc::new(SeaOrmListeningStatRepository::new(Arc::new(db.clone())));
+        // 创建排行榜仓库实例
+        let chart_repo = Arc::new(SeaOrmChartRepository::new(Arc::new(db.clone())));
+        // 创建相似歌曲仓库实例
+        let similarity_repo = Arc::new(SeaOrmSimilarityRepository::new(Arc::new(db.clone())));
+        // 创建推荐仓库实例
+        let recommendation_repo = Arc::new(SeaOrmRecommendationRepository::new(Arc::new(db.clone())));
+        // 创建播放队列仓库实例
+        let play_queue_repo = Arc::new(SeaOrmPlayQueueRepository::new(Arc::new(db.clone())));
+        // 创建歌曲版本仓库实例
+        let song_version_repo = Arc::new(SeaOrmSongVersionRepository::new(Arc::new(db.clone())));
+        // 创建回收站仓库实例
+        let trash_repo = Arc::new(SeaOrmTrashRepository::new(Arc::new(db.clone())));
+        // 创建署名仓库实例
+        let credit_repo = Arc::new(SeaOrmCreditRepository::new(Arc::new(db.clone())));
 
         AppConfig {
             db,
             jwt_secret,
             port,
+            media_root,
+            max_upload_size,
             artist_repo,
             song_repo,
             album_repo,
             user_repo,
+            playlist_repo,
+            star_repo,
+            rating_repo,
+            play_event_repo,
+            listening_stat_repo,
+            chart_repo,
+            similarity_repo,
+            recommendation_repo,
+            play_queue_repo,
+            song_version_repo,
+            trash_repo,
+            credit_repo,
+            default_timezone,
+            chart_refresh_minutes,
+            recommendation_refresh_minutes,
+            trash_retention_days,
+            trash_purge_minutes,
+            scan_status: ScanStatus::default(),
+            library_lock: Arc::new(tokio::sync::Mutex::new(())),
+            watch_media_root,
+            watch_debounce_ms,
+            transcoder: Transcoder::new(transcoder_path.clone(), max_concurrent_transcodes),
+            transcoder_path,
+            max_concurrent_transcodes,
+            transcode_cache,
         }
     }
-}
\ No newline at end of file
+}
+
+/// 规范化媒体根目录（不存在时先创建），使上传、扫描和文件监听得到的路径都是同一绝对路径
+pub(crate) async fn canonical_media_root(media_root: &str) -> String {
+    if let Err(e) = tokio::fs::create_dir_all(media_root).await {
+        log::warn!("Failed to create media root {}: {}", media_root, e);
+    }
+    match tokio::fs::canonicalize(media_root).await {
+        Ok(path) => path.to_string_lossy().to_string(),
+        Err(e) => {
+            log::warn!("Failed to canonicalize media root {}: {}", media_root, e);
+            media_root.to_string()
+        }
+    }
+}
@@ -1,25 +1,32 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
 // 移除SQLx引用
-use super::super::{models, AppState};
-use crate::services::{self, albums::model::AlbumQueryViewObject};
-use crate::handlers::ApiResponse;
+use super::super::AppState;
+use serde::Serialize;
+use uuid::Uuid;
+use crate::services::{self, albums::{self, AlbumServiceError}, albums::model::{AlbumQueryViewObject, DeleteAlbumQueryViewObject, MoveSongViewObject, ReorderTracksViewObject, UpdateAlbumViewObject}};
+use crate::handlers::{ApiResponse, current_user_id};
 
-// 获取专辑列表（支持按歌手、名称和发行日期筛选）
+// 获取专辑列表（支持按歌手、名称、发行日期和评分筛选）
 pub async fn get_albums(
     query: web::Query<AlbumQueryViewObject>,
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<impl Responder, actix_web::Error> {
     // 将ViewObject转换为DataObject
     let data_object = AlbumQueryViewObject {
         id: query.id,
         artist_id: query.artist_id,
+        artist_role: query.artist_role,
         name: query.name.clone(),
         release_year: query.release_year,
-        page: query.page.map(|p| p as u32),
-        page_size: query.page_size.map(|l| l as u32),
+        min_rating: query.min_rating,
+        sort: query.sort,
+        page: query.page,
+        page_size: query.page_size,
     };
 
-    let albums = services::albums::get_albums_service(data_object, state.config.album_repo.clone())
+    let user_id = current_user_id(&req).unwrap_or_default();
+    let albums = services::albums::get_albums_service(data_object, user_id, state.config.album_repo.clone(), state.config.rating_repo.clone())
         .await
         .map_err(|e| {
             log::error!("Service error: {:?}", e);
@@ -41,10 +48,12 @@ pub async fn get_albums(
 pub async fn get_album_by_id(
     album_id: web::Path<uuid::Uuid>,
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<impl Responder, actix_web::Error> {
     let album_id = album_id.into_inner();
+    let user_id = current_user_id(&req).unwrap_or_default();
 
-    let album = services::albums::get_album_by_id_service(album_id, state.config.album_repo.clone())
+    let album = services::albums::get_album_by_id_service(album_id, user_id, state.config.album_repo.clone(), state.config.star_repo.clone(), state.config.rating_repo.clone(), state.config.artist_repo.clone(), state.config.credit_repo.clone())
         .await
         .map_err(|e| {
             log::error!("Service error: {:?}", e);
@@ -70,11 +79,13 @@ pub async fn get_album_by_id(
 pub async fn create_album(
     data: web::Json<services::albums::model::CreateAlbumViewObject>,
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<impl Responder, actix_web::Error> {
     // 将ViewObject转换为DataObject
     let data_object = data.into_inner();
+    let created_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
 
-    let album = services::albums::create_album_service(data_object, state.config.album_repo.clone(), state.config.artist_repo.clone())
+    let album = services::albums::create_album_service(data_object, created_by, &state.config.media_root, state.config.album_repo.clone(), state.config.artist_repo.clone())
         .await
         .map_err(|e| {
             log::error!("Service error: {:?}", e);
@@ -82,6 +93,13 @@ pub async fn create_album(
                 "Artist_ not found" => e.to_string(),
                 _ => "Failed to create album".to_string(),
             };
+            if let AlbumServiceError::InvalidCoverImage(_) = e {
+                return actix_web::error::ErrorBadRequest(ApiResponse::<()> {
+                    success: false,
+                    data: None,
+                    message: Some(e.to_string()),
+                });
+            }
             actix_web::error::ErrorInternalServerError(ApiResponse::<()> {
                 success: false,
                 data: None,
@@ -94,4 +112,102 @@ pub async fn create_album(
         data: Some(album),
         message: Some("Album created successfully".to_string()),
     }))
-}
\ No newline at end of file
+}
+
+// 替换专辑信息
+pub async fn replace_album(
+    album_id: web::Path<Uuid>,
+    data: web::Json<services::albums::model::CreateAlbumViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = albums::replace_album_service(album_id.into_inner(), data.into_inner(), updated_by, &state.config.media_root, state.config.album_repo.clone(), state.config.artist_repo.clone()).await;
+    album_response(result, "Album updated successfully")
+}
+
+// 部分更新专辑信息
+pub async fn update_album(
+    album_id: web::Path<Uuid>,
+    data: web::Json<UpdateAlbumViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = albums::update_album_service(album_id.into_inner(), data.into_inner(), updated_by, &state.config.media_root, state.config.album_repo.clone(), state.config.artist_repo.clone()).await;
+    album_response(result, "Album updated successfully")
+}
+
+// 删除专辑（软删除），?songs=delete|detach指定专辑中歌曲的处理方式
+pub async fn delete_album(
+    album_id: web::Path<Uuid>,
+    query: web::Query<DeleteAlbumQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let deleted_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = albums::delete_album_service(album_id.into_inner(), query.songs, deleted_by, state.config.album_repo.clone(), state.config.song_repo.clone()).await;
+    album_response(result, "Album deleted successfully")
+}
+
+// 获取专辑曲目表
+pub async fn get_tracklist(
+    album_id: web::Path<Uuid>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let result = albums::get_tracklist_service(album_id.into_inner(), state.config.album_repo.clone(), state.config.song_repo.clone()).await;
+    album_response(result, "Tracklist fetched successfully")
+}
+
+// 重排专辑曲目
+pub async fn reorder_tracks(
+    album_id: web::Path<Uuid>,
+    data: web::Json<ReorderTracksViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = albums::reorder_tracks_service(album_id.into_inner(), data.into_inner(), updated_by, state.config.album_repo.clone(), state.config.song_repo.clone()).await;
+    album_response(result, "Tracks reordered successfully")
+}
+
+// 把歌曲移动到另一张专辑
+pub async fn move_song(
+    song_id: web::Path<Uuid>,
+    data: web::Json<MoveSongViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = albums::move_song_service(song_id.into_inner(), data.into_inner(), updated_by, state.config.album_repo.clone(), state.config.song_repo.clone()).await;
+    album_response(result, "Song moved successfully")
+}
+
+fn album_response<T: Serialize>(result: Result<T, AlbumServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                AlbumServiceError::AlbumNotFound
+                | AlbumServiceError::ArtistNotFound
+                | AlbumServiceError::SongNotFound(_) => (HttpResponse::NotFound(), e.to_string()),
+                AlbumServiceError::InvalidName
+                | AlbumServiceError::InvalidTracklist(_)
+                | AlbumServiceError::InvalidPosition
+                | AlbumServiceError::InvalidCoverImage(_) => (HttpResponse::BadRequest(), e.to_string()),
+                AlbumServiceError::AlbumHasSongs(_) => (HttpResponse::Conflict(), e.to_string()),
+                AlbumServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process album".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
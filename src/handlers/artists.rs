@@ -1,7 +1,9 @@
 use actix_web::{web, HttpResponse, Responder, HttpRequest, HttpMessage};
 use super::super::{models, AppState, services};
-use crate::services::artists::model::ArtistQueryViewObject;
-use crate::handlers::ApiResponse;
+use serde::Serialize;
+use crate::services::artists::ArtistServiceError;
+use crate::services::artists::model::{ArtistQueryViewObject, DeleteArtistQueryViewObject, UpdateArtistViewObject};
+use crate::handlers::{ApiResponse, current_user_id};
 
 // 获取歌手列表
 pub async fn get_artists(
@@ -40,8 +42,15 @@ pub async fn get_artists(
 pub async fn get_artist_by_id(
     artist_id: web::Path<uuid::Uuid>,
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<impl Responder, actix_web::Error> {
-    let artist = services::artists::get_artist_by_id_service(artist_id.into_inner(), state.config.artist_repo.clone())
+    let user_id = current_user_id(&req).unwrap_or_default();
+    let artist = services::artists::get_artist_by_id_service(
+        artist_id.into_inner(),
+        user_id,
+        state.config.artist_repo.clone(),
+        state.config.star_repo.clone(),
+    )
         .await
         .map_err(|e| {
             log::error!("Service error: {:?}", e);
@@ -83,10 +92,17 @@ pub async fn create_artist(
         created_by: req.extensions_mut().get::<String>().cloned().unwrap_or("system".to_string()),
     };
 
-    let artist = services::artists::create_artist_service(data_object, state.config.artist_repo.clone())
+    let artist = services::artists::create_artist_service(data_object, &state.config.media_root, state.config.artist_repo.clone())
         .await
         .map_err(|e| {
             log::error!("Service error: {:?}", e);
+            if let ArtistServiceError::InvalidAvatar(_) = e {
+                return actix_web::error::ErrorBadRequest(ApiResponse::<()> {
+                    success: false,
+                    data: None,
+                    message: Some(e.to_string()),
+                });
+            }
             actix_web::error::ErrorInternalServerError(ApiResponse::<()> {
                 success: false,
                 data: None,
@@ -99,4 +115,67 @@ pub async fn create_artist(
         data: Some(artist),
         message: Some("Artist created successfully".to_string()),
     }))
-}
\ No newline at end of file
+}
+
+// 替换歌手信息
+pub async fn replace_artist(
+    artist_id: web::Path<uuid::Uuid>,
+    data: web::Json<services::artists::model::CreateArtistViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = services::artists::replace_artist_service(artist_id.into_inner(), data.into_inner(), updated_by, &state.config.media_root, state.config.artist_repo.clone()).await;
+    artist_response(result, "Artist updated successfully")
+}
+
+// 部分更新歌手信息
+pub async fn update_artist(
+    artist_id: web::Path<uuid::Uuid>,
+    data: web::Json<UpdateArtistViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = services::artists::update_artist_service(artist_id.into_inner(), data.into_inner(), updated_by, &state.config.media_root, state.config.artist_repo.clone()).await;
+    artist_response(result, "Artist updated successfully")
+}
+
+// 删除歌手（软删除），?cascade=true时同时删除其专辑和歌曲
+pub async fn delete_artist(
+    artist_id: web::Path<uuid::Uuid>,
+    query: web::Query<DeleteArtistQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let deleted_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let cascade = query.cascade.unwrap_or(false);
+    let result = services::artists::delete_artist_service(artist_id.into_inner(), cascade, deleted_by, state.config.artist_repo.clone()).await;
+    artist_response(result, "Artist deleted successfully")
+}
+
+fn artist_response<T: Serialize>(result: Result<T, ArtistServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                ArtistServiceError::ArtistNotFound => (HttpResponse::NotFound(), e.to_string()),
+                ArtistServiceError::InvalidName
+                | ArtistServiceError::InvalidBirthDate(_)
+                | ArtistServiceError::InvalidAvatar(_) => (HttpResponse::BadRequest(), e.to_string()),
+                ArtistServiceError::ArtistInUse { .. } => (HttpResponse::Conflict(), e.to_string()),
+                ArtistServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process artist".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::Serialize;
+
+use crate::AppState;
+use crate::handlers::ApiResponse;
+use crate::services::charts::{self, ChartServiceError, model::ChartQueryViewObject};
+
+// 获取歌曲、专辑或歌手的热门榜或趋势榜
+pub async fn get_chart(
+    path: web::Path<(String, String)>,
+    query: web::Query<ChartQueryViewObject>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let (kind, chart) = path.into_inner();
+    let result = charts::get_chart_service(&kind, &chart, query.into_inner(), &state.config).await;
+    chart_response(result, "Chart fetched successfully")
+}
+
+fn chart_response<T: Serialize>(result: Result<T, ChartServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                ChartServiceError::InvalidItemType(_) | ChartServiceError::InvalidChart(_) => (HttpResponse::NotFound(), e.to_string()),
+                ChartServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to fetch chart".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
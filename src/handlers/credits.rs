@@ -0,0 +1,56 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::ApiResponse;
+use crate::services::credits::{self, CreditServiceError, model::SetCreditsViewObject};
+
+// 获取歌曲或专辑的署名
+pub async fn get_credits(
+    path: web::Path<(String, Uuid)>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let (kind, item_id) = path.into_inner();
+    let result = credits::get_credits_service(&kind, item_id, &state.config).await;
+    credit_response(result, "Credits fetched successfully")
+}
+
+// 设置歌曲或专辑的署名（整体替换）
+pub async fn set_credits(
+    req: HttpRequest,
+    path: web::Path<(String, Uuid)>,
+    data: web::Json<SetCreditsViewObject>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let created_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let (kind, item_id) = path.into_inner();
+    let result = credits::set_credits_service(&kind, item_id, data.into_inner(), &created_by, &state.config).await;
+    credit_response(result, "Credits updated successfully")
+}
+
+fn credit_response<T: Serialize>(result: Result<T, CreditServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                CreditServiceError::ItemNotFound(_) => (HttpResponse::NotFound(), e.to_string()),
+                CreditServiceError::InvalidItemType(_)
+                | CreditServiceError::ArtistNotFound(_)
+                | CreditServiceError::DuplicateCredit(_, _)
+                | CreditServiceError::TooManyCredits => (HttpResponse::BadRequest(), e.to_string()),
+                CreditServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process credits".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
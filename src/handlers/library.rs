@@ -0,0 +1,36 @@
+use actix_web::{web, HttpResponse, Responder};
+use crate::{services, AppState};
+use crate::services::library::LibraryServiceError;
+use crate::handlers::ApiResponse;
+
+// 启动媒体库扫描（后台执行）
+pub async fn start_library_scan(state: web::Data<AppState>) -> impl Responder {
+    match services::library::start_scan_service(&state.config) {
+        Ok(status) => HttpResponse::Accepted().json(ApiResponse {
+            success: true,
+            data: Some(status),
+            message: Some("Library scan started".to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                LibraryServiceError::ScanAlreadyRunning => (HttpResponse::Conflict(), e.to_string()),
+                _ => (HttpResponse::InternalServerError(), "Failed to start library scan".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
+
+// 获取媒体库扫描进度
+pub async fn get_library_scan_status(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(services::library::get_scan_status_service(&state.config)),
+        message: None,
+    })
+}
@@ -1,9 +1,26 @@
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
 use serde::Serialize;
+use uuid::Uuid;
 
 pub mod albums;
 pub mod auth;
 pub mod artists;
+pub mod charts;
+pub mod credits;
+pub mod library;
+pub mod playlists;
+pub mod plays;
+pub mod play_queue;
+pub mod radio;
+pub mod ratings;
+pub mod recommendations;
 pub mod songs;
+pub mod stars;
+pub mod stats;
+pub mod stream;
+pub mod subsonic;
+pub mod transcode;
+pub mod trash;
 pub mod users;
 
 #[derive(Debug, Serialize)]
@@ -17,4 +34,18 @@ impl<T: Serialize> std::fmt::Display for ApiResponse<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{:?}",  self.message.clone().expect("No message").to_string())
     }
-}
\ No newline at end of file
+}
+
+/// 从认证中间件写入的JWT sub中获取当前用户ID
+pub fn current_user_id(req: &HttpRequest) -> Option<Uuid> {
+    req.extensions().get::<String>().and_then(|sub| Uuid::parse_str(sub).ok())
+}
+
+/// 当前请求没有有效用户时的401响应
+pub fn unauthorized_response() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::<()> {
+        success: false,
+        data: None,
+        message: Some("Auth fail, Please relogin".to_string()),
+    })
+}
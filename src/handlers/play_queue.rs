@@ -0,0 +1,63 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::play_queue::{self, PlayQueueServiceError, model::SavePlayQueueViewObject};
+
+// 获取当前用户的播放队列
+pub async fn get_play_queue(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = play_queue::get_play_queue_service(user_id, &state.config).await;
+    play_queue_response(result, "Play queue fetched successfully")
+}
+
+// 保存当前用户的播放队列
+pub async fn save_play_queue(
+    data: web::Json<SavePlayQueueViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = play_queue::save_play_queue_service(user_id, data.into_inner(), &state.config).await;
+    play_queue_response(result, "Play queue saved successfully")
+}
+
+fn play_queue_response<T: Serialize>(result: Result<T, PlayQueueServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        // 冲突时返回服务端的当前队列，客户端可据此合并后重新保存
+        Err(PlayQueueServiceError::VersionConflict(queue)) => HttpResponse::Conflict().json(ApiResponse {
+            success: false,
+            message: Some(PlayQueueServiceError::VersionConflict(queue.clone()).to_string()),
+            data: Some(queue),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                PlayQueueServiceError::TooManySongs
+                | PlayQueueServiceError::InvalidCurrentIndex(_)
+                | PlayQueueServiceError::InvalidPosition
+                | PlayQueueServiceError::SongNotFound(_) => (HttpResponse::BadRequest(), e.to_string()),
+                PlayQueueServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process play queue".to_string()),
+                PlayQueueServiceError::VersionConflict(_) => (HttpResponse::Conflict(), e.to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use actix_web::http::header::{Charset, ContentDisposition, DispositionParam, DispositionType, ExtendedValue};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::playlists::{self, PlaylistServiceError, model::*, transfer};
+
+// 获取歌单列表（自己的歌单和其他用户的公开歌单）
+pub async fn get_playlists(
+    query: web::Query<PlaylistQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = playlists::get_playlists_service(query.into_inner(), user_id, state.config.playlist_repo.clone()).await;
+    playlist_response(result, "Playlists fetched successfully")
+}
+
+// 根据ID获取歌单详情
+pub async fn get_playlist_by_id(
+    playlist_id: web::Path<Uuid>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = playlists::get_playlist_service(
+        playlist_id.into_inner(),
+        user_id,
+        state.config.playlist_repo.clone(),
+        state.config.song_repo.clone(),
+    ).await;
+    playlist_response(result, "Playlist fetched successfully")
+}
+
+// 创建歌单
+pub async fn create_playlist(
+    data: web::Json<CreatePlaylistViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    match playlists::create_playlist_service(data.into_inner(), user_id, state.config.playlist_repo.clone()).await {
+        Ok(playlist) => HttpResponse::Created().json(ApiResponse {
+            success: true,
+            data: Some(playlist),
+            message: Some("Playlist created successfully".to_string()),
+        }),
+        Err(e) => playlist_error_response(e),
+    }
+}
+
+// 更新歌单（仅所有者）
+pub async fn update_playlist(
+    playlist_id: web::Path<Uuid>,
+    data: web::Json<UpdatePlaylistViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = playlists::update_playlist_service(playlist_id.into_inner(), data.into_inner(), user_id, state.config.playlist_repo.clone()).await;
+    playlist_response(result, "Playlist updated successfully")
+}
+
+// 删除歌单（仅所有者）
+pub async fn delete_playlist(
+    playlist_id: web::Path<Uuid>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = playlists::delete_playlist_service(playlist_id.into_inner(), user_id, state.config.playlist_repo.clone()).await;
+    playlist_response(result, "Playlist deleted successfully")
+}
+
+// 向歌单添加歌曲
+pub async fn add_playlist_entries(
+    playlist_id: web::Path<Uuid>,
+    data: web::Json<AddPlaylistEntriesViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = playlists::add_playlist_entries_service(
+        playlist_id.into_inner(),
+        data.into_inner(),
+        user_id,
+        state.config.playlist_repo.clone(),
+        state.config.song_repo.clone(),
+    ).await;
+    playlist_response(result, "Songs added to playlist")
+}
+
+// 调整歌单条目顺序
+pub async fn reorder_playlist(
+    playlist_id: web::Path<Uuid>,
+    data: web::Json<ReorderPlaylistViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = playlists::reorder_playlist_service(playlist_id.into_inner(), data.into_inner(), user_id, state.config.playlist_repo.clone()).await;
+    playlist_response(result, "Playlist reordered successfully")
+}
+
+// 从歌单移除条目
+pub async fn remove_playlist_entry(
+    path: web::Path<(Uuid, Uuid)>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let (playlist_id, entry_id) = path.into_inner();
+    let result = playlists::remove_playlist_entry_service(playlist_id, entry_id, user_id, state.config.playlist_repo.clone()).await;
+    playlist_response(result, "Entry removed from playlist")
+}
+
+// 导出歌单文件（m3u8、pls、xspf）
+pub async fn export_playlist(
+    playlist_id: web::Path<Uuid>,
+    query: web::Query<PlaylistExportQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    match transfer::export_playlist_service(playlist_id.into_inner(), query.into_inner(), user_id, &state.config).await {
+        Ok(export) => {
+            let disposition = ContentDisposition {
+                disposition: DispositionType::Attachment,
+                parameters: vec![DispositionParam::FilenameExt(ExtendedValue {
+                    charset: Charset::Ext("UTF-8".to_string()),
+                    language_tag: None,
+                    value: export.file_name.into_bytes(),
+                })],
+            };
+            HttpResponse::Ok()
+                .content_type(export.content_type)
+                .insert_header(disposition)
+                .body(export.body)
+        }
+        Err(e) => playlist_error_response(e),
+    }
+}
+
+// 导入歌单文件，请求体为文件内容，返回匹配报告
+pub async fn import_playlist(
+    query: web::Query<PlaylistImportQueryViewObject>,
+    body: web::Bytes,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let content = String::from_utf8_lossy(&body);
+    match transfer::import_playlist_service(query.into_inner(), &content, user_id, &state.config).await {
+        Ok(report) => HttpResponse::Created().json(ApiResponse {
+            success: true,
+            data: Some(report),
+            message: Some("Playlist imported".to_string()),
+        }),
+        Err(e) => playlist_error_response(e),
+    }
+}
+
+fn playlist_response<T: Serialize>(result: Result<T, PlaylistServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => playlist_error_response(e),
+    }
+}
+
+fn playlist_error_response(e: PlaylistServiceError) -> HttpResponse {
+    log::error!("Service error: {:?}", e);
+    let (mut response, message) = match e {
+        PlaylistServiceError::PlaylistNotFound | PlaylistServiceError::EntryNotFound => (HttpResponse::NotFound(), e.to_string()),
+        PlaylistServiceError::PermissionDenied => (HttpResponse::Forbidden(), e.to_string()),
+        PlaylistServiceError::SongNotFound(_)
+        | PlaylistServiceError::InvalidName
+        | PlaylistServiceError::InvalidOrder
+        | PlaylistServiceError::InvalidRules(_)
+        | PlaylistServiceError::NotSmartPlaylist => (HttpResponse::BadRequest(), e.to_string()),
+        PlaylistServiceError::UnsupportedFormat(_) | PlaylistServiceError::InvalidImport(_) => (HttpResponse::BadRequest(), e.to_string()),
+        PlaylistServiceError::SmartPlaylistReadOnly => (HttpResponse::Conflict(), e.to_string()),
+        PlaylistServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process playlist".to_string()),
+    };
+    response.json(ApiResponse::<()> {
+        success: false,
+        data: None,
+        message: Some(message),
+    })
+}
@@ -0,0 +1,57 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::plays::{self, PlayServiceError, model::{PlayHistoryQueryViewObject, ScrobbleViewObject}};
+
+// 上报播放事件（开始播放或播放完成）
+pub async fn scrobble(
+    song_id: web::Path<Uuid>,
+    data: web::Json<ScrobbleViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = plays::scrobble_service(user_id, song_id.into_inner(), data.into_inner(), &state.config).await;
+    play_response(result, "Scrobbled successfully")
+}
+
+// 获取当前用户的播放历史
+pub async fn get_play_history(
+    query: web::Query<PlayHistoryQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = plays::get_play_history_service(user_id, query.into_inner(), &state.config).await;
+    play_response(result, "Play history fetched successfully")
+}
+
+fn play_response<T: Serialize>(result: Result<T, PlayServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                PlayServiceError::SongNotFound => (HttpResponse::NotFound(), e.to_string()),
+                PlayServiceError::InvalidPlayedAt => (HttpResponse::BadRequest(), e.to_string()),
+                PlayServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process play event".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::radio::{self, RadioServiceError, model::RadioQueryViewObject};
+
+// 以歌曲、专辑或歌手为种子获取电台队列
+pub async fn get_radio(
+    path: web::Path<(String, Uuid)>,
+    query: web::Query<RadioQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let (kind, seed_id) = path.into_inner();
+    let result = radio::get_radio_service(user_id, &kind, seed_id, query.into_inner(), &state.config).await;
+    radio_response(result, "Radio fetched successfully")
+}
+
+fn radio_response<T: Serialize>(result: Result<T, RadioServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                RadioServiceError::InvalidItemType(_) | RadioServiceError::ItemNotFound(_) => (HttpResponse::NotFound(), e.to_string()),
+                RadioServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to build radio".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
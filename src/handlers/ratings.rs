@@ -0,0 +1,59 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::ratings::{self, RatingServiceError, model::RateItemViewObject};
+
+// 为歌曲或专辑评分
+pub async fn rate_item(
+    path: web::Path<(String, Uuid)>,
+    data: web::Json<RateItemViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let (kind, item_id) = path.into_inner();
+    let result = ratings::rate_item_service(user_id, &kind, item_id, data.rating, &state.config).await;
+    rating_response(result, "Rated successfully")
+}
+
+// 清除评分
+pub async fn clear_rating(
+    path: web::Path<(String, Uuid)>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let (kind, item_id) = path.into_inner();
+    let result = ratings::clear_rating_service(user_id, &kind, item_id, &state.config).await;
+    rating_response(result, "Rating cleared successfully")
+}
+
+fn rating_response<T: Serialize>(result: Result<T, RatingServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                RatingServiceError::ItemNotFound(_) => (HttpResponse::NotFound(), e.to_string()),
+                RatingServiceError::InvalidItemType(_) | RatingServiceError::InvalidRating(_) => (HttpResponse::BadRequest(), e.to_string()),
+                RatingServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process rating".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
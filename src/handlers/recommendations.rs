@@ -0,0 +1,42 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::recommendations::{self, RecommendationServiceError, model::RecommendationQueryViewObject};
+
+// 获取当前用户的专辑或歌手推荐
+pub async fn get_recommendations(
+    kind: web::Path<String>,
+    query: web::Query<RecommendationQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = recommendations::get_recommendations_service(user_id, &kind.into_inner(), query.into_inner(), &state.config).await;
+    recommendation_response(result, "Recommendations fetched successfully")
+}
+
+fn recommendation_response<T: Serialize>(result: Result<T, RecommendationServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                RecommendationServiceError::InvalidItemType(_) => (HttpResponse::NotFound(), e.to_string()),
+                RecommendationServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to fetch recommendations".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
@@ -1,24 +1,39 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_multipart::Multipart;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use futures_util::TryStreamExt;
+use std::collections::HashMap;
+use std::str::FromStr;
 use crate::{models, AppState, services};
-use services::songs::model::SongQueryViewObject;
-use crate::handlers::ApiResponse;
+use services::media::{MediaServiceError, MediaUpload};
+use services::songs::SongServiceError;
+use services::songs::model::{SongQueryViewObject, UpdateSongViewObject, UploadSongViewObject};
+use serde::Serialize;
+use crate::handlers::{ApiResponse, current_user_id};
+
+// multipart文本字段的最大长度
+const MAX_FORM_FIELD_SIZE: usize = 64 * 1024;
 
 // 获取歌曲列表（支持按专辑、歌手、流派和音质筛选）
 pub async fn get_songs(
     query: web::Query<SongQueryViewObject>,
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<impl Responder, actix_web::Error> {
     // 将ViewObject转换为DataObject
     let data_query = models::SongQueryParams {
         album_id: query.album_id,
         artist_id: query.artist_id,
+        artist_role: query.artist_role,
         genre: query.genre.clone(),
         quality: query.quality.clone(),
+        min_rating: query.min_rating,
+        sort: query.sort.unwrap_or_default(),
         page: query.page.map(|p| p as u64),
         page_size: query.limit.map(|l| l as u64),
     };
 
-    let songs = services::songs::get_songs_service(data_query, state.config.song_repo.clone())
+    let user_id = current_user_id(&req).unwrap_or_default();
+    let songs = services::songs::get_songs_service(data_query, user_id, state.config.song_repo.clone(), state.config.rating_repo.clone(), state.config.play_event_repo.clone())
         .await
         .map_err(|e| {
             log::error!("Service error: {:?}", e);
@@ -40,8 +55,10 @@ pub async fn get_songs(
 pub async fn get_song_by_id(
     song_id: web::Path<uuid::Uuid>,
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<impl Responder, actix_web::Error> {
-    let song = services::songs::get_song_by_id_service(song_id.into_inner(), state.config.song_repo.clone())
+    let user_id = current_user_id(&req).unwrap_or_default();
+    let song = services::songs::get_song_by_id_service(song_id.into_inner(), user_id, &state.config)
         .await
         .map_err(|e| {
             log::error!("Service error: {:?}", e);
@@ -70,6 +87,7 @@ pub async fn get_song_by_id(
 pub async fn create_song(
     data: web::Json<services::songs::model::CreateSongViewObject>,
     state: web::Data<AppState>,
+    req: HttpRequest,
 ) -> Result<impl Responder, actix_web::Error> {
     // 将ViewObject转换为DataObject
     let data_object = models::CreateSongRequest {
@@ -79,7 +97,14 @@ pub async fn create_song(
         genre: data.genre.clone(),
         duration: data.duration,
         quality: data.quality.clone(),
-        file_path: data.file_path.clone(),
+        // 音频文件只能通过上传或媒体库扫描关联，不接受客户端提供的路径
+        file_path: String::new(),
+        file_hash: None,
+        file_size: None,
+        file_modified_at: None,
+        track_number: data.track_number,
+        disc_number: data.disc_number,
+        created_by: req.extensions().get::<String>().cloned().unwrap_or("system".to_string()),
     };
 
     let song = services::songs::create_song_service(data_object, state.config.song_repo.clone(), state.config.album_repo.clone(), state.config.artist_repo.clone())
@@ -102,4 +127,296 @@ pub async fn create_song(
         data: Some(song),
         message: Some("Song created successfully".to_string()),
     }))
-}
\ No newline at end of file
+}
+// 上传歌曲（multipart: file字段为音频文件，其余字段为歌曲信息）
+pub async fn upload_song(
+    mut payload: Multipart,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let created_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let (fields, upload) = read_upload_form(&mut payload, &state).await?;
+
+    let upload = match upload {
+        Some(upload) => upload,
+        None => return Ok(bad_request("Missing audio file".to_string())),
+    };
+
+    let data = match parse_upload_form(&fields) {
+        Ok(data) => data,
+        Err(message) => {
+            upload.discard().await;
+            return Ok(bad_request(message));
+        }
+    };
+
+    match services::songs::upload_song_service(
+        data,
+        upload,
+        created_by,
+        &state.config.media_root,
+        state.config.song_repo.clone(),
+        state.config.album_repo.clone(),
+        state.config.artist_repo.clone(),
+    ).await {
+        Ok(song) => Ok(HttpResponse::Created().json(ApiResponse {
+            success: true,
+            data: Some(song),
+            message: Some("Song uploaded successfully".to_string()),
+        })),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            Ok(upload_error_response(&e))
+        }
+    }
+}
+
+// 读取multipart表单：file字段写入媒体库临时文件，其余字段作为文本返回
+async fn read_upload_form(
+    payload: &mut Multipart,
+    state: &web::Data<AppState>,
+) -> Result<(HashMap<String, String>, Option<MediaUpload>), actix_web::Error> {
+    let mut fields: HashMap<String, String> = HashMap::new();
+    let mut upload: Option<MediaUpload> = None;
+
+    loop {
+        let mut field = match payload.try_next().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                if let Some(upload) = upload {
+                    upload.discard().await;
+                }
+                return Err(e.into());
+            }
+        };
+        let name = field.name().unwrap_or_default().to_string();
+
+        if name == "file" {
+            if let Some(previous) = upload.take() {
+                previous.discard().await;
+            }
+            let file_name = field.content_disposition()
+                .and_then(|cd| cd.get_filename())
+                .unwrap_or_default()
+                .to_string();
+            let mut media = match MediaUpload::new(&state.config.media_root, &file_name, state.config.max_upload_size).await {
+                Ok(media) => media,
+                Err(e) => return Err(form_error(upload_error_response(&SongServiceError::MediaError(e)))),
+            };
+            loop {
+                match field.try_next().await {
+                    Ok(Some(chunk)) => {
+                        if let Err(e) = media.write_chunk(&chunk).await {
+                            media.discard().await;
+                            return Err(form_error(upload_error_response(&SongServiceError::MediaError(e))));
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        media.discard().await;
+                        return Err(e.into());
+                    }
+                }
+            }
+            upload = Some(media);
+        } else {
+            let mut value = Vec::new();
+            while let Some(chunk) = field.try_next().await? {
+                if value.len() + chunk.len() > MAX_FORM_FIELD_SIZE {
+                    if let Some(upload) = upload {
+                        upload.discard().await;
+                    }
+                    return Err(form_error(bad_request(format!("Field {} is too large", name))));
+                }
+                value.extend_from_slice(&chunk);
+            }
+            fields.insert(name, String::from_utf8_lossy(&value).trim().to_string());
+        }
+    }
+
+    Ok((fields, upload))
+}
+
+// 表单读取失败时直接返回的错误响应
+fn form_error(response: HttpResponse) -> actix_web::Error {
+    actix_web::error::InternalError::from_response("Invalid upload form", response).into()
+}
+
+// 更新歌曲信息
+pub async fn update_song(
+    song_id: web::Path<uuid::Uuid>,
+    data: web::Json<UpdateSongViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = services::songs::update_song_service(song_id.into_inner(), data.into_inner(), updated_by, &state.config).await;
+    song_response(result, "Song updated successfully")
+}
+
+// 删除歌曲（软删除）
+pub async fn delete_song(
+    song_id: web::Path<uuid::Uuid>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let deleted_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = services::songs::delete_song_service(song_id.into_inner(), deleted_by, &state.config).await;
+    song_response(result, "Song deleted successfully")
+}
+
+// 替换歌曲音频文件（multipart: file字段为新的音频文件）
+pub async fn replace_song_file(
+    song_id: web::Path<uuid::Uuid>,
+    mut payload: Multipart,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let (_, upload) = read_upload_form(&mut payload, &state).await?;
+    let Some(upload) = upload else {
+        return Ok(bad_request("Missing audio file".to_string()));
+    };
+
+    match services::songs::replace_song_file_service(song_id.into_inner(), upload, updated_by, &state.config).await {
+        Ok(song) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(song),
+            message: Some("Song file replaced successfully".to_string()),
+        })),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            Ok(upload_error_response(&e))
+        }
+    }
+}
+
+// 获取歌曲的历史版本（被替换前的音频文件）
+pub async fn get_song_versions(
+    song_id: web::Path<uuid::Uuid>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let result = services::songs::get_song_versions_service(song_id.into_inner(), &state.config).await;
+    song_response(result, "Song versions fetched successfully")
+}
+
+// 恢复歌曲的历史版本
+pub async fn restore_song_version(
+    path: web::Path<(uuid::Uuid, uuid::Uuid)>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (song_id, version_id) = path.into_inner();
+    let updated_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let result = services::songs::restore_song_version_service(song_id, version_id, updated_by, &state.config).await;
+    song_response(result, "Song version restored successfully")
+}
+
+// 获取歌曲音频文件中提取的标签
+pub async fn get_song_tags(
+    song_id: web::Path<uuid::Uuid>,
+    state: web::Data<AppState>,
+) -> Result<HttpResponse, actix_web::Error> {
+    match services::songs::get_song_tags_service(song_id.into_inner(), state.config.song_repo.clone()).await {
+        Ok(tags) => Ok(HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(tags),
+            message: Some("Song tags fetched successfully".to_string()),
+        })),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match &e {
+                SongServiceError::SongNotFound => (HttpResponse::NotFound(), e.to_string()),
+                SongServiceError::MediaError(MediaServiceError::IoError(io)) if io.kind() == std::io::ErrorKind::NotFound => {
+                    (HttpResponse::NotFound(), "Audio file not found".to_string())
+                }
+                SongServiceError::MediaError(MediaServiceError::UnsupportedFormat(_)) => (HttpResponse::UnprocessableEntity(), e.to_string()),
+                _ => (HttpResponse::InternalServerError(), "Failed to read song tags".to_string()),
+            };
+            Ok(response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            }))
+        }
+    }
+}
+
+// 解析上传表单中的歌曲信息
+fn parse_upload_form(fields: &HashMap<String, String>) -> Result<UploadSongViewObject, String> {
+    Ok(UploadSongViewObject {
+        title: optional_field(fields, "title")?,
+        album_id: optional_field(fields, "albumId")?,
+        artist_id: optional_field(fields, "artistId")?,
+        genre: optional_field(fields, "genre")?,
+        duration: optional_field(fields, "duration")?,
+        quality: optional_field(fields, "quality")?,
+        track_number: optional_field(fields, "trackNumber")?,
+        disc_number: optional_field(fields, "discNumber")?,
+    })
+}
+
+fn optional_field<T: FromStr>(fields: &HashMap<String, String>, name: &str) -> Result<Option<T>, String> {
+    match fields.get(name).filter(|value| !value.is_empty()) {
+        Some(value) => value.parse::<T>().map(Some).map_err(|_| format!("Invalid field: {}", name)),
+        None => Ok(None),
+    }
+}
+
+fn bad_request(message: String) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        data: None,
+        message: Some(message),
+    })
+}
+
+fn song_response<T: Serialize>(result: Result<T, SongServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match &e {
+                SongServiceError::SongNotFound | SongServiceError::VersionNotFound => (HttpResponse::NotFound(), e.to_string()),
+                SongServiceError::AlbumNotFound
+                | SongServiceError::ArtistNotFound
+                | SongServiceError::InvalidField(_) => (HttpResponse::BadRequest(), e.to_string()),
+                SongServiceError::MediaError(MediaServiceError::IoError(io)) if io.kind() == std::io::ErrorKind::NotFound => {
+                    (HttpResponse::Conflict(), "Audio file of this version no longer exists".to_string())
+                }
+                _ => (HttpResponse::InternalServerError(), "Failed to process song".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
+
+fn upload_error_response(e: &SongServiceError) -> HttpResponse {
+    let mut response = match e {
+        SongServiceError::SongNotFound => HttpResponse::NotFound(),
+        SongServiceError::SameFile => HttpResponse::Conflict(),
+        SongServiceError::AlbumNotFound | SongServiceError::ArtistNotFound | SongServiceError::MissingField(_) => HttpResponse::BadRequest(),
+        SongServiceError::MediaError(MediaServiceError::UnsupportedFormat(_)) => HttpResponse::UnsupportedMediaType(),
+        SongServiceError::MediaError(MediaServiceError::FileTooLarge(_)) => HttpResponse::PayloadTooLarge(),
+        SongServiceError::MediaError(MediaServiceError::EmptyFile) => HttpResponse::BadRequest(),
+        _ => HttpResponse::InternalServerError(),
+    };
+    let message = match e {
+        SongServiceError::DatabaseError(_) | SongServiceError::MediaError(MediaServiceError::IoError(_)) => "Failed to upload song".to_string(),
+        _ => e.to_string(),
+    };
+    response.json(ApiResponse::<()> {
+        success: false,
+        data: None,
+        message: Some(message),
+    })
+}
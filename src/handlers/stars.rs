@@ -0,0 +1,72 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::stars::{self, StarServiceError, model::StarredQueryViewObject};
+
+// 收藏歌曲、专辑或歌手
+pub async fn star_item(
+    path: web::Path<(String, Uuid)>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let (kind, item_id) = path.into_inner();
+    let result = stars::star_item_service(user_id, &kind, item_id, &state.config).await;
+    star_response(result, "Starred successfully")
+}
+
+// 取消收藏
+pub async fn unstar_item(
+    path: web::Path<(String, Uuid)>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let (kind, item_id) = path.into_inner();
+    let result = stars::unstar_item_service(user_id, &kind, item_id, &state.config).await;
+    star_response(result, "Unstarred successfully")
+}
+
+// 获取当前用户收藏的歌曲、专辑或歌手
+pub async fn get_starred(
+    kind: web::Path<String>,
+    query: web::Query<StarredQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = stars::get_starred_service(user_id, &kind.into_inner(), query.into_inner(), &state.config).await;
+    star_response(result, "Starred items fetched successfully")
+}
+
+fn star_response<T: Serialize>(result: Result<T, StarServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                StarServiceError::ItemNotFound(_) => (HttpResponse::NotFound(), e.to_string()),
+                StarServiceError::InvalidItemType(_) => (HttpResponse::BadRequest(), e.to_string()),
+                StarServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process star".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
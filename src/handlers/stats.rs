@@ -0,0 +1,71 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+
+use crate::AppState;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
+use crate::services::stats::{self, StatsServiceError, model::{ListeningStatsQueryViewObject, TimezoneViewObject, YearInReviewQueryViewObject}};
+
+// 获取当前用户指定日期范围内的听歌统计
+pub async fn get_listening_stats(
+    query: web::Query<ListeningStatsQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = stats::get_listening_stats_service(user_id, query.into_inner(), &state.config).await;
+    stats_response(result, "Listening stats fetched successfully")
+}
+
+// 获取当前用户的年度报告
+pub async fn get_year_in_review(
+    year: web::Path<i32>,
+    query: web::Query<YearInReviewQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = stats::get_year_in_review_service(user_id, year.into_inner(), query.into_inner(), &state.config).await;
+    stats_response(result, "Year in review fetched successfully")
+}
+
+// 设置当前用户的统计时区
+pub async fn set_timezone(
+    data: web::Json<TimezoneViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let Some(user_id) = current_user_id(&req) else {
+        return unauthorized_response();
+    };
+    let result = stats::set_timezone_service(user_id, data.into_inner(), &state.config).await;
+    stats_response(result, "Timezone updated successfully")
+}
+
+fn stats_response<T: Serialize>(result: Result<T, StatsServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                StatsServiceError::UserNotFound => (HttpResponse::NotFound(), e.to_string()),
+                StatsServiceError::InvalidRange
+                | StatsServiceError::InvalidYear(_)
+                | StatsServiceError::InvalidTimezone(_) => (HttpResponse::BadRequest(), e.to_string()),
+                StatsServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to fetch listening stats".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
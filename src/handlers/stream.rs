@@ -0,0 +1,133 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use crate::{services, AppState};
+use crate::services::stream::{SongStream, StreamServiceError};
+use crate::services::stream::hls::PLAYLIST_CONTENT_TYPE;
+use crate::services::stream::model::StreamQueryViewObject;
+use crate::handlers::{ApiResponse, current_user_id};
+
+// 播放歌曲音频流（原始文件和转码缓存支持Range请求，可按format/maxBitrate实时转码）
+pub async fn stream_song(
+    song_id: web::Path<uuid::Uuid>,
+    query: web::Query<StreamQueryViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let song_id = song_id.into_inner();
+    let result = services::stream::stream_song_service(
+        song_id,
+        &query,
+        state.config.song_repo.clone(),
+        &state.config.media_root,
+        &state.config.transcoder,
+        &state.config.transcode_cache,
+    ).await;
+    if result.is_ok() {
+        record_stream(&req, song_id, &state).await;
+    }
+
+    match result {
+        // NamedFile会根据Range头返回206/416，并设置Accept-Ranges和Content-Range
+        Ok(SongStream::File(file)) => Ok((*file).into_response(&req)),
+        // 转码输出长度未知，不支持Range
+        Ok(SongStream::Transcoded { content_type, body }) => Ok(HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header(("Accept-Ranges", "none"))
+            .streaming(body)),
+        Err(e) => Ok(stream_error_response(e)),
+    }
+}
+
+// 获取HLS主播放列表
+pub async fn hls_master_playlist(
+    song_id: web::Path<uuid::Uuid>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let song_id = song_id.into_inner();
+    match services::stream::hls::master_playlist_service(song_id, state.config.song_repo.clone()).await {
+        Ok(playlist) => {
+            record_stream(&req, song_id, &state).await;
+            HttpResponse::Ok().content_type(PLAYLIST_CONTENT_TYPE).body(playlist)
+        }
+        Err(e) => stream_error_response(e),
+    }
+}
+
+// 获取指定码率的HLS媒体播放列表
+pub async fn hls_variant_playlist(
+    path: web::Path<(uuid::Uuid, u32)>,
+    state: web::Data<AppState>,
+) -> HttpResponse {
+    let (song_id, bitrate) = path.into_inner();
+    match services::stream::hls::variant_playlist_service(song_id, bitrate, state.config.song_repo.clone()).await {
+        Ok(playlist) => HttpResponse::Ok().content_type(PLAYLIST_CONTENT_TYPE).body(playlist),
+        Err(e) => stream_error_response(e),
+    }
+}
+
+// 获取HLS分片（首次请求时生成）
+pub async fn hls_segment(
+    path: web::Path<(uuid::Uuid, u32, String)>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let (song_id, bitrate, segment) = path.into_inner();
+    let index = match segment.strip_suffix(".ts").and_then(|index| index.parse::<u32>().ok()) {
+        Some(index) => index,
+        None => return stream_error_response(StreamServiceError::SegmentNotFound),
+    };
+
+    let result = services::stream::hls::segment_service(
+        song_id,
+        bitrate,
+        index,
+        state.config.song_repo.clone(),
+        &state.config.media_root,
+        &state.config.transcoder,
+        &state.config.transcode_cache,
+    ).await;
+
+    match result {
+        Ok(file) => file.into_response(&req),
+        Err(e) => stream_error_response(e),
+    }
+}
+
+/// 是否为一次播放的首个音频请求（没有Range或从头开始），拖动进度产生的Range请求不重复记录
+pub fn is_stream_start(req: &HttpRequest) -> bool {
+    match req.headers().get(header::RANGE).and_then(|range| range.to_str().ok()) {
+        Some(range) => range.trim().starts_with("bytes=0-"),
+        None => true,
+    }
+}
+
+// 记录音频流开始用于排行榜，失败不影响播放
+async fn record_stream(req: &HttpRequest, song_id: uuid::Uuid, state: &AppState) {
+    let Some(user_id) = current_user_id(req) else {
+        return;
+    };
+    if !is_stream_start(req) {
+        return;
+    }
+    if let Err(e) = services::plays::record_stream_service(user_id, song_id, &state.config).await {
+        log::warn!("Failed to record stream of song {}: {}", song_id, e);
+    }
+}
+
+fn stream_error_response(e: StreamServiceError) -> HttpResponse {
+    log::error!("Service error: {:?}", e);
+    let (mut response, message) = match e {
+        StreamServiceError::SongNotFound | StreamServiceError::SegmentNotFound => (HttpResponse::NotFound(), e.to_string()),
+        StreamServiceError::FileMissing(_) => (HttpResponse::NotFound(), "Audio file not found".to_string()),
+        StreamServiceError::SongDeleted => (HttpResponse::Gone(), e.to_string()),
+        StreamServiceError::UnsupportedFormat(_) => (HttpResponse::BadRequest(), e.to_string()),
+        StreamServiceError::DurationUnknown => (HttpResponse::UnprocessableEntity(), e.to_string()),
+        StreamServiceError::TranscoderBusy => (HttpResponse::ServiceUnavailable(), e.to_string()),
+        _ => (HttpResponse::InternalServerError(), "Failed to open audio file".to_string()),
+    };
+    response.json(ApiResponse::<()> {
+        success: false,
+        data: None,
+        message: Some(message),
+    })
+}
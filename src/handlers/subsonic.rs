@@ -0,0 +1,125 @@
+use actix_files::NamedFile;
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::{services, AppState};
+use crate::handlers::stream::is_stream_start;
+use crate::models::User;
+use crate::services::stream::{SongStream, StreamServiceError};
+use crate::services::stream::model::StreamQueryViewObject;
+use crate::services::subsonic::{SubsonicError, SubsonicParams};
+use crate::services::subsonic::response::{render_error, render_ok, ResponseFormat};
+
+// Subsonic API入口（/rest/{method}，兼容.view后缀）
+pub async fn subsonic_api(
+    method: web::Path<String>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let params = SubsonicParams::from_query(req.query_string());
+    let format = ResponseFormat::parse(params.get("f"));
+    let method = method.into_inner();
+    let method = method.trim_end_matches(".view");
+
+    let user = match services::subsonic::authenticate_service(&params, &state.config).await {
+        Ok(user) => user,
+        Err(e) => {
+            log::warn!("Subsonic authentication failed: {}", e);
+            return error_response(format, e);
+        }
+    };
+
+    match method {
+        "stream" => stream(&params, &user, format, &state, &req, false).await,
+        "download" => stream(&params, &user, format, &state, &req, true).await,
+        "getCoverArt" => cover_art(&params, format, &state, &req).await,
+        _ => match services::subsonic::handle_request_service(method, &params, &user, &state.config).await {
+            Ok(payload) => HttpResponse::Ok()
+                .content_type(format.content_type())
+                .body(render_ok(format, payload)),
+            Err(e) => error_response(format, e),
+        },
+    }
+}
+
+// 播放或下载歌曲，下载始终返回原始文件
+async fn stream(
+    params: &SubsonicParams,
+    user: &User,
+    format: ResponseFormat,
+    state: &AppState,
+    req: &HttpRequest,
+    download: bool,
+) -> HttpResponse {
+    let song_id = match params.require_id() {
+        Ok(song_id) => song_id,
+        Err(e) => return error_response(format, e),
+    };
+    let query = StreamQueryViewObject {
+        format: if download { Some("raw".to_string()) } else { params.get("format").map(|format| format.to_string()) },
+        max_bitrate: if download { None } else { params.parse("maxBitRate") },
+    };
+
+    let result = services::stream::stream_song_service(
+        song_id,
+        &query,
+        state.config.song_repo.clone(),
+        &state.config.media_root,
+        &state.config.transcoder,
+        &state.config.transcode_cache,
+    ).await;
+
+    // 播放（非下载）的首个请求计入排行榜，失败不影响播放
+    if result.is_ok()
+        && !download
+        && is_stream_start(req)
+        && let Err(e) = services::plays::record_stream_service(user.id, song_id, &state.config).await
+    {
+        log::warn!("Failed to record stream of song {}: {}", song_id, e);
+    }
+
+    match result {
+        Ok(SongStream::File(file)) => (*file).into_response(req),
+        Ok(SongStream::Transcoded { content_type, body }) => HttpResponse::Ok()
+            .content_type(content_type)
+            .insert_header(("Accept-Ranges", "none"))
+            .streaming(body),
+        Err(e) => {
+            let error = match e {
+                StreamServiceError::SongNotFound | StreamServiceError::SongDeleted => SubsonicError::not_found("Song"),
+                StreamServiceError::FileMissing(_) => SubsonicError::not_found("Audio file"),
+                e => SubsonicError::generic(&e.to_string()),
+            };
+            error_response(format, error)
+        }
+    }
+}
+
+// 返回封面图片，远程地址直接重定向
+async fn cover_art(params: &SubsonicParams, format: ResponseFormat, state: &AppState, req: &HttpRequest) -> HttpResponse {
+    let cover = match services::subsonic::find_cover_art_service(params, &state.config).await {
+        Ok(cover) => cover,
+        Err(e) => return error_response(format, e),
+    };
+
+    if cover.starts_with("http://") || cover.starts_with("https://") {
+        return HttpResponse::Found().insert_header(("Location", cover)).finish();
+    }
+    // 只提供媒体库封面目录内的文件，避免读取服务器上的任意文件
+    let Some(path) = services::media::resolve_cover_path(&state.config.media_root, &cover).await else {
+        log::warn!("Rejected cover art outside the covers directory: {}", cover);
+        return error_response(format, SubsonicError::not_found("Cover art"));
+    };
+    match NamedFile::open_async(&path).await {
+        Ok(file) => file.into_response(req),
+        Err(e) => {
+            log::error!("Failed to open cover art {}: {:?}", cover, e);
+            error_response(format, SubsonicError::not_found("Cover art"))
+        }
+    }
+}
+
+// Subsonic协议错误同样以HTTP 200返回
+fn error_response(format: ResponseFormat, error: SubsonicError) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .body(render_error(format, &error))
+}
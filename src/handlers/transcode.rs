@@ -0,0 +1,37 @@
+use actix_web::{web, HttpResponse, Responder};
+use crate::AppState;
+use crate::services::transcode::model::CachePurgeQueryViewObject;
+use crate::handlers::ApiResponse;
+
+// 获取转码缓存统计
+pub async fn get_transcode_cache_stats(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(ApiResponse {
+        success: true,
+        data: Some(state.config.transcode_cache.stats()),
+        message: None,
+    })
+}
+
+// 清理转码缓存（可按歌曲清理）
+pub async fn purge_transcode_cache(
+    query: web::Query<CachePurgeQueryViewObject>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let cache = state.config.transcode_cache.clone();
+    let song_id = query.song_id;
+    match web::block(move || cache.purge(song_id)).await {
+        Ok(purged) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(purged),
+            message: Some("Transcode cache purged".to_string()),
+        }),
+        Err(e) => {
+            log::error!("Failed to purge transcode cache: {:?}", e);
+            HttpResponse::InternalServerError().json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some("Failed to purge transcode cache".to_string()),
+            })
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::handlers::ApiResponse;
+use crate::services::trash::{self, TrashServiceError, model::TrashQueryViewObject};
+
+// 列出回收站中的对象
+pub async fn list_trash(
+    query: web::Query<TrashQueryViewObject>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let result = trash::list_trash_service(query.into_inner(), &state.config).await;
+    trash_response(result, "Trash fetched successfully")
+}
+
+// 恢复回收站中的对象及随之删除的下级对象
+pub async fn restore_trash_item(
+    req: HttpRequest,
+    path: web::Path<(String, Uuid)>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let restored_by = req.extensions().get::<String>().cloned().unwrap_or("system".to_string());
+    let (kind, id) = path.into_inner();
+    let result = trash::restore_trash_item_service(&kind, id, &restored_by, &state.config).await;
+    trash_response(result, "Item restored successfully")
+}
+
+fn trash_response<T: Serialize>(result: Result<T, TrashServiceError>, message: &str) -> HttpResponse {
+    match result {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse {
+            success: true,
+            data: Some(data),
+            message: Some(message.to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                TrashServiceError::InvalidItemType(_) => (HttpResponse::BadRequest(), e.to_string()),
+                TrashServiceError::TrashItemNotFound => (HttpResponse::NotFound(), e.to_string()),
+                TrashServiceError::ParentDeleted(_) | TrashServiceError::UsernameTaken => (HttpResponse::Conflict(), e.to_string()),
+                TrashServiceError::DatabaseError(_) => (HttpResponse::InternalServerError(), "Failed to process trash request".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use actix_web::web::Query;
-use actix_web:: {web, HttpResponse, Responder};
+use actix_web:: {web, HttpRequest, HttpResponse, Responder};
 
-use crate::services::users::{UserQueryViewObject, get_users_service};
+use crate::services::users::{UserQueryViewObject, SubsonicPasswordViewObject, UserServiceError, get_users_service, set_subsonic_password_service};
 use crate::AppState;
-use crate::handlers::ApiResponse;
+use crate::handlers::{ApiResponse, current_user_id, unauthorized_response};
 
 pub async fn get_users(query: Query<UserQueryViewObject>, state: web::Data<AppState>) ->Result<impl Responder, actix_web::Error> { 
     
@@ -19,4 +19,37 @@ pub async fn get_users(query: Query<UserQueryViewObject>, state: web::Data<AppSt
         data: Some(users),
         message: Some("users fetched successfully".to_string()),
     }))
+}
+
+// 设置当前用户的Subsonic客户端密码
+pub async fn set_subsonic_password(
+    data: web::Json<SubsonicPasswordViewObject>,
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = match current_user_id(&req) {
+        Some(user_id) => user_id,
+        None => return unauthorized_response(),
+    };
+
+    match set_subsonic_password_service(user_id, data.into_inner(), &state.config.jwt_secret, state.config.user_repo.clone()).await {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::<()> {
+            success: true,
+            data: None,
+            message: Some("Subsonic password updated".to_string()),
+        }),
+        Err(e) => {
+            log::error!("Service error: {:?}", e);
+            let (mut response, message) = match e {
+                UserServiceError::UserNotFound => (HttpResponse::NotFound(), e.to_string()),
+                UserServiceError::InvalidPassword => (HttpResponse::BadRequest(), e.to_string()),
+                _ => (HttpResponse::InternalServerError(), "Failed to update subsonic password".to_string()),
+            };
+            response.json(ApiResponse::<()> {
+                success: false,
+                data: None,
+                message: Some(message),
+            })
+        }
+    }
 }
\ No newline at end of file
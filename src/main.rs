@@ -17,15 +17,44 @@ pub struct AppState {
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     let config = config::AppConfig::new().await;
+
+    // 命令行子命令：music-server scan 扫描媒体库后退出
+    if std::env::args().nth(1).as_deref() == Some("scan") {
+        return match services::library::run_scan_service(&config).await {
+            Ok(status) => {
+                println!(
+                    "Library scan finished: {} files, {} added, {} updated, {} unchanged, {} missing, {} failed",
+                    status.total_files, status.added, status.updated, status.unchanged, status.missing, status.failed
+                );
+                Ok(())
+            }
+            Err(e) => Err(std::io::Error::other(e.to_string())),
+        };
+    }
+
     let app_state = AppState { config: config.clone() };
 
+    // 可选：后台监听媒体目录，保持曲库与文件同步
+    if config.watch_media_root
+        && let Err(e) = services::library::watcher::start_watcher_service(&config)
+    {
+        log::error!("Failed to start library watcher: {}", e);
+    }
+
+    // 后台定时刷新排行榜和推荐模型
+    services::charts::start_chart_scheduler(&config);
+    services::recommendations::start_recommendation_scheduler(&config);
+    // 后台定时清理回收站中超过保留期限的对象
+    services::trash::start_trash_purge_scheduler(&config);
+
     println!("Server running on @ http://localhost:{}", config.port);
     // let logger = Logger::default();
 
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(app_state.clone()))
-            .wrap(Logger::default().log_level(log::Level::Debug))
+            // Subsonic请求的查询字符串包含认证参数，不写入访问日志
+            .wrap(Logger::default().exclude_regex("^/rest/").log_level(log::Level::Debug))
             .wrap(Cors::default()
                   .allow_any_origin()
                   .allow_any_method()
@@ -0,0 +1,74 @@
+use actix_web::{dev::{Transform, Service, ServiceRequest, ServiceResponse}, web, Error, HttpMessage};
+use std::rc::Rc;
+use std::future::Ready;
+use crate::AppState;
+
+// 管理员权限中间件（需在AuthMiddleware之后使用）
+pub struct AdminMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for AdminMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = AdminMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        std::future::ready(Ok(AdminMiddlewareService { service: Rc::new(service) }))
+    }
+}
+
+pub struct AdminMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for AdminMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(
+        &self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let state = req.app_data::<web::Data<AppState>>()
+                .ok_or_else(|| {
+                    actix_web::error::ErrorInternalServerError("Failed to get app state".to_string())
+                })?;
+
+            // AuthMiddleware已将用户ID写入请求扩展
+            let user_id = req.extensions().get::<String>()
+                .and_then(|sub| uuid::Uuid::parse_str(sub).ok())
+                .ok_or_else(|| actix_web::error::ErrorUnauthorized("Auth fail, Please relogin".to_string()))?;
+
+            let user = state.config.user_repo.find_by_id(user_id)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to load user: {:?}", e);
+                    actix_web::error::ErrorInternalServerError("Failed to load user".to_string())
+                })?;
+
+            match user {
+                Some(user) if user.role == "admin" => service.call(req).await,
+                _ => Err(actix_web::error::ErrorForbidden("Admin permission required".to_string())),
+            }
+        })
+    }
+}
@@ -1,2 +1,3 @@
 pub mod auth;
-pub mod logger;
\ No newline at end of file
+pub mod logger;
+pub mod admin;
\ No newline at end of file
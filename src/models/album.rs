@@ -1,8 +1,14 @@
 use chrono::{DateTime, Local, NaiveDate};
-use serde::{Deserialize};
-use sea_orm::{ActiveModelBehavior,ActiveValue, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, DatabaseConnection, DeriveRelation, EnumIter, DeriveEntityModel, prelude::*};
+use serde::{Deserialize, Serialize};
+use sea_orm::{ActiveModelBehavior,ActiveValue, ColumnTrait, ConnectionTrait, DatabaseTransaction, DbBackend, EntityTrait, QueryFilter, QueryOrder, QuerySelect, DatabaseConnection, DeriveRelation, EnumIter, DeriveEntityModel, Statement, TransactionTrait, prelude::*};
+use sea_orm::sea_query::{Func, NullOrdering};
 use uuid::Uuid;
 use std::sync::Arc;
+use super::rating::RatingItemType;
+use super::rating_summary;
+use super::song_play_stat;
+use super::song;
+use super::credit::{self, CreditItemType, CreditRole};
 
 // 定义专辑表实体
 #[derive(Debug, Clone, PartialEq, DeriveEntityModel, Deserialize)]
@@ -53,6 +59,20 @@ pub trait AlbumRepository: Send + Sync {
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Album>, DbErr>;
     async fn create(&self, request: &CreateAlbumData) -> Result<Album, DbErr>;
     async fn find_all(&self, params: &AlbumQueryData) -> Result<Vec<Album>, DbErr>;
+    async fn find_by_name(&self, artist_id: Uuid, name: &str) -> Result<Option<Album>, DbErr>;
+    async fn find_by_artist(&self, artist_id: Uuid) -> Result<Vec<Album>, DbErr>;
+    async fn update_cover_image(&self, id: Uuid, cover_image: &str) -> Result<(), DbErr>;
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Album>, DbErr>;
+    async fn find_list(&self, params: &AlbumListParams) -> Result<Vec<Album>, DbErr>;
+    async fn search(&self, keyword: &str, offset: u64, limit: u64) -> Result<Vec<Album>, DbErr>;
+    async fn count_by_artist(&self) -> Result<Vec<(Uuid, i64)>, DbErr>;
+    async fn update(&self, id: Uuid, data: &UpdateAlbumData) -> Result<Album, DbErr>;
+    // 软删除专辑，专辑中的歌曲按songs处理
+    async fn delete(&self, id: Uuid, deleted_by: &str, songs: AlbumSongsPolicy) -> Result<(), DbErr>;
+    // 按给定的碟号和曲目号重排专辑曲目
+    async fn reorder_tracks(&self, album_id: Uuid, tracks: &[TrackPosition], updated_by: &str) -> Result<(), DbErr>;
+    // 把歌曲移动到另一张专辑（或同一专辑的其他位置），track_number为空时放到该碟末尾
+    async fn move_song(&self, song_id: Uuid, album_id: Uuid, disc_number: i32, track_number: Option<i32>, updated_by: &str) -> Result<(), DbErr>;
 }
 
 // SeaORM 实现的专辑仓库
@@ -71,7 +91,10 @@ impl SeaOrmAlbumRepository {
 #[async_trait::async_trait]
 impl AlbumRepository for SeaOrmAlbumRepository {
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Album>, DbErr> {
-        Entity::find_by_id(id).one(&*self.db).await
+        Entity::find_by_id(id)
+            .filter(Column::DeleteFlag.eq(false))
+            .one(&*self.db)
+            .await
     }
 
     async fn create(&self, request: &CreateAlbumData) -> Result<Album, DbErr> {
@@ -82,6 +105,8 @@ impl AlbumRepository for SeaOrmAlbumRepository {
             cover_image: ActiveValue::Set(request.cover_image.clone()),
             genre: ActiveValue::Set(request.genre.clone()),
             release_date: ActiveValue::Set(request.release_date),
+            created_by: ActiveValue::Set(request.created_by.clone()),
+            updated_by: ActiveValue::Set(request.created_by.clone()),
             ..ActiveModel::new()
         };
 
@@ -89,11 +114,26 @@ impl AlbumRepository for SeaOrmAlbumRepository {
     }
 
     async fn find_all(&self, params: &AlbumQueryData) -> Result<Vec<Album>, DbErr> {
-        let mut query = Entity::find().order_by_desc(Column::ReleaseDate).filter(Column::DeleteFlag.eq(false));
+        let mut query = Entity::find().filter(Column::DeleteFlag.eq(false));
+
+        // 按评分筛选或排序时连接评分汇总表
+        if params.min_rating.is_some() || params.sort == AlbumSort::Rating {
+            query = rating_summary::join_summary(query, RatingItemType::Album);
+        }
+        if let Some(min_rating) = params.min_rating {
+            query = query.filter(rating_summary::average_rating().gte(min_rating));
+        }
+        if params.sort == AlbumSort::Rating {
+            // 未评分的专辑排在最后，平均分相同时评分人数多的在前
+            query = query
+                .order_by_with_nulls(rating_summary::average_rating(), sea_orm::Order::Desc, NullOrdering::Last)
+                .order_by_with_nulls(Expr::col((rating_summary::Entity, rating_summary::Column::RatingCount)), sea_orm::Order::Desc, NullOrdering::Last);
+        }
+        query = query.order_by_desc(Column::ReleaseDate);
 
         // 添加筛选条件
-        if let Some(artist_id) = &params.artist_id {
-            query = query.filter(Column::ArtistId.eq(*artist_id));
+        if let Some(artist_id) = params.artist_id {
+            query = query.filter(credit::credited_condition(CreditItemType::Album, Column::Id, Column::ArtistId, artist_id, params.artist_role));
         }
 
         if let Some(name) = &params.name {
@@ -115,6 +155,335 @@ impl AlbumRepository for SeaOrmAlbumRepository {
             .all(&*self.db)
             .await
     }
+
+    async fn find_by_name(&self, artist_id: Uuid, name: &str) -> Result<Option<Album>, DbErr> {
+        Entity::find()
+            .filter(Column::ArtistId.eq(artist_id))
+            .filter(Column::Name.eq(name)).filter(Column::DeleteFlag.eq(false))
+            .one(&*self.db)
+            .await
+    }
+
+    async fn find_by_artist(&self, artist_id: Uuid) -> Result<Vec<Album>, DbErr> {
+        Entity::find()
+            .filter(Column::ArtistId.eq(artist_id)).filter(Column::DeleteFlag.eq(false))
+            .order_by_desc(Column::ReleaseDate)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn update_cover_image(&self, id: Uuid, cover_image: &str) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::CoverImage, Expr::value(cover_image))
+            .col_expr(Column::UpdatedAt, Expr::value(Local::now()))
+            .filter(Column::Id.eq(id))
+            .exec(&*self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Album>, DbErr> {
+        Entity::find()
+            .filter(Column::Id.is_in(ids.iter().copied()))
+            .filter(Column::DeleteFlag.eq(false))
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_list(&self, params: &AlbumListParams) -> Result<Vec<Album>, DbErr> {
+        let mut query = Entity::find().filter(Column::DeleteFlag.eq(false));
+
+        if let Some(genre) = &params.genre {
+            query = query.filter(Column::Genre.eq(genre));
+        }
+        if let Some(from_year) = params.from_year.and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1)) {
+            query = query.filter(Column::ReleaseDate.gte(from_year));
+        }
+        if let Some(to_year) = params.to_year.and_then(|year| NaiveDate::from_ymd_opt(year, 12, 31)) {
+            query = query.filter(Column::ReleaseDate.lte(to_year));
+        }
+
+        query = match params.order {
+            AlbumListOrder::Newest => query.order_by_desc(Column::CreatedAt),
+            AlbumListOrder::Random => query.order_by(Expr::cust("RANDOM()"), sea_orm::Order::Asc),
+            AlbumListOrder::Name => query.order_by_asc(Column::Name),
+            AlbumListOrder::ArtistName => query
+                .join(sea_orm::JoinType::InnerJoin, Relation::Artist.def())
+                .order_by_asc(super::artist::Column::Name)
+                .order_by_asc(Column::Name),
+            AlbumListOrder::ReleaseDateAsc => query.order_by_asc(Column::ReleaseDate),
+            AlbumListOrder::ReleaseDateDesc => query.order_by_desc(Column::ReleaseDate),
+            // 只返回有评分的专辑
+            AlbumListOrder::Highest => rating_summary::join_summary(query, RatingItemType::Album)
+                .filter(rating_summary::average_rating().is_not_null())
+                .order_by_desc(rating_summary::average_rating())
+                .order_by_desc(Expr::col((rating_summary::Entity, rating_summary::Column::RatingCount)))
+                .order_by_asc(Column::Name),
+            // 按专辑内歌曲的播放统计排序，只返回播放过的专辑
+            AlbumListOrder::Frequent | AlbumListOrder::Recent => {
+                let query = query
+                    .join(sea_orm::JoinType::InnerJoin, super::song::Relation::Album.def().rev())
+                    .join(sea_orm::JoinType::InnerJoin, song_play_stat::Relation::Song.def().rev())
+                    .filter(Expr::col((super::song::Entity, super::song::Column::DeleteFlag)).eq(false))
+                    .group_by(Column::Id);
+                match params.order {
+                    AlbumListOrder::Frequent => query.order_by_desc(song_play_stat::play_count().sum()),
+                    _ => query.order_by_desc(song_play_stat::last_played_at().max()),
+                }
+            }
+        };
+
+        query
+            .offset(params.offset)
+            .limit(params.limit)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn search(&self, keyword: &str, offset: u64, limit: u64) -> Result<Vec<Album>, DbErr> {
+        Entity::find()
+            .filter(Column::DeleteFlag.eq(false))
+            .filter(Expr::expr(Func::lower(Expr::col(Column::Name))).like(format!("%{}%", keyword.to_lowercase())))
+            .order_by_asc(Column::Name)
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn count_by_artist(&self) -> Result<Vec<(Uuid, i64)>, DbErr> {
+        Entity::find()
+            .select_only()
+            .column(Column::ArtistId)
+            .column_as(Column::Id.count(), "album_count")
+            .filter(Column::DeleteFlag.eq(false))
+            .group_by(Column::ArtistId)
+            .into_tuple()
+            .all(&*self.db)
+            .await
+    }
+
+    async fn update(&self, id: Uuid, data: &UpdateAlbumData) -> Result<Album, DbErr> {
+        let mut album = ActiveModel {
+            id: ActiveValue::Unchanged(id),
+            updated_at: ActiveValue::Set(Local::now()),
+            updated_by: ActiveValue::Set(data.updated_by.clone()),
+            ..Default::default()
+        };
+
+        if let Some(artist_id) = data.artist_id {
+            album.artist_id = ActiveValue::Set(artist_id);
+        }
+        if let Some(name) = &data.name {
+            album.name = ActiveValue::Set(name.clone());
+        }
+        if let Some(description) = &data.description {
+            album.description = ActiveValue::Set(description.clone());
+        }
+        if let Some(cover_image) = &data.cover_image {
+            album.cover_image = ActiveValue::Set(cover_image.clone());
+        }
+        if let Some(genre) = &data.genre {
+            album.genre = ActiveValue::Set(genre.clone());
+        }
+        if let Some(release_date) = data.release_date {
+            album.release_date = ActiveValue::Set(release_date);
+        }
+
+        album.update(&*self.db).await
+    }
+
+    async fn delete(&self, id: Uuid, deleted_by: &str, songs: AlbumSongsPolicy) -> Result<(), DbErr> {
+        let now = Local::now();
+        let txn = self.db.begin().await?;
+        match songs {
+            AlbumSongsPolicy::Delete => {
+                song::Entity::update_many()
+                    .col_expr(song::Column::DeleteFlag, Expr::value(true))
+                    .col_expr(song::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(song::Column::UpdatedBy, Expr::value(deleted_by))
+                    .filter(song::Column::AlbumId.eq(id))
+                    .filter(song::Column::DeleteFlag.eq(false))
+                    .exec(&txn)
+                    .await?;
+            }
+            AlbumSongsPolicy::Detach => {
+                // 歌曲移到各自歌手的"Unknown Album"中，与扫描没有专辑标签的文件一致
+                let artist_ids: Vec<Uuid> = song::Entity::find()
+                    .select_only()
+                    .column(song::Column::ArtistId)
+                    .distinct()
+                    .filter(song::Column::AlbumId.eq(id))
+                    .filter(song::Column::DeleteFlag.eq(false))
+                    .into_tuple()
+                    .all(&txn)
+                    .await?;
+                for artist_id in artist_ids {
+                    let target = unknown_album(&txn, artist_id, id, deleted_by).await?;
+                    let next_track = next_track_number(&txn, target, 1).await?;
+                    // 追加到目标专辑第1碟末尾，保持原专辑中的顺序
+                    txn.execute(Statement::from_sql_and_values(
+                        DbBackend::Postgres,
+                        r#"UPDATE "song" s SET "album_id" = $1, "disc_number" = 1, "track_number" = o."rn" + $2 - 1,
+                            "updated_at" = $3, "updated_by" = $4
+                        FROM (
+                            SELECT "id", CAST(ROW_NUMBER() OVER (ORDER BY COALESCE("disc_number", 1), "track_number" NULLS LAST, "title", "id") AS integer) AS "rn"
+                            FROM "song" WHERE "album_id" = $5 AND "artist_id" = $6 AND "delete_flag" = false
+                        ) o
+                        WHERE s."id" = o."id""#,
+                        [target.into(), next_track.into(), now.into(), deleted_by.into(), id.into(), artist_id.into()],
+                    )).await?;
+                }
+            }
+        }
+        Entity::update_many()
+            .col_expr(Column::DeleteFlag, Expr::value(true))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .col_expr(Column::UpdatedBy, Expr::value(deleted_by))
+            .filter(Column::Id.eq(id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await
+    }
+
+    async fn reorder_tracks(&self, album_id: Uuid, tracks: &[TrackPosition], updated_by: &str) -> Result<(), DbErr> {
+        let now = Local::now();
+        let txn = self.db.begin().await?;
+        for track in tracks {
+            song::Entity::update_many()
+                .col_expr(song::Column::DiscNumber, Expr::value(track.disc_number))
+                .col_expr(song::Column::TrackNumber, Expr::value(track.track_number))
+                .col_expr(song::Column::UpdatedAt, Expr::value(now))
+                .col_expr(song::Column::UpdatedBy, Expr::value(updated_by))
+                .filter(song::Column::Id.eq(track.song_id))
+                .filter(song::Column::AlbumId.eq(album_id))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await
+    }
+
+    async fn move_song(&self, song_id: Uuid, album_id: Uuid, disc_number: i32, track_number: Option<i32>, updated_by: &str) -> Result<(), DbErr> {
+        let now = Local::now();
+        let txn = self.db.begin().await?;
+        let song = song::Entity::find_by_id(song_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("song".to_string()))?;
+
+        // 目标碟按当前顺序排列，插入歌曲后整碟重新编号为1..n
+        let current: Vec<(Uuid, Option<i32>)> = song::Entity::find()
+            .select_only()
+            .column(song::Column::Id)
+            .column(song::Column::TrackNumber)
+            .filter(song::Column::AlbumId.eq(album_id))
+            .filter(Expr::expr(Func::coalesce([Expr::col(song::Column::DiscNumber).into(), Expr::val(1).into()])).eq(disc_number))
+            .filter(song::Column::DeleteFlag.eq(false))
+            .order_by_with_nulls(song::Column::TrackNumber, sea_orm::Order::Asc, NullOrdering::Last)
+            .order_by_asc(song::Column::Title)
+            .order_by_asc(song::Column::Id)
+            .into_tuple()
+            .all(&txn)
+            .await?;
+        let order = insert_track(current.iter().map(|(id, _)| *id).collect(), song_id, track_number);
+        for (index, id) in order.into_iter().enumerate() {
+            let track_number = index as i32 + 1;
+            if id == song_id {
+                song::Entity::update_many()
+                    .col_expr(song::Column::AlbumId, Expr::value(album_id))
+                    .col_expr(song::Column::DiscNumber, Expr::value(disc_number))
+                    .col_expr(song::Column::TrackNumber, Expr::value(track_number))
+                    .col_expr(song::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(song::Column::UpdatedBy, Expr::value(updated_by))
+                    .filter(song::Column::Id.eq(song_id))
+                    .exec(&txn)
+                    .await?;
+            } else if !current.contains(&(id, Some(track_number))) {
+                song::Entity::update_many()
+                    .col_expr(song::Column::TrackNumber, Expr::value(track_number))
+                    .filter(song::Column::Id.eq(id))
+                    .exec(&txn)
+                    .await?;
+            }
+        }
+
+        // 移到其他碟时重新编号原来所在的碟，消除留下的空位
+        let source_disc = song.disc_number.unwrap_or(1);
+        if song.album_id != album_id || source_disc != disc_number {
+            compact_disc(&txn, song.album_id, source_disc).await?;
+        }
+        txn.commit().await
+    }
+}
+
+// 查找歌手未删除的"Unknown Album"，不存在时创建
+async fn unknown_album(txn: &DatabaseTransaction, artist_id: Uuid, excluded: Uuid, created_by: &str) -> Result<Uuid, DbErr> {
+    let existing = Entity::find()
+        .filter(Column::ArtistId.eq(artist_id))
+        .filter(Column::Name.eq(UNKNOWN_ALBUM))
+        .filter(Column::Id.ne(excluded))
+        .filter(Column::DeleteFlag.eq(false))
+        .one(txn)
+        .await?;
+    if let Some(album) = existing {
+        return Ok(album.id);
+    }
+    let album = ActiveModel {
+        artist_id: ActiveValue::Set(artist_id),
+        name: ActiveValue::Set(UNKNOWN_ALBUM.to_string()),
+        description: ActiveValue::Set(None),
+        cover_image: ActiveValue::Set(None),
+        genre: ActiveValue::Set(None),
+        release_date: ActiveValue::Set(NaiveDate::default()),
+        created_by: ActiveValue::Set(created_by.to_string()),
+        updated_by: ActiveValue::Set(created_by.to_string()),
+        delete_flag: ActiveValue::Set(false),
+        ..ActiveModel::new()
+    };
+    Ok(album.insert(txn).await?.id)
+}
+
+// 专辑某一碟的下一个曲目号
+async fn next_track_number(txn: &DatabaseTransaction, album_id: Uuid, disc_number: i32) -> Result<i32, DbErr> {
+    let max: Option<i32> = song::Entity::find()
+        .select_only()
+        .column_as(song::Column::TrackNumber.max(), "max_track")
+        .filter(song::Column::AlbumId.eq(album_id))
+        .filter(Expr::expr(Func::coalesce([Expr::col(song::Column::DiscNumber).into(), Expr::val(1).into()])).eq(disc_number))
+        .filter(song::Column::DeleteFlag.eq(false))
+        .into_tuple()
+        .one(txn)
+        .await?
+        .flatten();
+    Ok(max.unwrap_or(0) + 1)
+}
+
+// 把歌曲放到一碟曲目顺序中的指定曲目号（从1开始），order中已有该歌曲时先取出；
+// 曲目号为空或超出末尾时追加到末尾
+fn insert_track(mut order: Vec<Uuid>, song_id: Uuid, track_number: Option<i32>) -> Vec<Uuid> {
+    order.retain(|id| *id != song_id);
+    let index = track_number
+        .map(|track| (track.max(1) - 1) as usize)
+        .unwrap_or(order.len())
+        .min(order.len());
+    order.insert(index, song_id);
+    order
+}
+
+// 按当前顺序把专辑某一碟的曲目号重排为1..n
+async fn compact_disc(txn: &DatabaseTransaction, album_id: Uuid, disc_number: i32) -> Result<(), DbErr> {
+    txn.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"UPDATE "song" s SET "track_number" = o."rn"
+        FROM (
+            SELECT "id", CAST(ROW_NUMBER() OVER (ORDER BY "track_number" NULLS LAST, "title", "id") AS integer) AS "rn"
+            FROM "song" WHERE "album_id" = $1 AND COALESCE("disc_number", 1) = $2 AND "delete_flag" = false
+        ) o
+        WHERE s."id" = o."id" AND s."track_number" IS DISTINCT FROM o."rn""#,
+        [album_id.into(), disc_number.into()],
+    )).await?;
+    Ok(())
 }
 
 // 重命名为Album以保持兼容性
@@ -129,18 +498,94 @@ pub struct CreateAlbumData {
     pub cover_image: Option<String>,
     pub genre: Option<String>,
     pub release_date: NaiveDate,
+    pub created_by: String,
+}
+
+// 扫描和解除关联时，没有专辑的歌曲归入歌手的该专辑
+pub const UNKNOWN_ALBUM: &str = "Unknown Album";
+
+// 专辑更新数据，外层为None的字段保持不变，Some(None)表示清空
+#[derive(Debug, Default)]
+pub struct UpdateAlbumData {
+    pub artist_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub cover_image: Option<Option<String>>,
+    pub genre: Option<Option<String>>,
+    pub release_date: Option<NaiveDate>,
+    pub updated_by: String,
+}
+
+// 删除专辑时对专辑中歌曲的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlbumSongsPolicy {
+    // 一并软删除
+    Delete,
+    // 保留歌曲，移到歌手的"Unknown Album"中
+    Detach,
+}
+
+// 曲目位置
+#[derive(Debug, Clone)]
+pub struct TrackPosition {
+    pub song_id: Uuid,
+    pub disc_number: i32,
+    pub track_number: i32,
 }
 
 // 专辑查询参数
 #[derive(Debug, Deserialize)]
 pub struct AlbumQueryData {
+    // 匹配主歌手及任一署名歌手
     pub artist_id: Option<Uuid>,
+    // 只匹配以该角色署名的歌手
+    pub artist_role: Option<CreditRole>,
     pub name: Option<String>,
     pub release_date: Option<NaiveDate>,
+    // 最低平均评分
+    pub min_rating: Option<f64>,
+    pub sort: AlbumSort,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
 }
 
+// 专辑查询排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlbumSort {
+    // 按发行日期倒序
+    #[default]
+    ReleaseDate,
+    // 按平均评分倒序
+    Rating,
+}
+
+// 专辑列表排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlbumListOrder {
+    Newest,
+    Random,
+    Name,
+    ArtistName,
+    ReleaseDateAsc,
+    ReleaseDateDesc,
+    Highest,
+    Frequent,
+    Recent,
+}
+
+// 专辑列表查询参数
+#[derive(Debug)]
+pub struct AlbumListParams {
+    pub order: AlbumListOrder,
+    pub genre: Option<String>,
+    pub from_year: Option<i32>,
+    pub to_year: Option<i32>,
+    pub offset: u64,
+    pub limit: u64,
+}
+
 // 为Album模型添加数据访问方法
 impl Album {
     // 创建新专辑
@@ -152,6 +597,8 @@ impl Album {
             cover_image: ActiveValue::Set(request.cover_image.clone()),
             genre: ActiveValue::Set(request.genre.clone()),
             release_date: ActiveValue::Set(request.release_date),
+            created_by: ActiveValue::Set(request.created_by.clone()),
+            updated_by: ActiveValue::Set(request.created_by.clone()),
             ..ActiveModel::new()
         };
 
@@ -171,8 +618,8 @@ impl Album {
         let mut query = Entity::find().order_by_desc(Column::ReleaseDate).filter(Column::DeleteFlag.eq(false));
 
         // 添加筛选条件
-        if let Some(artist_id) = &params.artist_id {
-            query = query.filter(Column::ArtistId.eq(*artist_id));
+        if let Some(artist_id) = params.artist_id {
+            query = query.filter(credit::credited_condition(CreditItemType::Album, Column::Id, Column::ArtistId, artist_id, params.artist_role));
         }
 
         if let Some(name) = &params.name {
@@ -194,4 +641,36 @@ impl Album {
             .all(db)
             .await
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::now_v7()).collect()
+    }
+
+    #[test]
+    fn insert_track_moves_down_within_disc() {
+        // A..F，把B移到第5首：A C D E B F
+        let id = ids(6);
+        let order = insert_track(id.clone(), id[1], Some(5));
+        assert_eq!(order, vec![id[0], id[2], id[3], id[4], id[1], id[5]]);
+    }
+
+    #[test]
+    fn insert_track_moves_up_within_disc() {
+        let id = ids(4);
+        let order = insert_track(id.clone(), id[3], Some(1));
+        assert_eq!(order, vec![id[3], id[0], id[1], id[2]]);
+    }
+
+    #[test]
+    fn insert_track_from_other_disc() {
+        let id = ids(3);
+        let moved = Uuid::now_v7();
+        assert_eq!(insert_track(id.clone(), moved, Some(2)), vec![id[0], moved, id[1], id[2]]);
+        assert_eq!(insert_track(id.clone(), moved, None), vec![id[0], id[1], id[2], moved]);
+        assert_eq!(insert_track(id.clone(), moved, Some(10)), vec![id[0], id[1], id[2], moved]);
+    }
+}
@@ -1,9 +1,11 @@
-use chrono::{ DateTime, Local, NaiveDate, Utc};
+use chrono::{ DateTime, Local, NaiveDate};
 use serde::{Serialize, Deserialize};
-use sea_orm::{ActiveModelTrait, ActiveValue,DeriveEntityModel, QueryOrder, QuerySelect};
+use sea_orm::{ActiveModelTrait, ActiveValue,DeriveEntityModel, QueryOrder, QuerySelect, TransactionTrait};
+use sea_orm::sea_query::Func;
 use sea_orm::entity::prelude::*;
 use uuid::Uuid;
 use std::sync::Arc;
+use super::{album, song};
 
 
 // 定义歌手表实体
@@ -57,6 +59,17 @@ pub struct CreateArtistData {
     pub sex: Option<String>,
 }
 
+// 歌手更新数据，外层为None的字段保持不变，Some(None)表示清空
+#[derive(Debug, Default)]
+pub struct UpdateArtistData {
+    pub name: Option<String>,
+    pub nationality: Option<Option<String>>,
+    pub birth_date: Option<Option<NaiveDate>>,
+    pub avatar: Option<Option<String>>,
+    pub sex: Option<Option<String>>,
+    pub updated_by: String,
+}
+
 // 专辑创建请求
 #[derive(Debug, Deserialize)]
 pub struct ArtistQueryData {
@@ -75,6 +88,14 @@ pub trait ArtistRepository {
     async fn create(&self, data: &CreateArtistData) -> Result<Artist, DbErr>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Artist>, DbErr>;
     async fn find_all(&self, params: &ArtistQueryData) -> Result<Vec<Artist>, DbErr>;
+    async fn find_by_name(&self, name: &str) -> Result<Option<Artist>, DbErr>;
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Artist>, DbErr>;
+    async fn search(&self, keyword: &str, offset: u64, limit: u64) -> Result<Vec<Artist>, DbErr>;
+    async fn update(&self, id: Uuid, data: &UpdateArtistData) -> Result<Artist, DbErr>;
+    // 歌手名下未删除的专辑数和歌曲数
+    async fn count_dependants(&self, id: Uuid) -> Result<(u64, u64), DbErr>;
+    // 软删除歌手，cascade为true时同时软删除其专辑、专辑中的歌曲和歌手的歌曲
+    async fn delete(&self, id: Uuid, deleted_by: &str, cascade: bool) -> Result<(), DbErr>;
 }
 
 // 重命名为Artist以保持兼容性
@@ -112,7 +133,7 @@ impl ArtistRepository for SeaOrmArtistRepository {
     }
 
     async fn find_all(&self, params: &ArtistQueryData) -> Result<Vec<Artist>, DbErr> {
-        let mut query = Entity::find();
+        let mut query = Entity::find().filter(Column::DeleteFlag.eq(false));
 
         if let Some(id) = params.id {
             query = query.filter(Column::Id.eq(id));
@@ -137,6 +158,114 @@ impl ArtistRepository for SeaOrmArtistRepository {
     }
 
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Artist>, DbErr> {
-        Entity::find_by_id(id).one(&*self.db).await
+        Entity::find_by_id(id)
+            .filter(Column::DeleteFlag.eq(false))
+            .one(&*self.db)
+            .await
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Artist>, DbErr> {
+        Entity::find()
+            .filter(Column::Name.eq(name)).filter(Column::DeleteFlag.eq(false))
+            .one(&*self.db)
+            .await
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Artist>, DbErr> {
+        Entity::find()
+            .filter(Column::Id.is_in(ids.iter().copied()))
+            .filter(Column::DeleteFlag.eq(false))
+            .all(&*self.db)
+            .await
+    }
+
+    async fn search(&self, keyword: &str, offset: u64, limit: u64) -> Result<Vec<Artist>, DbErr> {
+        Entity::find()
+            .filter(Column::DeleteFlag.eq(false))
+            .filter(Expr::expr(Func::lower(Expr::col(Column::Name))).like(format!("%{}%", keyword.to_lowercase())))
+            .order_by_asc(Column::Name)
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn update(&self, id: Uuid, data: &UpdateArtistData) -> Result<Artist, DbErr> {
+        let mut artist = ActiveModel {
+            id: ActiveValue::Unchanged(id),
+            updated_at: ActiveValue::Set(Local::now()),
+            updated_by: ActiveValue::Set(data.updated_by.clone()),
+            ..Default::default()
+        };
+
+        if let Some(name) = &data.name {
+            artist.name = ActiveValue::Set(name.clone());
+        }
+        if let Some(nationality) = &data.nationality {
+            artist.nationality = ActiveValue::Set(nationality.clone());
+        }
+        if let Some(birth_date) = data.birth_date {
+            artist.birth_date = ActiveValue::Set(birth_date);
+        }
+        if let Some(avatar) = &data.avatar {
+            artist.avatar = ActiveValue::Set(avatar.clone());
+        }
+        if let Some(sex) = &data.sex {
+            artist.sex = ActiveValue::Set(sex.clone());
+        }
+
+        artist.update(&*self.db).await
+    }
+
+    async fn count_dependants(&self, id: Uuid) -> Result<(u64, u64), DbErr> {
+        let albums = album::Entity::find()
+            .filter(album::Column::ArtistId.eq(id))
+            .filter(album::Column::DeleteFlag.eq(false))
+            .count(&*self.db)
+            .await?;
+        let songs = song::Entity::find()
+            .filter(song::Column::ArtistId.eq(id))
+            .filter(song::Column::DeleteFlag.eq(false))
+            .count(&*self.db)
+            .await?;
+        Ok((albums, songs))
+    }
+
+    async fn delete(&self, id: Uuid, deleted_by: &str, cascade: bool) -> Result<(), DbErr> {
+        let now = Local::now();
+        let txn = self.db.begin().await?;
+        if cascade {
+            let album_ids: Vec<Uuid> = album::Entity::find()
+                .select_only()
+                .column(album::Column::Id)
+                .filter(album::Column::ArtistId.eq(id))
+                .filter(album::Column::DeleteFlag.eq(false))
+                .into_tuple()
+                .all(&txn)
+                .await?;
+            song::Entity::update_many()
+                .col_expr(song::Column::DeleteFlag, Expr::value(true))
+                .col_expr(song::Column::UpdatedAt, Expr::value(now))
+                .col_expr(song::Column::UpdatedBy, Expr::value(deleted_by))
+                .filter(song::Column::DeleteFlag.eq(false))
+                .filter(song::Column::ArtistId.eq(id).or(song::Column::AlbumId.is_in(album_ids.iter().copied())))
+                .exec(&txn)
+                .await?;
+            album::Entity::update_many()
+                .col_expr(album::Column::DeleteFlag, Expr::value(true))
+                .col_expr(album::Column::UpdatedAt, Expr::value(now))
+                .col_expr(album::Column::UpdatedBy, Expr::value(deleted_by))
+                .filter(album::Column::Id.is_in(album_ids))
+                .exec(&txn)
+                .await?;
+        }
+        Entity::update_many()
+            .col_expr(Column::DeleteFlag, Expr::value(true))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .col_expr(Column::UpdatedBy, Expr::value(deleted_by))
+            .filter(Column::Id.eq(id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await
     }
 }
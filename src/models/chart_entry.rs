@@ -0,0 +1,284 @@
+use chrono::{DateTime, Duration, Local, SubsecRound};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, QuerySelect, Statement, TransactionTrait};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+
+// 定义排行榜条目表实体，由定时任务整体刷新
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chart_entry")]
+pub struct Model {
+    // 榜单对象类型：song、album、artist
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_type: String,
+    // 统计周期：day、week、month
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub period: String,
+    // 流派（小写），总榜为空字符串
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub genre: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_id: Uuid,
+    // 本周期按播放次数的排名
+    pub position: i32,
+    // 上一周期按播放次数的排名，上一周期没有播放时为空
+    pub previous_position: Option<i32>,
+    // 按趋势分数的排名
+    pub trending_position: i32,
+    // 上次刷新时的趋势排名，上次不在榜单时为空
+    pub previous_trending_position: Option<i32>,
+    pub play_count: i64,
+    pub previous_play_count: i64,
+    // 收听人数
+    pub listener_count: i64,
+    pub trending_score: f64,
+    pub computed_at: DateTime<Local>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// 重命名为ChartEntry以保持兼容性
+pub type ChartEntry = Model;
+
+// 每个榜单保存的最大条目数
+pub const CHART_SIZE: i32 = 100;
+
+// 排行榜对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartItemType {
+    Song,
+    Album,
+    Artist,
+}
+
+impl ChartItemType {
+    pub const ALL: [ChartItemType; 3] = [ChartItemType::Song, ChartItemType::Album, ChartItemType::Artist];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChartItemType::Song => "song",
+            ChartItemType::Album => "album",
+            ChartItemType::Artist => "artist",
+        }
+    }
+
+    // 同时接受单数和复数形式（路由中使用songs/albums/artists）
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "song" | "songs" => Some(ChartItemType::Song),
+            "album" | "albums" => Some(ChartItemType::Album),
+            "artist" | "artists" => Some(ChartItemType::Artist),
+            _ => None,
+        }
+    }
+
+    // 歌曲表中用于分组的列
+    fn song_column(self) -> &'static str {
+        match self {
+            ChartItemType::Song => "id",
+            ChartItemType::Album => "album_id",
+            ChartItemType::Artist => "artist_id",
+        }
+    }
+}
+
+// 排行榜统计周期，均为截至刷新时刻的滚动窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChartPeriod {
+    Day,
+    #[default]
+    Week,
+    Month,
+}
+
+impl ChartPeriod {
+    pub const ALL: [ChartPeriod; 3] = [ChartPeriod::Day, ChartPeriod::Week, ChartPeriod::Month];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChartPeriod::Day => "day",
+            ChartPeriod::Week => "week",
+            ChartPeriod::Month => "month",
+        }
+    }
+
+    pub fn window(self) -> Duration {
+        match self {
+            ChartPeriod::Day => Duration::days(1),
+            ChartPeriod::Week => Duration::days(7),
+            ChartPeriod::Month => Duration::days(30),
+        }
+    }
+}
+
+// 榜单排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChartOrder {
+    // 按播放次数
+    Top,
+    // 按趋势分数
+    Trending,
+}
+
+impl ChartOrder {
+    pub fn parse(chart: &str) -> Option<Self> {
+        match chart {
+            "top" => Some(ChartOrder::Top),
+            "trending" => Some(ChartOrder::Trending),
+            _ => None,
+        }
+    }
+}
+
+// 定义排行榜仓库 trait
+#[async_trait::async_trait]
+pub trait ChartRepository: Send + Sync {
+    // 重新计算全部榜单，now为统计窗口的结束时间
+    async fn refresh(&self, now: DateTime<Local>) -> Result<(), DbErr>;
+    async fn find_entries(
+        &self,
+        item_type: ChartItemType,
+        period: ChartPeriod,
+        genre: &str,
+        order: ChartOrder,
+        limit: u64,
+    ) -> Result<Vec<ChartEntry>, DbErr>;
+}
+
+// SeaORM 实现的排行榜仓库
+pub struct SeaOrmChartRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmChartRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+// 计算一个对象类型和周期下的总榜及各流派榜单
+//
+// 一次收听为一个播放完成事件，或一个附近没有对应播放完成事件的音频流事件
+// （只拉取音频流、不上报播放的客户端）。趋势分数为本周期按时间衰减（半衰期为周期的1/4）
+// 的收听数乘以相对上一周期的增长倍数，新近增长越快分数越高。
+// 已在榜单中的条目保留上次刷新的趋势排名，之后删除本次没有更新的条目
+fn refresh_sql(item_type: ChartItemType) -> String {
+    format!(
+        r#"WITH listens AS (
+            SELECT e."user_id", e."played_at", s."{key}" AS "item_id", NULLIF(LOWER(BTRIM(s."genre")), '') AS "genre"
+            FROM "play_event" e JOIN "song" s ON s."id" = e."song_id"
+            WHERE e."played_at" >= $3 AND e."played_at" < $5 AND s."delete_flag" = false
+                AND (e."event_type" = 'completed' OR (e."event_type" = 'stream' AND NOT EXISTS (
+                    SELECT 1 FROM "play_event" c
+                    WHERE c."user_id" = e."user_id" AND c."song_id" = e."song_id" AND c."event_type" = 'completed'
+                        AND c."played_at" BETWEEN e."played_at" - INTERVAL '30 minutes'
+                            AND e."played_at" + make_interval(secs => s."duration") + INTERVAL '30 minutes'
+                )))
+        ),
+        totals AS (
+            SELECT l."item_id", g."genre",
+                COUNT(*) FILTER (WHERE l."played_at" >= $4) AS "play_count",
+                COUNT(*) FILTER (WHERE l."played_at" < $4) AS "previous_play_count",
+                COUNT(DISTINCT l."user_id") FILTER (WHERE l."played_at" >= $4) AS "listener_count",
+                COALESCE(SUM(POWER(0.5, EXTRACT(EPOCH FROM ($5 - l."played_at"))::float8 / $6::float8)) FILTER (WHERE l."played_at" >= $4), 0) AS "weighted"
+            FROM listens l CROSS JOIN LATERAL (VALUES (''), (l."genre")) AS g("genre")
+            WHERE g."genre" IS NOT NULL
+            GROUP BY l."item_id", g."genre"
+        ),
+        scored AS (
+            SELECT *, "weighted" * ("play_count" + 1) / ("previous_play_count" + 1) AS "trending_score"
+            FROM totals
+        ),
+        ranked AS (
+            SELECT *,
+                ROW_NUMBER() OVER (PARTITION BY "genre" ORDER BY "play_count" DESC, "listener_count" DESC, "item_id") AS "position",
+                ROW_NUMBER() OVER (PARTITION BY "genre" ORDER BY "previous_play_count" DESC, "item_id") AS "previous_position",
+                ROW_NUMBER() OVER (PARTITION BY "genre" ORDER BY "trending_score" DESC, "play_count" DESC, "item_id") AS "trending_position"
+            FROM scored
+        )
+        INSERT INTO "chart_entry" ("item_type", "period", "genre", "item_id", "position", "previous_position",
+            "trending_position", "previous_trending_position", "play_count", "previous_play_count",
+            "listener_count", "trending_score", "computed_at")
+        SELECT $1, $2, "genre", "item_id", "position",
+            CASE WHEN "previous_play_count" > 0 THEN "previous_position" END,
+            "trending_position", NULL, "play_count", "previous_play_count", "listener_count", "trending_score", $5
+        FROM ranked
+        WHERE "play_count" > 0 AND ("position" <= $7 OR "trending_position" <= $7)
+        ON CONFLICT ("item_type", "period", "genre", "item_id") DO UPDATE SET
+            "position" = EXCLUDED."position",
+            "previous_position" = EXCLUDED."previous_position",
+            "trending_position" = EXCLUDED."trending_position",
+            "previous_trending_position" = "chart_entry"."trending_position",
+            "play_count" = EXCLUDED."play_count",
+            "previous_play_count" = EXCLUDED."previous_play_count",
+            "listener_count" = EXCLUDED."listener_count",
+            "trending_score" = EXCLUDED."trending_score",
+            "computed_at" = EXCLUDED."computed_at""#,
+        key = item_type.song_column(),
+    )
+}
+
+#[async_trait::async_trait]
+impl ChartRepository for SeaOrmChartRepository {
+    // 所有榜单在同一事务中刷新，读取方不会看到一半新一半旧的数据
+    async fn refresh(&self, now: DateTime<Local>) -> Result<(), DbErr> {
+        // 数据库只保存到微秒，截断后才能按computed_at识别本次写入的条目
+        let now = now.trunc_subsecs(6);
+        let txn = self.db.begin().await?;
+        for item_type in ChartItemType::ALL {
+            for period in ChartPeriod::ALL {
+                let window = period.window();
+                let half_life = (window.num_seconds() / 4) as f64;
+                txn.execute(Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    refresh_sql(item_type),
+                    [
+                        item_type.as_str().into(),
+                        period.as_str().into(),
+                        (now - window * 2).into(),
+                        (now - window).into(),
+                        now.into(),
+                        half_life.into(),
+                        CHART_SIZE.into(),
+                    ],
+                )).await?;
+            }
+        }
+        Entity::delete_many()
+            .filter(Column::ComputedAt.ne(now))
+            .exec(&txn)
+            .await?;
+        txn.commit().await
+    }
+
+    async fn find_entries(
+        &self,
+        item_type: ChartItemType,
+        period: ChartPeriod,
+        genre: &str,
+        order: ChartOrder,
+        limit: u64,
+    ) -> Result<Vec<ChartEntry>, DbErr> {
+        let rank = match order {
+            ChartOrder::Top => Column::Position,
+            ChartOrder::Trending => Column::TrendingPosition,
+        };
+        Entity::find()
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::Period.eq(period.as_str()))
+            .filter(Column::Genre.eq(genre))
+            .filter(rank.lte(CHART_SIZE))
+            .order_by_asc(rank)
+            .limit(limit)
+            .all(&*self.db)
+            .await
+    }
+}
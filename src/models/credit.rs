@@ -0,0 +1,194 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveValue, ColumnTrait, Condition, DatabaseConnection, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, QuerySelect, TransactionTrait};
+use sea_orm::sea_query::Query;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+use super::{album, song};
+
+// 定义署名表实体，记录歌曲或专辑除主歌手外的其他署名歌手
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "credit")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    // 署名对象类型：song、album
+    pub item_type: String,
+    pub item_id: Uuid,
+    pub artist_id: Uuid,
+    // 署名角色：primary、featuring、composer、lyricist、producer、remixer
+    pub role: String,
+    // 对象署名中的显示顺序，从0开始
+    pub position: i32,
+    pub created_at: DateTime<Local>,
+    pub created_by: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// 重命名为Credit以保持兼容性
+pub type Credit = Model;
+
+// 署名对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreditItemType {
+    Song,
+    Album,
+}
+
+impl CreditItemType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CreditItemType::Song => "song",
+            CreditItemType::Album => "album",
+        }
+    }
+
+    // 同时接受单数和复数形式（路由中使用songs/albums）
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "song" | "songs" => Some(CreditItemType::Song),
+            "album" | "albums" => Some(CreditItemType::Album),
+            _ => None,
+        }
+    }
+}
+
+// 署名角色，主歌手（song.artist_id、album.artist_id）隐含primary角色，
+// 合辑或合唱的其他主歌手也以primary署名
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CreditRole {
+    Primary,
+    Featuring,
+    Composer,
+    Lyricist,
+    Producer,
+    Remixer,
+}
+
+impl CreditRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CreditRole::Primary => "primary",
+            CreditRole::Featuring => "featuring",
+            CreditRole::Composer => "composer",
+            CreditRole::Lyricist => "lyricist",
+            CreditRole::Producer => "producer",
+            CreditRole::Remixer => "remixer",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "primary" => Some(CreditRole::Primary),
+            "featuring" => Some(CreditRole::Featuring),
+            "composer" => Some(CreditRole::Composer),
+            "lyricist" => Some(CreditRole::Lyricist),
+            "producer" => Some(CreditRole::Producer),
+            "remixer" => Some(CreditRole::Remixer),
+            _ => None,
+        }
+    }
+}
+
+// 按署名歌手筛选歌曲或专辑：主歌手或署名表中的任一歌手均匹配，指定角色时只匹配该角色
+// （主歌手视为primary角色）。id和primary_artist为被筛选表的主键列和主歌手列
+pub fn credited_condition<C: ColumnTrait>(
+    item_type: CreditItemType,
+    id: C,
+    primary_artist: C,
+    artist_id: Uuid,
+    role: Option<CreditRole>,
+) -> Condition {
+    let mut credited = Query::select();
+    credited
+        .column(Column::ItemId)
+        .from(Entity)
+        .and_where(Column::ItemType.eq(item_type.as_str()))
+        .and_where(Column::ArtistId.eq(artist_id));
+    if let Some(role) = role {
+        credited.and_where(Column::Role.eq(role.as_str()));
+    }
+
+    let condition = Condition::any().add(id.in_subquery(credited.to_owned()));
+    match role {
+        None | Some(CreditRole::Primary) => condition.add(primary_artist.eq(artist_id)),
+        Some(_) => condition,
+    }
+}
+
+// 定义署名仓库 trait
+#[async_trait::async_trait]
+pub trait CreditRepository: Send + Sync {
+    // 对象的署名，按显示顺序
+    async fn find_by_item(&self, item_type: CreditItemType, item_id: Uuid) -> Result<Vec<Credit>, DbErr>;
+    // 整体替换对象的署名，列表顺序即显示顺序
+    async fn replace(&self, item_type: CreditItemType, item_id: Uuid, credits: &[(Uuid, CreditRole)], created_by: &str) -> Result<Vec<Credit>, DbErr>;
+}
+
+// SeaORM 实现的署名仓库
+pub struct SeaOrmCreditRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmCreditRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CreditRepository for SeaOrmCreditRepository {
+    async fn find_by_item(&self, item_type: CreditItemType, item_id: Uuid) -> Result<Vec<Credit>, DbErr> {
+        Entity::find()
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.eq(item_id))
+            .order_by_asc(Column::Position)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn replace(&self, item_type: CreditItemType, item_id: Uuid, credits: &[(Uuid, CreditRole)], created_by: &str) -> Result<Vec<Credit>, DbErr> {
+        let txn = self.db.begin().await?;
+        // 锁定署名对象，同一对象的并发替换依次执行
+        match item_type {
+            CreditItemType::Song => song::Entity::find_by_id(item_id).lock_exclusive().one(&txn).await?.map(|_| ()),
+            CreditItemType::Album => album::Entity::find_by_id(item_id).lock_exclusive().one(&txn).await?.map(|_| ()),
+        }
+        .ok_or_else(|| DbErr::RecordNotFound(item_type.as_str().to_string()))?;
+        Entity::delete_many()
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.eq(item_id))
+            .exec(&txn)
+            .await?;
+        if !credits.is_empty() {
+            let now = Local::now();
+            let models = credits.iter().enumerate().map(|(position, (artist_id, role))| ActiveModel {
+                id: ActiveValue::Set(Uuid::now_v7()),
+                item_type: ActiveValue::Set(item_type.as_str().to_string()),
+                item_id: ActiveValue::Set(item_id),
+                artist_id: ActiveValue::Set(*artist_id),
+                role: ActiveValue::Set(role.as_str().to_string()),
+                position: ActiveValue::Set(position as i32),
+                created_at: ActiveValue::Set(now),
+                created_by: ActiveValue::Set(created_by.to_string()),
+            });
+            Entity::insert_many(models).exec_without_returning(&txn).await?;
+        }
+        let saved = Entity::find()
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.eq(item_id))
+            .order_by_asc(Column::Position)
+            .all(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(saved)
+    }
+}
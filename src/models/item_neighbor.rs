@@ -0,0 +1,197 @@
+use chrono::{DateTime, Local, SubsecRound};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, Statement, TransactionTrait};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+
+// 定义相似对象表实体（基于用户收听行为的协同过滤模型），由定时任务整体重建
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "item_neighbor")]
+pub struct Model {
+    // 对象类型：album、artist
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_type: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub neighbor_id: Uuid,
+    // 相似度（0-1）
+    pub score: f64,
+    // 在item_id的相似对象中的排名，从1开始
+    pub rank: i32,
+    pub computed_at: DateTime<Local>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// 重命名为ItemNeighbor以保持兼容性
+pub type ItemNeighbor = Model;
+
+// 每个对象保存的相似对象数量
+pub const MAX_NEIGHBORS: i32 = 50;
+// 共同用户数较少时相似度向0收缩，避免个别用户造成偶然的高相似度
+const SHRINKAGE_USERS: f64 = 2.0;
+
+// 推荐对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecommendationItemType {
+    Album,
+    Artist,
+}
+
+impl RecommendationItemType {
+    pub const ALL: [RecommendationItemType; 2] = [RecommendationItemType::Album, RecommendationItemType::Artist];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecommendationItemType::Album => "album",
+            RecommendationItemType::Artist => "artist",
+        }
+    }
+
+    // 同时接受单数和复数形式（路由中使用albums/artists）
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "album" | "albums" => Some(RecommendationItemType::Album),
+            "artist" | "artists" => Some(RecommendationItemType::Artist),
+            _ => None,
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            RecommendationItemType::Album => "album",
+            RecommendationItemType::Artist => "artist",
+        }
+    }
+
+    // 歌曲表中指向该对象的列
+    fn song_column(self) -> &'static str {
+        match self {
+            RecommendationItemType::Album => "album_id",
+            RecommendationItemType::Artist => "artist_id",
+        }
+    }
+}
+
+// 定义推荐仓库 trait
+#[async_trait::async_trait]
+pub trait RecommendationRepository: Send + Sync {
+    // 根据全部用户的收听信号重建相似对象模型
+    async fn rebuild(&self, now: DateTime<Local>) -> Result<(), DbErr>;
+    // 用户对各对象的偏好分数，按分数倒序
+    async fn find_affinities(&self, user_id: Uuid, item_type: RecommendationItemType, limit: u64) -> Result<Vec<(Uuid, f64)>, DbErr>;
+    async fn find_neighbors(&self, item_type: RecommendationItemType, item_ids: &[Uuid]) -> Result<Vec<ItemNeighbor>, DbErr>;
+}
+
+// SeaORM 实现的推荐仓库
+pub struct SeaOrmRecommendationRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmRecommendationRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+// 用户对对象的偏好：播放完成次数取对数，收藏加1，评分4分及以上加0.5；已删除的对象不计入
+// $1为对象类型，user_filter为空时计算全部用户
+fn affinity_sql(item_type: RecommendationItemType, user_filter: &str) -> String {
+    format!(
+        r#"SELECT signals."user_id", signals."item_id", SUM(signals."weight") AS "weight" FROM (
+            SELECT e."user_id", s."{key}" AS "item_id", LN(1 + CAST(COUNT(*) AS float8)) AS "weight"
+            FROM "play_event" e JOIN "song" s ON s."id" = e."song_id"
+            WHERE e."event_type" = 'completed' AND s."delete_flag" = false {user_filter}
+            GROUP BY e."user_id", s."{key}"
+            UNION ALL
+            SELECT e."user_id", e."item_id", 1.0 FROM "star" e WHERE e."item_type" = $1 {user_filter}
+            UNION ALL
+            SELECT e."user_id", e."item_id", 0.5 FROM "rating" e WHERE e."item_type" = $1 AND e."rating" >= 4 {user_filter}
+        ) signals JOIN "{table}" i ON i."id" = signals."item_id" AND i."delete_flag" = false
+        GROUP BY signals."user_id", signals."item_id""#,
+        key = item_type.song_column(),
+        table = item_type.table(),
+        user_filter = user_filter,
+    )
+}
+
+// 按余弦相似度计算对象之间的相似度，每个对象保留前N个
+fn rebuild_sql(item_type: RecommendationItemType) -> String {
+    format!(
+        r#"WITH affinity AS ({affinity}),
+        norms AS (
+            SELECT "item_id", SQRT(SUM("weight" * "weight")) AS "norm" FROM affinity GROUP BY "item_id"
+        ),
+        pairs AS (
+            SELECT a."item_id", b."item_id" AS "neighbor_id", SUM(a."weight" * b."weight") AS "dot", COUNT(*) AS "users"
+            FROM affinity a JOIN affinity b ON a."user_id" = b."user_id" AND a."item_id" <> b."item_id"
+            GROUP BY a."item_id", b."item_id"
+        ),
+        scored AS (
+            SELECT p."item_id", p."neighbor_id",
+                p."dot" / (na."norm" * nb."norm") * p."users" / (p."users" + $3::float8) AS "score"
+            FROM pairs p
+            JOIN norms na ON na."item_id" = p."item_id"
+            JOIN norms nb ON nb."item_id" = p."neighbor_id"
+        ),
+        ranked AS (
+            SELECT *, ROW_NUMBER() OVER (PARTITION BY "item_id" ORDER BY "score" DESC, "neighbor_id") AS "rank"
+            FROM scored
+        )
+        INSERT INTO "item_neighbor" ("item_type", "item_id", "neighbor_id", "score", "rank", "computed_at")
+        SELECT $1, "item_id", "neighbor_id", "score", "rank", $2 FROM ranked WHERE "rank" <= $4"#,
+        affinity = affinity_sql(item_type, ""),
+    )
+}
+
+#[async_trait::async_trait]
+impl RecommendationRepository for SeaOrmRecommendationRepository {
+    // 所有类型在同一事务中替换，读取方不会看到重建一半的模型
+    async fn rebuild(&self, now: DateTime<Local>) -> Result<(), DbErr> {
+        let now = now.trunc_subsecs(6);
+        let txn = self.db.begin().await?;
+        Entity::delete_many().exec(&txn).await?;
+        for item_type in RecommendationItemType::ALL {
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                rebuild_sql(item_type),
+                [item_type.as_str().into(), now.into(), SHRINKAGE_USERS.into(), MAX_NEIGHBORS.into()],
+            )).await?;
+        }
+        txn.commit().await
+    }
+
+    async fn find_affinities(&self, user_id: Uuid, item_type: RecommendationItemType, limit: u64) -> Result<Vec<(Uuid, f64)>, DbErr> {
+        let sql = format!(
+            r#"SELECT "item_id", "weight" FROM ({affinity}) affinity ORDER BY "weight" DESC, "item_id" LIMIT $3"#,
+            affinity = affinity_sql(item_type, r#"AND e."user_id" = $2"#),
+        );
+        let rows = self.db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [item_type.as_str().into(), user_id.into(), (limit as i64).into()],
+            ))
+            .await?;
+        rows.iter()
+            .map(|row| Ok((row.try_get("", "item_id")?, row.try_get("", "weight")?)))
+            .collect()
+    }
+
+    async fn find_neighbors(&self, item_type: RecommendationItemType, item_ids: &[Uuid]) -> Result<Vec<ItemNeighbor>, DbErr> {
+        Entity::find()
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.is_in(item_ids.iter().copied()))
+            .order_by_asc(Column::ItemId)
+            .order_by_asc(Column::Rank)
+            .all(&*self.db)
+            .await
+    }
+}
@@ -0,0 +1,24 @@
+use chrono::NaiveDate;
+use serde::{Serialize, Deserialize};
+use sea_orm::{DeriveEntityModel, DeriveRelation, EnumIter};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+// 定义每日分时听歌汇总表实体，day和hour为用户时区的本地日期和小时
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "listening_daily_hour")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub day: NaiveDate,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hour: i16,
+    pub play_count: i64,
+    pub listened_seconds: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,34 @@
+use chrono::NaiveDate;
+use serde::{Serialize, Deserialize};
+use sea_orm::{DeriveEntityModel, DeriveRelation, EnumIter};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+// 定义每日歌曲听歌汇总表实体，day为用户时区的本地日期
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "listening_daily_song")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub day: NaiveDate,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub song_id: Uuid,
+    pub play_count: i64,
+    pub listened_seconds: i64,
+}
+
+// 定义关联
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::song::Entity", from = "Column::SongId", to = "super::song::Column::Id")]
+    Song,
+}
+
+impl Related<super::song::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Song.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,225 @@
+use chrono::NaiveDate;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Select, Statement, TransactionTrait};
+use sea_orm::sea_query::{Expr, IntoColumnRef, SimpleExpr};
+use uuid::Uuid;
+use std::sync::Arc;
+use super::{listening_daily_hour, listening_daily_song, song};
+
+// 统计结果：(分组键, 播放次数, 听歌秒数)
+pub type ListeningTotal<K> = (K, i64, i64);
+
+// 定义听歌统计仓库 trait，查询均基于每日汇总表，日期为用户时区的本地日期
+#[async_trait::async_trait]
+pub trait ListeningStatRepository: Send + Sync {
+    // 按新时区重新生成用户的每日汇总
+    async fn rebuild(&self, user_id: Uuid, timezone: &str) -> Result<(), DbErr>;
+    async fn totals(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<(i64, i64), DbErr>;
+    async fn top_songs(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<Uuid>>, DbErr>;
+    async fn top_albums(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<Uuid>>, DbErr>;
+    async fn top_artists(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<Uuid>>, DbErr>;
+    async fn top_genres(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<String>>, DbErr>;
+    // 按小时（0-23）分布
+    async fn by_hour(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<ListeningTotal<i16>>, DbErr>;
+    // 按星期（1为周一，7为周日）分布
+    async fn by_weekday(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<ListeningTotal<i32>>, DbErr>;
+    // 有播放记录的日期，升序
+    async fn active_days(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<NaiveDate>, DbErr>;
+    // 指定时区的当前日期
+    async fn local_today(&self, timezone: &str) -> Result<NaiveDate, DbErr>;
+}
+
+// 将一次播放完成累加到每日汇总，与播放事件在同一事务中执行
+pub async fn add_play<C: ConnectionTrait>(db: &C, event_id: Uuid, timezone: &str, listened_seconds: i64) -> Result<(), DbErr> {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"INSERT INTO "listening_daily_song" ("user_id", "day", "song_id", "play_count", "listened_seconds")
+        SELECT "user_id", ("played_at" AT TIME ZONE $2)::date, "song_id", 1, $3 FROM "play_event" WHERE "id" = $1
+        ON CONFLICT ("user_id", "day", "song_id") DO UPDATE SET
+            "play_count" = "listening_daily_song"."play_count" + 1,
+            "listened_seconds" = "listening_daily_song"."listened_seconds" + EXCLUDED."listened_seconds""#,
+        [event_id.into(), timezone.into(), listened_seconds.into()],
+    )).await?;
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"INSERT INTO "listening_daily_hour" ("user_id", "day", "hour", "play_count", "listened_seconds")
+        SELECT "user_id", ("played_at" AT TIME ZONE $2)::date, EXTRACT(HOUR FROM "played_at" AT TIME ZONE $2)::smallint, 1, $3
+        FROM "play_event" WHERE "id" = $1
+        ON CONFLICT ("user_id", "day", "hour") DO UPDATE SET
+            "play_count" = "listening_daily_hour"."play_count" + 1,
+            "listened_seconds" = "listening_daily_hour"."listened_seconds" + EXCLUDED."listened_seconds""#,
+        [event_id.into(), timezone.into(), listened_seconds.into()],
+    )).await?;
+    Ok(())
+}
+
+// SeaORM 实现的听歌统计仓库
+pub struct SeaOrmListeningStatRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmListeningStatRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+
+    // 日期范围内未删除歌曲的每日汇总
+    fn song_totals(user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Select<listening_daily_song::Entity> {
+        listening_daily_song::Entity::find()
+            .select_only()
+            .join(JoinType::InnerJoin, listening_daily_song::Relation::Song.def())
+            .filter(listening_daily_song::Column::UserId.eq(user_id))
+            .filter(listening_daily_song::Column::Day.between(from, to))
+            .filter(song::Column::DeleteFlag.eq(false))
+            .column_as(sum(listening_daily_song::Column::PlayCount), "play_count")
+            .column_as(sum(listening_daily_song::Column::ListenedSeconds), "listened_seconds")
+            .order_by_desc(sum(listening_daily_song::Column::PlayCount))
+            .order_by_desc(sum(listening_daily_song::Column::ListenedSeconds))
+    }
+
+    fn hour_totals(user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Select<listening_daily_hour::Entity> {
+        listening_daily_hour::Entity::find()
+            .select_only()
+            .filter(listening_daily_hour::Column::UserId.eq(user_id))
+            .filter(listening_daily_hour::Column::Day.between(from, to))
+    }
+
+    // 先按次数排序，再按分组键保证结果稳定
+    async fn top_by(&self, key: song::Column, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<Uuid>>, DbErr> {
+        Self::song_totals(user_id, from, to)
+            .column(key)
+            .group_by(key)
+            .order_by_asc(key)
+            .limit(limit)
+            .into_tuple::<(i64, i64, Uuid)>()
+            .all(&*self.db)
+            .await
+            .map(|rows| rows.into_iter().map(|(plays, seconds, id)| (id, plays, seconds)).collect())
+    }
+}
+
+// SUM结果为numeric，转为bigint
+fn sum<C: IntoColumnRef>(column: C) -> SimpleExpr {
+    Expr::expr(Expr::col(column).sum()).cast_as("bigint")
+}
+
+#[async_trait::async_trait]
+impl ListeningStatRepository for SeaOrmListeningStatRepository {
+    async fn rebuild(&self, user_id: Uuid, timezone: &str) -> Result<(), DbErr> {
+        let txn = self.db.begin().await?;
+        listening_daily_song::Entity::delete_many()
+            .filter(listening_daily_song::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        listening_daily_hour::Entity::delete_many()
+            .filter(listening_daily_hour::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"INSERT INTO "listening_daily_song" ("user_id", "day", "song_id", "play_count", "listened_seconds")
+            SELECT e."user_id", (e."played_at" AT TIME ZONE $2)::date, e."song_id", COUNT(*),
+                SUM(COALESCE(NULLIF(e."position_seconds", 0), s."duration"))
+            FROM "play_event" e JOIN "song" s ON s."id" = e."song_id"
+            WHERE e."user_id" = $1 AND e."event_type" = 'completed'
+            GROUP BY 1, 2, 3"#,
+            [user_id.into(), timezone.into()],
+        )).await?;
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"INSERT INTO "listening_daily_hour" ("user_id", "day", "hour", "play_count", "listened_seconds")
+            SELECT e."user_id", (e."played_at" AT TIME ZONE $2)::date, EXTRACT(HOUR FROM e."played_at" AT TIME ZONE $2)::smallint,
+                COUNT(*), SUM(COALESCE(NULLIF(e."position_seconds", 0), s."duration"))
+            FROM "play_event" e JOIN "song" s ON s."id" = e."song_id"
+            WHERE e."user_id" = $1 AND e."event_type" = 'completed'
+            GROUP BY 1, 2, 3"#,
+            [user_id.into(), timezone.into()],
+        )).await?;
+
+        txn.commit().await
+    }
+
+    async fn totals(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<(i64, i64), DbErr> {
+        let totals: Option<(Option<i64>, Option<i64>)> = Self::hour_totals(user_id, from, to)
+            .column_as(sum(listening_daily_hour::Column::PlayCount), "play_count")
+            .column_as(sum(listening_daily_hour::Column::ListenedSeconds), "listened_seconds")
+            .into_tuple()
+            .one(&*self.db)
+            .await?;
+        let (plays, seconds) = totals.unwrap_or_default();
+        Ok((plays.unwrap_or(0), seconds.unwrap_or(0)))
+    }
+
+    async fn top_songs(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<Uuid>>, DbErr> {
+        self.top_by(song::Column::Id, user_id, from, to, limit).await
+    }
+
+    async fn top_albums(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<Uuid>>, DbErr> {
+        self.top_by(song::Column::AlbumId, user_id, from, to, limit).await
+    }
+
+    async fn top_artists(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<Uuid>>, DbErr> {
+        self.top_by(song::Column::ArtistId, user_id, from, to, limit).await
+    }
+
+    async fn top_genres(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate, limit: u64) -> Result<Vec<ListeningTotal<String>>, DbErr> {
+        Self::song_totals(user_id, from, to)
+            .column(song::Column::Genre)
+            .filter(song::Column::Genre.is_not_null())
+            .filter(song::Column::Genre.ne(""))
+            .group_by(song::Column::Genre)
+            .order_by_asc(song::Column::Genre)
+            .limit(limit)
+            .into_tuple::<(i64, i64, String)>()
+            .all(&*self.db)
+            .await
+            .map(|rows| rows.into_iter().map(|(plays, seconds, genre)| (genre, plays, seconds)).collect())
+    }
+
+    async fn by_hour(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<ListeningTotal<i16>>, DbErr> {
+        Self::hour_totals(user_id, from, to)
+            .column(listening_daily_hour::Column::Hour)
+            .column_as(sum(listening_daily_hour::Column::PlayCount), "play_count")
+            .column_as(sum(listening_daily_hour::Column::ListenedSeconds), "listened_seconds")
+            .group_by(listening_daily_hour::Column::Hour)
+            .into_tuple()
+            .all(&*self.db)
+            .await
+    }
+
+    async fn by_weekday(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<ListeningTotal<i32>>, DbErr> {
+        let weekday = Expr::cust(r#"CAST(EXTRACT(ISODOW FROM "listening_daily_hour"."day") AS integer)"#);
+        Self::hour_totals(user_id, from, to)
+            .column_as(weekday.clone(), "weekday")
+            .column_as(sum(listening_daily_hour::Column::PlayCount), "play_count")
+            .column_as(sum(listening_daily_hour::Column::ListenedSeconds), "listened_seconds")
+            .group_by(weekday)
+            .into_tuple()
+            .all(&*self.db)
+            .await
+    }
+
+    async fn active_days(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<NaiveDate>, DbErr> {
+        Self::hour_totals(user_id, from, to)
+            .column(listening_daily_hour::Column::Day)
+            .distinct()
+            .order_by_asc(listening_daily_hour::Column::Day)
+            .into_tuple()
+            .all(&*self.db)
+            .await
+    }
+
+    async fn local_today(&self, timezone: &str) -> Result<NaiveDate, DbErr> {
+        let row = self.db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT (now() AT TIME ZONE $1)::date AS "today""#,
+                [timezone.into()],
+            ))
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("today".to_string()))?;
+        row.try_get("", "today")
+    }
+}
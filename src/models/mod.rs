@@ -10,4 +10,24 @@ pub mod album;
 pub use album::{ CreateAlbumData, AlbumQueryData};
 
 pub mod song;
-pub use song::{Song, CreateSongRequest, SongQueryParams};
\ No newline at end of file
+pub use song::{Song, CreateSongRequest, SongQueryParams, UpdateSongData};
+
+pub mod playlist;
+pub mod playlist_entry;
+pub mod smart_rule;
+pub mod star;
+pub mod rating;
+pub mod rating_summary;
+pub mod play_event;
+pub mod song_play_stat;
+pub mod listening_daily_song;
+pub mod listening_daily_hour;
+pub mod listening_stat;
+pub mod chart_entry;
+pub mod similarity;
+pub mod item_neighbor;
+pub mod play_queue;
+pub mod song_version;
+pub mod trash;
+pub mod credit;
+pub use playlist::{Playlist, CreatePlaylistData, UpdatePlaylistData, PlaylistQueryData};
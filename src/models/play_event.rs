@@ -0,0 +1,187 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, QuerySelect, TransactionTrait};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+use super::song::{self, Song};
+use super::song_play_stat::{self, SongPlayStat};
+use super::listening_stat;
+
+// 定义播放事件表实体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "play_event")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub user_id: Uuid,
+    pub song_id: Uuid,
+    // 事件类型：now_playing、completed、stream
+    pub event_type: String,
+    // 上报时的播放进度（秒）
+    pub position_seconds: Option<i32>,
+    // 播放时间，离线客户端补报时早于上报时间
+    pub played_at: DateTime<Local>,
+    pub created_at: DateTime<Local>,
+}
+
+// 定义关联
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::song::Entity", from = "Column::SongId", to = "super::song::Column::Id")]
+    Song,
+}
+
+impl Related<super::song::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Song.def()
+    }
+}
+
+impl sea_orm::ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        Self {
+            id: ActiveValue::Set(Uuid::now_v7()),
+            created_at: ActiveValue::Set(Local::now()),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
+
+// 重命名为PlayEvent以保持兼容性
+pub type PlayEvent = Model;
+
+// 播放事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayEventType {
+    // 开始播放
+    NowPlaying,
+    // 播放完成，计入播放次数
+    Completed,
+    // 开始拉取音频流，由服务端记录，只用于排行榜
+    #[serde(skip_deserializing)]
+    Stream,
+}
+
+impl PlayEventType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlayEventType::NowPlaying => "now_playing",
+            PlayEventType::Completed => "completed",
+            PlayEventType::Stream => "stream",
+        }
+    }
+}
+
+// 播放事件创建数据
+#[derive(Debug)]
+pub struct CreatePlayEventData {
+    pub user_id: Uuid,
+    pub song_id: Uuid,
+    pub event_type: PlayEventType,
+    pub position_seconds: Option<i32>,
+    pub played_at: DateTime<Local>,
+    // 用户时区，用于累加每日听歌汇总
+    pub timezone: String,
+    // 本次播放的收听时长（秒）
+    pub listened_seconds: i64,
+}
+
+// 定义播放事件仓库 trait
+#[async_trait::async_trait]
+pub trait PlayEventRepository: Send + Sync {
+    // 记录播放事件，重复上报的事件被忽略并返回false
+    async fn record(&self, data: &CreatePlayEventData) -> Result<bool, DbErr>;
+    async fn find_history(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(PlayEvent, Song)>, DbErr>;
+    async fn find_song_stats(&self, song_ids: &[Uuid]) -> Result<Vec<SongPlayStat>, DbErr>;
+}
+
+// SeaORM 实现的播放事件仓库
+pub struct SeaOrmPlayEventRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmPlayEventRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PlayEventRepository for SeaOrmPlayEventRepository {
+    async fn record(&self, data: &CreatePlayEventData) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+
+        let event_id = Uuid::now_v7();
+        let event = ActiveModel {
+            id: ActiveValue::Set(event_id),
+            user_id: ActiveValue::Set(data.user_id),
+            song_id: ActiveValue::Set(data.song_id),
+            event_type: ActiveValue::Set(data.event_type.as_str().to_string()),
+            position_seconds: ActiveValue::Set(data.position_seconds),
+            played_at: ActiveValue::Set(data.played_at),
+            ..ActiveModel::new()
+        };
+        let inserted = Entity::insert(event)
+            .on_conflict(
+                OnConflict::columns([Column::UserId, Column::SongId, Column::EventType, Column::PlayedAt])
+                    .do_nothing()
+                    .to_owned()
+            )
+            .exec_without_returning(&txn)
+            .await? > 0;
+
+        // 播放完成时累加播放次数和每日汇总，补报的旧事件不会覆盖更晚的最近播放时间
+        if inserted && data.event_type == PlayEventType::Completed {
+            let stat = song_play_stat::ActiveModel {
+                song_id: ActiveValue::Set(data.song_id),
+                play_count: ActiveValue::Set(1),
+                last_played_at: ActiveValue::Set(data.played_at),
+            };
+            song_play_stat::Entity::insert(stat)
+                .on_conflict(
+                    OnConflict::column(song_play_stat::Column::SongId)
+                        .value(song_play_stat::Column::PlayCount, song_play_stat::play_count().add(1))
+                        .value(
+                            song_play_stat::Column::LastPlayedAt,
+                            Expr::cust("GREATEST(\"song_play_stat\".\"last_played_at\", \"excluded\".\"last_played_at\")"),
+                        )
+                        .to_owned()
+                )
+                .exec_without_returning(&txn)
+                .await?;
+
+            listening_stat::add_play(&txn, event_id, &data.timezone, data.listened_seconds).await?;
+        }
+
+        txn.commit().await?;
+        Ok(inserted)
+    }
+
+    // 播放历史只包含播放完成的事件，不返回已删除的歌曲
+    async fn find_history(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(PlayEvent, Song)>, DbErr> {
+        let events = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::EventType.eq(PlayEventType::Completed.as_str()))
+            .find_also_related(song::Entity)
+            .filter(song::Column::DeleteFlag.eq(false))
+            .order_by_desc(Column::PlayedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.db)
+            .await?;
+        Ok(events.into_iter().filter_map(|(event, song)| song.map(|song| (event, song))).collect())
+    }
+
+    async fn find_song_stats(&self, song_ids: &[Uuid]) -> Result<Vec<SongPlayStat>, DbErr> {
+        song_play_stat::Entity::find()
+            .filter(song_play_stat::Column::SongId.is_in(song_ids.iter().copied()))
+            .all(&*self.db)
+            .await
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QuerySelect, TransactionTrait};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+
+// 定义播放队列表实体，每个用户一个队列，多设备共享
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "play_queue")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: Uuid,
+    // 有序的歌曲ID数组
+    pub song_ids: Json,
+    // 当前播放的歌曲在队列中的下标
+    pub current_index: i32,
+    // 当前歌曲的播放进度（毫秒）
+    pub position_ms: i64,
+    // 最后保存队列的客户端名称
+    pub client_name: Option<String>,
+    // 每次保存加1，用于检测基于旧版本的保存
+    pub version: i64,
+    pub updated_at: DateTime<Local>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// 重命名为PlayQueue以保持兼容性
+pub type PlayQueue = Model;
+
+impl Model {
+    pub fn song_ids(&self) -> Vec<Uuid> {
+        serde_json::from_value(self.song_ids.clone()).unwrap_or_default()
+    }
+}
+
+// 保存播放队列的数据
+#[derive(Debug)]
+pub struct SavePlayQueueData {
+    pub user_id: Uuid,
+    pub song_ids: Vec<Uuid>,
+    pub current_index: i32,
+    pub position_ms: i64,
+    pub client_name: Option<String>,
+    // 客户端所基于的版本，为空时直接覆盖（后写入者胜出）；尚无队列时为0
+    pub base_version: Option<i64>,
+}
+
+// 保存结果
+#[derive(Debug)]
+pub enum SavePlayQueueResult {
+    Saved(PlayQueue),
+    // 队列已被其他设备更新，附带当前队列
+    Conflict(Option<PlayQueue>),
+}
+
+// 定义播放队列仓库 trait
+#[async_trait::async_trait]
+pub trait PlayQueueRepository: Send + Sync {
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Option<PlayQueue>, DbErr>;
+    async fn save(&self, data: &SavePlayQueueData) -> Result<SavePlayQueueResult, DbErr>;
+}
+
+// SeaORM 实现的播放队列仓库
+pub struct SeaOrmPlayQueueRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmPlayQueueRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PlayQueueRepository for SeaOrmPlayQueueRepository {
+    async fn find_by_user(&self, user_id: Uuid) -> Result<Option<PlayQueue>, DbErr> {
+        Entity::find_by_id(user_id).one(&*self.db).await
+    }
+
+    // 锁定当前队列后比较版本，保证并发保存时只有一个基于同一版本的保存成功
+    async fn save(&self, data: &SavePlayQueueData) -> Result<SavePlayQueueResult, DbErr> {
+        let txn = self.db.begin().await?;
+        let current = Entity::find_by_id(data.user_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?;
+
+        let current_version = current.as_ref().map(|queue| queue.version).unwrap_or(0);
+        if let Some(base_version) = data.base_version
+            && base_version != current_version
+        {
+            return Ok(SavePlayQueueResult::Conflict(current));
+        }
+
+        let queue = ActiveModel {
+            user_id: ActiveValue::Set(data.user_id),
+            song_ids: ActiveValue::Set(serde_json::json!(data.song_ids)),
+            current_index: ActiveValue::Set(data.current_index),
+            position_ms: ActiveValue::Set(data.position_ms),
+            client_name: ActiveValue::Set(data.client_name.clone()),
+            version: ActiveValue::Set(current_version + 1),
+            updated_at: ActiveValue::Set(Local::now()),
+        };
+        let saved = match current {
+            Some(_) => queue.update(&txn).await?,
+            None => {
+                // 两个设备同时创建队列时，直接覆盖的保存更新对方的队列，基于版本的保存按冲突处理
+                let mut on_conflict = OnConflict::column(Column::UserId);
+                match data.base_version {
+                    None => on_conflict
+                        .update_columns([Column::SongIds, Column::CurrentIndex, Column::PositionMs, Column::ClientName, Column::UpdatedAt])
+                        .value(Column::Version, Expr::col((Entity, Column::Version)).add(1)),
+                    Some(_) => on_conflict.do_nothing(),
+                };
+                let inserted = Entity::insert(queue)
+                    .on_conflict(on_conflict)
+                    .exec_without_returning(&txn)
+                    .await? > 0;
+                if !inserted {
+                    txn.rollback().await?;
+                    return Ok(SavePlayQueueResult::Conflict(self.find_by_user(data.user_id).await?));
+                }
+                Entity::find_by_id(data.user_id)
+                    .one(&txn)
+                    .await?
+                    .ok_or_else(|| DbErr::RecordNotFound("play_queue".to_string()))?
+            }
+        };
+
+        txn.commit().await?;
+        Ok(SavePlayQueueResult::Saved(saved))
+    }
+}
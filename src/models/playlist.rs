@@ -0,0 +1,441 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, Condition, DatabaseConnection, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, QuerySelect, TransactionTrait};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Func;
+use uuid::Uuid;
+use std::sync::Arc;
+use super::playlist_entry::{self, PlaylistEntry};
+use super::song::{self, Song};
+
+// 定义歌单表实体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "playlist")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub owner_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub public: bool,
+    // 智能歌单的规则树，为空表示普通歌单
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub rules: Option<Json>,
+    #[sea_orm(indexed)]
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+    pub created_by: String,
+    pub updated_by: String,
+    #[sea_orm(indexed,default=false)]
+    pub delete_flag: bool,
+}
+
+// 定义关联
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    // 关联用户表
+    #[sea_orm(belongs_to = "super::user::Entity", from = "Column::OwnerId", to = "super::user::Column::Id")]
+    Owner,
+}
+
+impl sea_orm::ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        Self {
+            id: ActiveValue::Set(Uuid::now_v7()),
+            created_at: ActiveValue::Set(Local::now()),
+            updated_at: ActiveValue::Set(Local::now()),
+            delete_flag: ActiveValue::Set(false),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
+
+// 重命名为Playlist以保持兼容性
+pub type Playlist = Model;
+
+// 定义歌单仓库 trait
+#[async_trait::async_trait]
+pub trait PlaylistRepository: Send + Sync {
+    async fn create(&self, data: &CreatePlaylistData) -> Result<Playlist, DbErr>;
+    // 在同一事务中创建歌单并按顺序添加歌曲
+    async fn create_with_entries(&self, data: &CreatePlaylistData, song_ids: &[Uuid]) -> Result<Playlist, DbErr>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Playlist>, DbErr>;
+    async fn find_all(&self, params: &PlaylistQueryData) -> Result<Vec<Playlist>, DbErr>;
+    async fn update(&self, id: Uuid, data: &UpdatePlaylistData) -> Result<Playlist, DbErr>;
+    async fn delete(&self, id: Uuid, deleted_by: &str) -> Result<(), DbErr>;
+    async fn find_entries(&self, playlist_id: Uuid) -> Result<Vec<(PlaylistEntry, Song)>, DbErr>;
+    async fn summaries(&self, playlist_ids: &[Uuid]) -> Result<Vec<(Uuid, i64, i64)>, DbErr>;
+    async fn add_entries(&self, playlist_id: Uuid, song_ids: &[Uuid], position: Option<i32>, created_by: &str) -> Result<(), DbErr>;
+    async fn remove_entry(&self, playlist_id: Uuid, entry_id: Uuid, updated_by: &str) -> Result<bool, DbErr>;
+    async fn reorder_entries(&self, playlist_id: Uuid, entry_ids: &[Uuid], updated_by: &str) -> Result<bool, DbErr>;
+}
+
+// 创建歌单数据
+#[derive(Debug)]
+pub struct CreatePlaylistData {
+    pub owner_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub public: bool,
+    pub rules: Option<Json>,
+    pub created_by: String,
+}
+
+// 更新歌单数据（仅更新有值的字段）
+#[derive(Debug, Default)]
+pub struct UpdatePlaylistData {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub rules: Option<Json>,
+    pub updated_by: String,
+}
+
+// 歌单查询参数
+#[derive(Debug)]
+pub struct PlaylistQueryData {
+    // 当前用户，只返回其拥有的歌单和公开歌单
+    pub viewer_id: Uuid,
+    pub owner_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub public: Option<bool>,
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+// SeaORM 实现的歌单仓库
+pub struct SeaOrmPlaylistRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmPlaylistRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+async fn insert_playlist<C: ConnectionTrait>(db: &C, data: &CreatePlaylistData) -> Result<Playlist, DbErr> {
+    let playlist = ActiveModel {
+        owner_id: ActiveValue::Set(data.owner_id),
+        name: ActiveValue::Set(data.name.clone()),
+        description: ActiveValue::Set(data.description.clone()),
+        public: ActiveValue::Set(data.public),
+        rules: ActiveValue::Set(data.rules.clone()),
+        created_by: ActiveValue::Set(data.created_by.clone()),
+        updated_by: ActiveValue::Set(data.created_by.clone()),
+        ..ActiveModel::new()
+    };
+
+    playlist.insert(db).await
+}
+
+// 锁定歌单行，串行化同一歌单的条目修改
+async fn lock_playlist<C: ConnectionTrait>(db: &C, playlist_id: Uuid) -> Result<Option<Playlist>, DbErr> {
+    Entity::find_by_id(playlist_id)
+        .filter(Column::DeleteFlag.eq(false))
+        .lock_exclusive()
+        .one(db)
+        .await
+}
+
+async fn touch_playlist<C: ConnectionTrait>(db: &C, playlist_id: Uuid, updated_by: &str) -> Result<(), DbErr> {
+    Entity::update_many()
+        .col_expr(Column::UpdatedAt, Expr::value(Local::now()))
+        .col_expr(Column::UpdatedBy, Expr::value(updated_by))
+        .filter(Column::Id.eq(playlist_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+// 按给定顺序重新写入条目位置
+async fn write_positions<C: ConnectionTrait>(db: &C, entry_ids: &[Uuid]) -> Result<(), DbErr> {
+    for (position, entry_id) in entry_ids.iter().enumerate() {
+        playlist_entry::Entity::update_many()
+            .col_expr(playlist_entry::Column::Position, Expr::value(position as i32))
+            .filter(playlist_entry::Column::Id.eq(*entry_id))
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn entry_ids<C: ConnectionTrait>(db: &C, playlist_id: Uuid) -> Result<Vec<Uuid>, DbErr> {
+    playlist_entry::Entity::find()
+        .select_only()
+        .column(playlist_entry::Column::Id)
+        .filter(playlist_entry::Column::PlaylistId.eq(playlist_id))
+        .order_by_asc(playlist_entry::Column::Position)
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+// 条目ID及其歌曲是否可见（歌曲未被删除），按当前位置排序
+async fn entry_visibility<C: ConnectionTrait>(db: &C, playlist_id: Uuid) -> Result<Vec<(Uuid, bool)>, DbErr> {
+    let rows: Vec<(Uuid, Option<bool>)> = playlist_entry::Entity::find()
+        .select_only()
+        .column(playlist_entry::Column::Id)
+        .column(song::Column::DeleteFlag)
+        .join(sea_orm::JoinType::LeftJoin, playlist_entry::Relation::Song.def())
+        .filter(playlist_entry::Column::PlaylistId.eq(playlist_id))
+        .order_by_asc(playlist_entry::Column::Position)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(rows.into_iter().map(|(id, deleted)| (id, deleted == Some(false))).collect())
+}
+
+// 根据请求的可见条目顺序计算全部条目的新顺序：请求必须恰好包含全部可见条目，
+// 不可见的条目（歌曲已删除）保持原有相对顺序排在其后
+fn merge_visible_order(entries: &[(Uuid, bool)], requested: &[Uuid]) -> Option<Vec<Uuid>> {
+    let mut visible: Vec<Uuid> = entries.iter().filter(|(_, visible)| *visible).map(|(id, _)| *id).collect();
+    let mut sorted = requested.to_vec();
+    visible.sort();
+    sorted.sort();
+    if visible != sorted {
+        return None;
+    }
+
+    let mut order = requested.to_vec();
+    order.extend(entries.iter().filter(|(_, visible)| !*visible).map(|(id, _)| *id));
+    Some(order)
+}
+
+#[async_trait::async_trait]
+impl PlaylistRepository for SeaOrmPlaylistRepository {
+    async fn create(&self, data: &CreatePlaylistData) -> Result<Playlist, DbErr> {
+        insert_playlist(&*self.db, data).await
+    }
+
+    async fn create_with_entries(&self, data: &CreatePlaylistData, song_ids: &[Uuid]) -> Result<Playlist, DbErr> {
+        let txn = self.db.begin().await?;
+        let playlist = insert_playlist(&txn, data).await?;
+        for (position, song_id) in song_ids.iter().enumerate() {
+            playlist_entry::ActiveModel {
+                playlist_id: ActiveValue::Set(playlist.id),
+                song_id: ActiveValue::Set(*song_id),
+                position: ActiveValue::Set(position as i32),
+                created_by: ActiveValue::Set(data.created_by.clone()),
+                ..playlist_entry::ActiveModel::new()
+            }
+            .insert(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+        Ok(playlist)
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Playlist>, DbErr> {
+        Entity::find_by_id(id)
+            .filter(Column::DeleteFlag.eq(false))
+            .one(&*self.db)
+            .await
+    }
+
+    async fn find_all(&self, params: &PlaylistQueryData) -> Result<Vec<Playlist>, DbErr> {
+        let mut query = Entity::find()
+            .filter(Column::DeleteFlag.eq(false))
+            .filter(
+                Condition::any()
+                    .add(Column::OwnerId.eq(params.viewer_id))
+                    .add(Column::Public.eq(true))
+            )
+            .order_by_desc(Column::UpdatedAt);
+
+        // 添加筛选条件
+        if let Some(owner_id) = params.owner_id {
+            query = query.filter(Column::OwnerId.eq(owner_id));
+        }
+        if let Some(name) = &params.name {
+            query = query.filter(Expr::expr(Func::lower(Expr::col(Column::Name))).like(format!("%{}%", name.to_lowercase())));
+        }
+        if let Some(public) = params.public {
+            query = query.filter(Column::Public.eq(public));
+        }
+
+        // 处理分页
+        let page = params.page.unwrap_or(1).max(1);
+        let page_size = params.page_size.unwrap_or(20);
+        query
+            .offset((page - 1) * page_size)
+            .limit(page_size)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn update(&self, id: Uuid, data: &UpdatePlaylistData) -> Result<Playlist, DbErr> {
+        let mut playlist = ActiveModel {
+            id: ActiveValue::Unchanged(id),
+            updated_at: ActiveValue::Set(Local::now()),
+            updated_by: ActiveValue::Set(data.updated_by.clone()),
+            ..Default::default()
+        };
+        if let Some(name) = &data.name {
+            playlist.name = ActiveValue::Set(name.clone());
+        }
+        if let Some(description) = &data.description {
+            playlist.description = ActiveValue::Set(Some(description.clone()));
+        }
+        if let Some(public) = data.public {
+            playlist.public = ActiveValue::Set(public);
+        }
+        if let Some(rules) = &data.rules {
+            playlist.rules = ActiveValue::Set(Some(rules.clone()));
+        }
+
+        playlist.update(&*self.db).await
+    }
+
+    async fn delete(&self, id: Uuid, deleted_by: &str) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::DeleteFlag, Expr::value(true))
+            .col_expr(Column::UpdatedAt, Expr::value(Local::now()))
+            .col_expr(Column::UpdatedBy, Expr::value(deleted_by))
+            .filter(Column::Id.eq(id))
+            .exec(&*self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn find_entries(&self, playlist_id: Uuid) -> Result<Vec<(PlaylistEntry, Song)>, DbErr> {
+        // 已删除的歌曲保留条目但不返回
+        let entries = playlist_entry::Entity::find()
+            .filter(playlist_entry::Column::PlaylistId.eq(playlist_id))
+            .find_also_related(song::Entity)
+            .filter(song::Column::DeleteFlag.eq(false))
+            .order_by_asc(playlist_entry::Column::Position)
+            .all(&*self.db)
+            .await?;
+
+        Ok(entries.into_iter()
+            .filter_map(|(entry, song)| song.map(|song| (entry, song)))
+            .collect())
+    }
+
+    async fn summaries(&self, playlist_ids: &[Uuid]) -> Result<Vec<(Uuid, i64, i64)>, DbErr> {
+        // 返回 (歌单ID, 歌曲数, 总时长)，不统计已删除的歌曲
+        playlist_entry::Entity::find()
+            .select_only()
+            .column(playlist_entry::Column::PlaylistId)
+            .column_as(playlist_entry::Column::Id.count(), "song_count")
+            .column_as(Expr::expr(Func::coalesce([Expr::col((song::Entity, song::Column::Duration)).sum(), Expr::val(0).into()])).cast_as("bigint"), "duration")
+            .join(sea_orm::JoinType::InnerJoin, playlist_entry::Relation::Song.def())
+            .filter(playlist_entry::Column::PlaylistId.is_in(playlist_ids.iter().copied()))
+            .filter(song::Column::DeleteFlag.eq(false))
+            .group_by(playlist_entry::Column::PlaylistId)
+            .into_tuple()
+            .all(&*self.db)
+            .await
+    }
+
+    async fn add_entries(&self, playlist_id: Uuid, song_ids: &[Uuid], position: Option<i32>, created_by: &str) -> Result<(), DbErr> {
+        let txn = self.db.begin().await?;
+        if lock_playlist(&txn, playlist_id).await?.is_none() {
+            return Err(DbErr::RecordNotFound(format!("playlist {}", playlist_id)));
+        }
+
+        // 插入位置按可见条目计数，与列表接口返回的顺序一致
+        let entries = entry_visibility(&txn, playlist_id).await?;
+        let insert_at = position
+            .and_then(|position| {
+                entries.iter()
+                    .enumerate()
+                    .filter(|(_, (_, visible))| *visible)
+                    .nth(position.max(0) as usize)
+                    .map(|(index, _)| index)
+            })
+            .unwrap_or(entries.len());
+        let mut ids: Vec<Uuid> = entries.into_iter().map(|(id, _)| id).collect();
+
+        let mut new_ids = Vec::with_capacity(song_ids.len());
+        for song_id in song_ids {
+            let entry = playlist_entry::ActiveModel {
+                playlist_id: ActiveValue::Set(playlist_id),
+                song_id: ActiveValue::Set(*song_id),
+                position: ActiveValue::Set(0),
+                created_by: ActiveValue::Set(created_by.to_string()),
+                ..playlist_entry::ActiveModel::new()
+            };
+            new_ids.push(entry.insert(&txn).await?.id);
+        }
+        ids.splice(insert_at..insert_at, new_ids);
+
+        write_positions(&txn, &ids).await?;
+        touch_playlist(&txn, playlist_id, created_by).await?;
+        txn.commit().await
+    }
+
+    async fn remove_entry(&self, playlist_id: Uuid, entry_id: Uuid, updated_by: &str) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+        if lock_playlist(&txn, playlist_id).await?.is_none() {
+            return Ok(false);
+        }
+
+        let result = playlist_entry::Entity::delete_many()
+            .filter(playlist_entry::Column::Id.eq(entry_id))
+            .filter(playlist_entry::Column::PlaylistId.eq(playlist_id))
+            .exec(&txn)
+            .await?;
+        if result.rows_affected == 0 {
+            return Ok(false);
+        }
+
+        // 删除后重新连续编号
+        let ids = entry_ids(&txn, playlist_id).await?;
+        write_positions(&txn, &ids).await?;
+        touch_playlist(&txn, playlist_id, updated_by).await?;
+        txn.commit().await?;
+        Ok(true)
+    }
+
+    async fn reorder_entries(&self, playlist_id: Uuid, entry_ids_in_order: &[Uuid], updated_by: &str) -> Result<bool, DbErr> {
+        let txn = self.db.begin().await?;
+        if lock_playlist(&txn, playlist_id).await?.is_none() {
+            return Ok(false);
+        }
+
+        // 新顺序必须恰好包含歌单中可见的全部条目
+        let entries = entry_visibility(&txn, playlist_id).await?;
+        let Some(order) = merge_visible_order(&entries, entry_ids_in_order) else {
+            return Ok(false);
+        };
+
+        write_positions(&txn, &order).await?;
+        touch_playlist(&txn, playlist_id, updated_by).await?;
+        txn.commit().await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<Uuid> {
+        (0..n).map(|_| Uuid::now_v7()).collect()
+    }
+
+    #[test]
+    fn merge_visible_order_keeps_hidden_entries_last() {
+        let id = ids(4);
+        let entries = vec![(id[0], true), (id[1], false), (id[2], true), (id[3], true)];
+
+        let order = merge_visible_order(&entries, &[id[3], id[0], id[2]]);
+
+        assert_eq!(order, Some(vec![id[3], id[0], id[2], id[1]]));
+    }
+
+    #[test]
+    fn merge_visible_order_rejects_hidden_or_missing_entries() {
+        let id = ids(3);
+        let entries = vec![(id[0], true), (id[1], false), (id[2], true)];
+
+        assert_eq!(merge_visible_order(&entries, &[id[2], id[1], id[0]]), None);
+        assert_eq!(merge_visible_order(&entries, &[id[2]]), None);
+        assert_eq!(merge_visible_order(&entries, &[id[2], id[0], id[0]]), None);
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveModelTrait, ActiveValue, DeriveEntityModel, DeriveRelation, EnumIter};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+// 定义歌单条目表实体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "playlist_entry")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub playlist_id: Uuid,
+    #[sea_orm(indexed)]
+    pub song_id: Uuid,
+    // 歌单内顺序，从0开始连续编号
+    pub position: i32,
+    pub created_at: DateTime<Local>,
+    pub created_by: String,
+}
+
+// 定义关联
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    // 关联歌单表
+    #[sea_orm(belongs_to = "super::playlist::Entity", from = "Column::PlaylistId", to = "super::playlist::Column::Id")]
+    Playlist,
+    // 关联歌曲表
+    #[sea_orm(belongs_to = "super::song::Entity", from = "Column::SongId", to = "super::song::Column::Id")]
+    Song,
+}
+
+impl Related<super::song::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Song.def()
+    }
+}
+
+impl sea_orm::ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        Self {
+            id: ActiveValue::Set(Uuid::now_v7()),
+            created_at: ActiveValue::Set(Local::now()),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
+
+// 重命名为PlaylistEntry以保持兼容性
+pub type PlaylistEntry = Model;
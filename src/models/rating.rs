@@ -0,0 +1,192 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QuerySelect, TransactionTrait};
+use sea_orm::sea_query::{Func, OnConflict};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+use super::rating_summary::{self, RatingSummary};
+
+// 定义评分表实体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "rating")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub user_id: Uuid,
+    // 评分对象类型：song、album
+    pub item_type: String,
+    pub item_id: Uuid,
+    // 1-5星
+    pub rating: i16,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl sea_orm::ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        Self {
+            id: ActiveValue::Set(Uuid::now_v7()),
+            created_at: ActiveValue::Set(Local::now()),
+            updated_at: ActiveValue::Set(Local::now()),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
+
+// 重命名为Rating以保持兼容性
+pub type Rating = Model;
+
+// 评分取值范围
+pub const MIN_RATING: i16 = 1;
+pub const MAX_RATING: i16 = 5;
+
+// 评分对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RatingItemType {
+    Song,
+    Album,
+}
+
+impl RatingItemType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RatingItemType::Song => "song",
+            RatingItemType::Album => "album",
+        }
+    }
+
+    // 同时接受单数和复数形式（路由中使用songs/albums）
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "song" | "songs" => Some(RatingItemType::Song),
+            "album" | "albums" => Some(RatingItemType::Album),
+            _ => None,
+        }
+    }
+}
+
+// 定义评分仓库 trait
+#[async_trait::async_trait]
+pub trait RatingRepository: Send + Sync {
+    // 设置或清除（rating为None）用户评分，返回更新后的汇总
+    async fn rate(&self, user_id: Uuid, item_type: RatingItemType, item_id: Uuid, rating: Option<i16>) -> Result<RatingSummary, DbErr>;
+    async fn find_by_items(&self, user_id: Uuid, item_type: RatingItemType, item_ids: &[Uuid]) -> Result<Vec<Rating>, DbErr>;
+    async fn find_summaries(&self, item_type: RatingItemType, item_ids: &[Uuid]) -> Result<Vec<RatingSummary>, DbErr>;
+}
+
+// SeaORM 实现的评分仓库
+pub struct SeaOrmRatingRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmRatingRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RatingRepository for SeaOrmRatingRepository {
+    async fn rate(&self, user_id: Uuid, item_type: RatingItemType, item_id: Uuid, rating: Option<i16>) -> Result<RatingSummary, DbErr> {
+        let txn = self.db.begin().await?;
+
+        // 先锁定汇总行，同一对象的评分修改按顺序执行，汇总不会丢失更新
+        let summary = rating_summary::ActiveModel {
+            item_type: ActiveValue::Set(item_type.as_str().to_string()),
+            item_id: ActiveValue::Set(item_id),
+            rating_count: ActiveValue::Set(0),
+            rating_sum: ActiveValue::Set(0),
+            average_rating: ActiveValue::Set(None),
+            updated_at: ActiveValue::Set(Local::now()),
+        };
+        rating_summary::Entity::insert(summary)
+            .on_conflict(
+                OnConflict::columns([rating_summary::Column::ItemType, rating_summary::Column::ItemId])
+                    .do_nothing()
+                    .to_owned()
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        rating_summary::Entity::find_by_id((item_type.as_str().to_string(), item_id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?;
+
+        match rating {
+            Some(rating) => {
+                let model = ActiveModel {
+                    user_id: ActiveValue::Set(user_id),
+                    item_type: ActiveValue::Set(item_type.as_str().to_string()),
+                    item_id: ActiveValue::Set(item_id),
+                    rating: ActiveValue::Set(rating),
+                    ..ActiveModel::new()
+                };
+                Entity::insert(model)
+                    .on_conflict(
+                        OnConflict::columns([Column::UserId, Column::ItemType, Column::ItemId])
+                            .update_columns([Column::Rating, Column::UpdatedAt])
+                            .to_owned()
+                    )
+                    .exec_without_returning(&txn)
+                    .await?;
+            }
+            None => {
+                Entity::delete_many()
+                    .filter(Column::UserId.eq(user_id))
+                    .filter(Column::ItemType.eq(item_type.as_str()))
+                    .filter(Column::ItemId.eq(item_id))
+                    .exec(&txn)
+                    .await?;
+            }
+        }
+
+        // 持有锁时按评分表重新计算汇总
+        let (rating_count, rating_sum): (i64, i64) = Entity::find()
+            .select_only()
+            .column_as(Column::Id.count(), "rating_count")
+            .column_as(Expr::expr(Func::coalesce([Expr::col(Column::Rating).sum(), Expr::val(0).into()])).cast_as("bigint"), "rating_sum")
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.eq(item_id))
+            .into_tuple()
+            .one(&txn)
+            .await?
+            .unwrap_or_default();
+        let summary = rating_summary::ActiveModel {
+            item_type: ActiveValue::Unchanged(item_type.as_str().to_string()),
+            item_id: ActiveValue::Unchanged(item_id),
+            rating_count: ActiveValue::Set(rating_count),
+            rating_sum: ActiveValue::Set(rating_sum),
+            average_rating: ActiveValue::Set((rating_count > 0).then(|| rating_sum as f64 / rating_count as f64)),
+            updated_at: ActiveValue::Set(Local::now()),
+        }
+        .update(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok(summary)
+    }
+
+    async fn find_by_items(&self, user_id: Uuid, item_type: RatingItemType, item_ids: &[Uuid]) -> Result<Vec<Rating>, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.is_in(item_ids.iter().copied()))
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_summaries(&self, item_type: RatingItemType, item_ids: &[Uuid]) -> Result<Vec<RatingSummary>, DbErr> {
+        rating_summary::Entity::find()
+            .filter(rating_summary::Column::ItemType.eq(item_type.as_str()))
+            .filter(rating_summary::Column::ItemId.is_in(item_ids.iter().copied()))
+            .all(&*self.db)
+            .await
+    }
+}
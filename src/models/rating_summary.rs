@@ -0,0 +1,55 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{DeriveEntityModel, DeriveRelation, EnumIter, JoinType, QuerySelect, Select};
+use sea_orm::sea_query::IntoCondition;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use super::rating::RatingItemType;
+
+// 定义评分汇总表实体，每个评分对象一行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "rating_summary")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_type: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub item_id: Uuid,
+    pub rating_count: i64,
+    pub rating_sum: i64,
+    // 没有评分时为空
+    pub average_rating: Option<f64>,
+    pub updated_at: DateTime<Local>,
+}
+
+// 定义关联（item_id按item_type指向不同的表）
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::song::Entity", from = "Column::ItemId", to = "super::song::Column::Id")]
+    Song,
+    #[sea_orm(belongs_to = "super::album::Entity", from = "Column::ItemId", to = "super::album::Column::Id")]
+    Album,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// 重命名为RatingSummary以保持兼容性
+pub type RatingSummary = Model;
+
+// 为歌曲或专辑查询左连接评分汇总，之后可使用average_rating列筛选和排序
+pub fn join_summary<E: EntityTrait>(query: Select<E>, item_type: RatingItemType) -> Select<E> {
+    let relation = match item_type {
+        RatingItemType::Song => Relation::Song.def(),
+        RatingItemType::Album => Relation::Album.def(),
+    };
+    query.join(
+        JoinType::LeftJoin,
+        relation.rev().on_condition(move |_, _| {
+            Expr::col((Entity, Column::ItemType)).eq(item_type.as_str()).into_condition()
+        }),
+    )
+}
+
+// 平均评分列（带表名，避免与其他表的同名列冲突）
+pub fn average_rating() -> Expr {
+    Expr::col((Entity, Column::AverageRating))
+}
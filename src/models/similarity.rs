@@ -0,0 +1,190 @@
+use chrono::{DateTime, Local};
+use sea_orm::{ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, JoinType, Order, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Select, Statement};
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use uuid::Uuid;
+use std::sync::Arc;
+use super::play_event::{self, PlayEventType};
+use super::song;
+
+// 相似度计算使用的歌曲特征
+#[derive(Debug, Clone)]
+pub struct SongFeatures {
+    pub id: Uuid,
+    pub artist_id: Uuid,
+    // 小写流派，歌曲没有流派时使用专辑的流派
+    pub genre: Option<String>,
+    // 所属专辑的发行年份
+    pub year: i32,
+}
+
+// 电台种子类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedType {
+    Song,
+    Album,
+    Artist,
+}
+
+impl SeedType {
+    // 同时接受单数和复数形式（路由中使用songs/albums/artists）
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "song" | "songs" => Some(SeedType::Song),
+            "album" | "albums" => Some(SeedType::Album),
+            "artist" | "artists" => Some(SeedType::Artist),
+            _ => None,
+        }
+    }
+
+    fn column(self) -> song::Column {
+        match self {
+            SeedType::Song => song::Column::Id,
+            SeedType::Album => song::Column::AlbumId,
+            SeedType::Artist => song::Column::ArtistId,
+        }
+    }
+}
+
+// 定义相似歌曲仓库 trait
+#[async_trait::async_trait]
+pub trait SimilarityRepository: Send + Sync {
+    // 种子（歌曲、专辑或歌手）包含的未删除歌曲
+    async fn find_seed_features(&self, seed_type: SeedType, seed_id: Uuid) -> Result<Vec<SongFeatures>, DbErr>;
+    // 候选歌曲：流派或歌手相同，或在听歌会话中一起出现过的未删除歌曲
+    async fn find_candidates(
+        &self,
+        genres: &[String],
+        artist_ids: &[Uuid],
+        song_ids: &[Uuid],
+        limit: u64,
+    ) -> Result<Vec<SongFeatures>, DbErr>;
+    // 与种子歌曲出现在同一听歌会话中的歌曲及会话数，按会话数倒序
+    async fn find_co_listened(&self, song_ids: &[Uuid], limit: u64) -> Result<Vec<(Uuid, i64)>, DbErr>;
+    // 用户在指定时间之后播放完成的歌曲
+    async fn find_recently_played(&self, user_id: Uuid, since: DateTime<Local>) -> Result<Vec<Uuid>, DbErr>;
+}
+
+// SeaORM 实现的相似歌曲仓库
+pub struct SeaOrmSimilarityRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmSimilarityRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+
+    fn features() -> Select<song::Entity> {
+        song::Entity::find()
+            .select_only()
+            .column(song::Column::Id)
+            .column(song::Column::ArtistId)
+            .column_as(genre(), "genre")
+            .column_as(Expr::cust(r#"CAST(EXTRACT(YEAR FROM "album"."release_date") AS integer)"#), "year")
+            .join(JoinType::InnerJoin, song::Relation::Album.def())
+            .filter(song::Column::DeleteFlag.eq(false))
+    }
+
+    async fn all_features(&self, query: Select<song::Entity>) -> Result<Vec<SongFeatures>, DbErr> {
+        let rows: Vec<(Uuid, Uuid, Option<String>, i32)> = query.into_tuple().all(&*self.db).await?;
+        Ok(rows.into_iter()
+            .map(|(id, artist_id, genre, year)| SongFeatures { id, artist_id, genre, year })
+            .collect())
+    }
+}
+
+fn genre() -> SimpleExpr {
+    Expr::cust(r#"NULLIF(LOWER(BTRIM(COALESCE("song"."genre", "album"."genre"))), '')"#)
+}
+
+// 听歌会话：同一用户相邻两次播放完成间隔不超过30分钟
+const CO_LISTENED_SQL: &str = r#"WITH seeds AS (
+        SELECT CAST("id" AS uuid) AS "song_id" FROM UNNEST(string_to_array($1, ',')) AS t("id")
+    ),
+    ordered AS (
+        SELECT "user_id", "song_id", "played_at",
+            CASE WHEN "played_at" - LAG("played_at") OVER (PARTITION BY "user_id" ORDER BY "played_at") <= INTERVAL '30 minutes'
+                THEN 0 ELSE 1 END AS "new_session"
+        FROM "play_event"
+        WHERE "event_type" = 'completed' AND "user_id" IN (
+            SELECT DISTINCT "user_id" FROM "play_event"
+            WHERE "event_type" = 'completed' AND "song_id" IN (SELECT "song_id" FROM seeds)
+        )
+    ),
+    sessions AS (
+        SELECT "user_id", "song_id", SUM("new_session") OVER (PARTITION BY "user_id" ORDER BY "played_at") AS "session"
+        FROM ordered
+    ),
+    seed_sessions AS (
+        SELECT DISTINCT "user_id", "session" FROM sessions WHERE "song_id" IN (SELECT "song_id" FROM seeds)
+    )
+    SELECT s."song_id", COUNT(DISTINCT (s."user_id", s."session")) AS "sessions"
+    FROM sessions s JOIN seed_sessions USING ("user_id", "session")
+    WHERE s."song_id" NOT IN (SELECT "song_id" FROM seeds)
+    GROUP BY s."song_id"
+    ORDER BY "sessions" DESC, s."song_id"
+    LIMIT $2"#;
+
+#[async_trait::async_trait]
+impl SimilarityRepository for SeaOrmSimilarityRepository {
+    async fn find_seed_features(&self, seed_type: SeedType, seed_id: Uuid) -> Result<Vec<SongFeatures>, DbErr> {
+        self.all_features(Self::features().filter(seed_type.column().eq(seed_id))).await
+    }
+
+    async fn find_candidates(
+        &self,
+        genres: &[String],
+        artist_ids: &[Uuid],
+        song_ids: &[Uuid],
+        limit: u64,
+    ) -> Result<Vec<SongFeatures>, DbErr> {
+        let co_listened = song::Column::Id.is_in(song_ids.iter().copied());
+        let same_artist = song::Column::ArtistId.is_in(artist_ids.iter().copied());
+        let condition = Condition::any()
+            .add(Expr::expr(genre()).is_in(genres.iter().cloned()))
+            .add(same_artist.clone())
+            .add(co_listened.clone());
+        // 超出上限时优先保留共同收听的歌曲，其次是相同或相关歌手的歌曲，最后才是同流派的歌曲；
+        // 同一层级内按ID排序使截取的候选集合稳定
+        let tier = Expr::case(co_listened, 0).case(same_artist, 1).finally(2);
+        self.all_features(
+            Self::features()
+                .filter(condition)
+                .order_by(SimpleExpr::Case(Box::new(tier)), Order::Asc)
+                .order_by_asc(song::Column::Id)
+                .limit(limit),
+        ).await
+    }
+
+    async fn find_co_listened(&self, song_ids: &[Uuid], limit: u64) -> Result<Vec<(Uuid, i64)>, DbErr> {
+        if song_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids = song_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+        let rows = self.db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                CO_LISTENED_SQL,
+                [ids.into(), (limit as i64).into()],
+            ))
+            .await?;
+        rows.iter()
+            .map(|row| Ok((row.try_get("", "song_id")?, row.try_get("", "sessions")?)))
+            .collect()
+    }
+
+    async fn find_recently_played(&self, user_id: Uuid, since: DateTime<Local>) -> Result<Vec<Uuid>, DbErr> {
+        play_event::Entity::find()
+            .select_only()
+            .column(play_event::Column::SongId)
+            .distinct()
+            .filter(play_event::Column::UserId.eq(user_id))
+            .filter(play_event::Column::EventType.eq(PlayEventType::Completed.as_str()))
+            .filter(play_event::Column::PlayedAt.gte(since))
+            .into_tuple()
+            .all(&*self.db)
+            .await
+    }
+}
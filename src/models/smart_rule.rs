@@ -0,0 +1,483 @@
+use chrono::{Duration, Local, NaiveDate};
+use sea_orm::sea_query::{Alias, Expr, Func, LikeExpr, SimpleExpr};
+use sea_orm::{Condition, EntityTrait, JoinType, Order, QueryFilter, QueryOrder, QuerySelect, RelationTrait, Select};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use super::{album, artist, rating_summary, song, song_play_stat};
+use super::rating::RatingItemType;
+
+// 规则树最大嵌套深度
+pub const MAX_RULE_DEPTH: usize = 5;
+// 单个规则树最多包含的条件数
+pub const MAX_RULE_CONDITIONS: usize = 50;
+// 智能歌单最多返回的歌曲数（未指定limit时也使用该值）
+pub const MAX_SMART_PLAYLIST_SONGS: u64 = 1000;
+
+// 智能歌单规则定义，以JSON形式保存在歌单中
+//
+// {
+//   "filter": {"combinator": "and", "rules": [
+//     {"field": "genre", "operator": "eq", "value": "Jazz"},
+//     {"field": "quality", "operator": "eq", "value": "FLAC"},
+//     {"field": "releaseDate", "operator": "after", "value": "2015-12-31"}
+//   ]},
+//   "sort": [{"field": "releaseDate", "direction": "desc"}],
+//   "limit": 100
+// }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SmartRules {
+    pub filter: RuleNode,
+    #[serde(default)]
+    pub sort: Vec<RuleSort>,
+    pub limit: Option<u64>,
+}
+
+// 规则节点：条件组（AND/OR）或单个条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleNode {
+    Group {
+        combinator: Combinator,
+        rules: Vec<RuleNode>,
+    },
+    Condition {
+        field: String,
+        operator: String,
+        #[serde(default)]
+        value: Value,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Combinator {
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleSort {
+    // 排序字段，可使用任意规则字段或random
+    pub field: String,
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+// 规则校验错误，消息直接返回给客户端
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleError(pub String);
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldKind {
+    Text,
+    Number,
+    Date,
+}
+
+// 规则可引用的字段
+#[derive(Debug, Clone, Copy)]
+enum RuleField {
+    Title,
+    Genre,
+    Quality,
+    Duration,
+    TrackNumber,
+    DiscNumber,
+    Album,
+    Artist,
+    ReleaseDate,
+    Year,
+    AddedAt,
+    Rating,
+    PlayCount,
+    LastPlayed,
+}
+
+impl RuleField {
+    fn parse(name: &str) -> Result<Self, RuleError> {
+        match name {
+            "title" => Ok(RuleField::Title),
+            "genre" => Ok(RuleField::Genre),
+            "quality" => Ok(RuleField::Quality),
+            "duration" => Ok(RuleField::Duration),
+            "trackNumber" => Ok(RuleField::TrackNumber),
+            "discNumber" => Ok(RuleField::DiscNumber),
+            "album" => Ok(RuleField::Album),
+            "artist" => Ok(RuleField::Artist),
+            "releaseDate" => Ok(RuleField::ReleaseDate),
+            "year" => Ok(RuleField::Year),
+            "addedAt" => Ok(RuleField::AddedAt),
+            "rating" => Ok(RuleField::Rating),
+            "playCount" => Ok(RuleField::PlayCount),
+            "lastPlayed" => Ok(RuleField::LastPlayed),
+            _ => Err(RuleError(format!("Unknown field '{}'", name))),
+        }
+    }
+
+    fn kind(self) -> FieldKind {
+        match self {
+            RuleField::Title | RuleField::Genre | RuleField::Quality | RuleField::Album | RuleField::Artist => FieldKind::Text,
+            RuleField::Duration | RuleField::TrackNumber | RuleField::DiscNumber | RuleField::Year | RuleField::Rating | RuleField::PlayCount => FieldKind::Number,
+            RuleField::ReleaseDate | RuleField::AddedAt | RuleField::LastPlayed => FieldKind::Date,
+        }
+    }
+
+    // 字段对应的列表达式（歌曲、专辑、歌手表都有created_at等同名列，必须带表名）
+    fn expr(self) -> SimpleExpr {
+        match self {
+            RuleField::Title => Expr::col((song::Entity, song::Column::Title)).into(),
+            RuleField::Genre => Expr::col((song::Entity, song::Column::Genre)).into(),
+            RuleField::Quality => Expr::col((song::Entity, song::Column::Quality)).into(),
+            RuleField::Duration => Expr::col((song::Entity, song::Column::Duration)).into(),
+            RuleField::TrackNumber => Expr::col((song::Entity, song::Column::TrackNumber)).into(),
+            RuleField::DiscNumber => Expr::col((song::Entity, song::Column::DiscNumber)).into(),
+            RuleField::Album => Expr::col((album::Entity, album::Column::Name)).into(),
+            RuleField::Artist => Expr::col((artist::Entity, artist::Column::Name)).into(),
+            RuleField::ReleaseDate => Expr::col((album::Entity, album::Column::ReleaseDate)).into(),
+            RuleField::Year => Func::cust(Alias::new("date_part"))
+                .arg(Expr::val("year"))
+                .arg(Expr::col((album::Entity, album::Column::ReleaseDate)))
+                .into(),
+            RuleField::AddedAt => Expr::col((song::Entity, song::Column::CreatedAt)).into(),
+            // 平均评分，未评分的歌曲视为0
+            RuleField::Rating => Func::coalesce([rating_summary::average_rating().into(), Expr::val(0.0).into()]).into(),
+            RuleField::PlayCount => Func::coalesce([song_play_stat::play_count().into(), Expr::val(0).into()]).into(),
+            // 未播放过的歌曲视为1970年播放，notInTheLast等条件可以匹配到
+            RuleField::LastPlayed => Func::coalesce([song_play_stat::last_played_at().into(), Expr::cust("'epoch'::timestamptz")]).into(),
+        }
+    }
+}
+
+impl SmartRules {
+    // 校验规则并编译为歌曲查询，排除已删除的歌曲
+    pub fn compile(&self) -> Result<Select<song::Entity>, RuleError> {
+        let mut conditions = 0;
+        let filter = compile_node(&self.filter, 1, &mut conditions)?;
+
+        let limit = self.limit.unwrap_or(MAX_SMART_PLAYLIST_SONGS);
+        if limit == 0 || limit > MAX_SMART_PLAYLIST_SONGS {
+            return Err(RuleError(format!("Limit must be between 1 and {}", MAX_SMART_PLAYLIST_SONGS)));
+        }
+
+        let query = song::Entity::find()
+            .join(JoinType::InnerJoin, song::Relation::Album.def())
+            .join(JoinType::InnerJoin, song::Relation::Artist.def());
+        let query = song_play_stat::join_stats(rating_summary::join_summary(query, RatingItemType::Song));
+        let mut query = query
+            .filter(Expr::col((song::Entity, song::Column::DeleteFlag)).eq(false))
+            .filter(filter);
+
+        if self.sort.is_empty() {
+            // 默认按歌手、专辑发行日期、碟号和音轨号排序
+            query = query
+                .order_by(RuleField::Artist.expr(), Order::Asc)
+                .order_by(RuleField::ReleaseDate.expr(), Order::Asc)
+                .order_by(RuleField::DiscNumber.expr(), Order::Asc)
+                .order_by(RuleField::TrackNumber.expr(), Order::Asc);
+        }
+        for sort in &self.sort {
+            let order = match sort.direction {
+                SortDirection::Asc => Order::Asc,
+                SortDirection::Desc => Order::Desc,
+            };
+            query = match sort.field.as_str() {
+                "random" => query.order_by(Expr::cust("RANDOM()"), order),
+                field => query.order_by(RuleField::parse(field)?.expr(), order),
+            };
+        }
+
+        // 保证相同排序值时结果稳定
+        Ok(query
+            .order_by(Expr::col((song::Entity, song::Column::Id)), Order::Asc)
+            .limit(limit))
+    }
+
+    // 仅校验规则
+    pub fn validate(&self) -> Result<(), RuleError> {
+        self.compile().map(|_| ())
+    }
+}
+
+fn compile_node(node: &RuleNode, depth: usize, conditions: &mut usize) -> Result<Condition, RuleError> {
+    if depth > MAX_RULE_DEPTH {
+        return Err(RuleError(format!("Rules may be nested at most {} levels deep", MAX_RULE_DEPTH)));
+    }
+
+    match node {
+        RuleNode::Group { combinator, rules } => {
+            if rules.is_empty() {
+                return Err(RuleError("A rule group must contain at least one rule".to_string()));
+            }
+            let mut condition = match combinator {
+                Combinator::And => Condition::all(),
+                Combinator::Or => Condition::any(),
+            };
+            for rule in rules {
+                condition = condition.add(compile_node(rule, depth + 1, conditions)?);
+            }
+            Ok(condition)
+        }
+        RuleNode::Condition { field, operator, value } => {
+            *conditions += 1;
+            if *conditions > MAX_RULE_CONDITIONS {
+                return Err(RuleError(format!("Rules may contain at most {} conditions", MAX_RULE_CONDITIONS)));
+            }
+            let rule_field = RuleField::parse(field)?;
+            let condition = match rule_field.kind() {
+                FieldKind::Text => text_condition(rule_field.expr(), operator, value),
+                FieldKind::Number => number_condition(rule_field.expr(), operator, value),
+                FieldKind::Date => date_condition(rule_field.expr(), operator, value),
+            };
+            condition.map_err(|RuleError(message)| RuleError(format!("Invalid rule on '{}': {}", field, message)))
+        }
+    }
+}
+
+// 文本条件，比较时忽略大小写
+fn text_condition(column: SimpleExpr, operator: &str, value: &Value) -> Result<Condition, RuleError> {
+    let lower = || Expr::expr(Func::lower(column.clone()));
+    let condition = match operator {
+        "eq" => Condition::all().add(lower().eq(text_value(value)?.to_lowercase())),
+        "ne" => Condition::any()
+            .add(Expr::expr(column.clone()).is_null())
+            .add(lower().ne(text_value(value)?.to_lowercase())),
+        "contains" => Condition::all().add(lower().like(like_pattern("%", &text_value(value)?, "%"))),
+        "notContains" => Condition::any()
+            .add(Expr::expr(column.clone()).is_null())
+            .add(lower().not_like(like_pattern("%", &text_value(value)?, "%"))),
+        "startsWith" => Condition::all().add(lower().like(like_pattern("", &text_value(value)?, "%"))),
+        "endsWith" => Condition::all().add(lower().like(like_pattern("%", &text_value(value)?, ""))),
+        "in" => {
+            let values = list_value(value)?
+                .iter()
+                .map(|value| text_value(value).map(|value| value.to_lowercase()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Condition::all().add(lower().is_in(values))
+        }
+        _ => return Err(unsupported_operator(operator, "eq, ne, contains, notContains, startsWith, endsWith, in")),
+    };
+    Ok(condition)
+}
+
+// 构造小写的LIKE模式，值中的%、_和转义符按字面匹配
+fn like_pattern(prefix: &str, text: &str, suffix: &str) -> LikeExpr {
+    let escaped = text.to_lowercase()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    LikeExpr::new(format!("{}{}{}", prefix, escaped, suffix)).escape('\\')
+}
+
+fn number_condition(column: SimpleExpr, operator: &str, value: &Value) -> Result<Condition, RuleError> {
+    let column = || Expr::expr(column.clone());
+    let condition = match operator {
+        "eq" => column().eq(number_value(value)?),
+        "ne" => column().ne(number_value(value)?),
+        "gt" => column().gt(number_value(value)?),
+        "gte" => column().gte(number_value(value)?),
+        "lt" => column().lt(number_value(value)?),
+        "lte" => column().lte(number_value(value)?),
+        "between" => {
+            let (from, to) = pair_value(value, number_value)?;
+            column().between(from, to)
+        }
+        "in" => {
+            let values = list_value(value)?
+                .iter()
+                .map(number_value)
+                .collect::<Result<Vec<_>, _>>()?;
+            column().is_in(values)
+        }
+        _ => return Err(unsupported_operator(operator, "eq, ne, gt, gte, lt, lte, between, in")),
+    };
+    Ok(Condition::all().add(condition))
+}
+
+// 日期条件，值为YYYY-MM-DD格式，inTheLast/notInTheLast的值为天数
+fn date_condition(column: SimpleExpr, operator: &str, value: &Value) -> Result<Condition, RuleError> {
+    let column = || Expr::expr(column.clone());
+    let condition = match operator {
+        "eq" => {
+            let date = date_value(value)?;
+            Condition::all()
+                .add(column().gte(date))
+                .add(column().lt(next_day(date)?))
+        }
+        "before" => Condition::all().add(column().lt(date_value(value)?)),
+        "after" => Condition::all().add(column().gte(next_day(date_value(value)?)?)),
+        "between" => {
+            let (from, to) = pair_value(value, date_value)?;
+            Condition::all()
+                .add(column().gte(from))
+                .add(column().lt(next_day(to)?))
+        }
+        "inTheLast" => Condition::all().add(column().gte(days_ago(value)?)),
+        "notInTheLast" => Condition::all().add(column().lt(days_ago(value)?)),
+        _ => return Err(unsupported_operator(operator, "eq, before, after, between, inTheLast, notInTheLast")),
+    };
+    Ok(condition)
+}
+
+fn unsupported_operator(operator: &str, supported: &str) -> RuleError {
+    RuleError(format!("operator '{}' is not supported, expected one of: {}", operator, supported))
+}
+
+fn text_value(value: &Value) -> Result<String, RuleError> {
+    match value {
+        Value::String(text) if !text.trim().is_empty() => Ok(text.trim().to_string()),
+        _ => Err(RuleError("value must be a non-empty string".to_string())),
+    }
+}
+
+fn number_value(value: &Value) -> Result<i64, RuleError> {
+    value.as_i64().ok_or_else(|| RuleError("value must be an integer".to_string()))
+}
+
+fn date_value(value: &Value) -> Result<NaiveDate, RuleError> {
+    value.as_str()
+        .and_then(|text| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok())
+        .ok_or_else(|| RuleError("value must be a date in YYYY-MM-DD format".to_string()))
+}
+
+fn list_value(value: &Value) -> Result<&Vec<Value>, RuleError> {
+    match value {
+        Value::Array(values) if !values.is_empty() => Ok(values),
+        _ => Err(RuleError("value must be a non-empty array".to_string())),
+    }
+}
+
+fn pair_value<T: PartialOrd>(value: &Value, parse: fn(&Value) -> Result<T, RuleError>) -> Result<(T, T), RuleError> {
+    match value {
+        Value::Array(values) if values.len() == 2 => {
+            let (from, to) = (parse(&values[0])?, parse(&values[1])?);
+            if from > to {
+                return Err(RuleError("range start must not be after range end".to_string()));
+            }
+            Ok((from, to))
+        }
+        _ => Err(RuleError("value must be an array of two bounds".to_string())),
+    }
+}
+
+fn next_day(date: NaiveDate) -> Result<NaiveDate, RuleError> {
+    date.succ_opt().ok_or_else(|| RuleError("date is out of range".to_string()))
+}
+
+fn days_ago(value: &Value) -> Result<NaiveDate, RuleError> {
+    let days = value.as_u64()
+        .filter(|days| (1..=36500).contains(days))
+        .ok_or_else(|| RuleError("value must be a number of days between 1 and 36500".to_string()))?;
+    Ok(Local::now().date_naive() - Duration::days(days as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, QueryTrait};
+    use serde_json::json;
+
+    fn sql(rules: serde_json::Value) -> String {
+        let rules: SmartRules = serde_json::from_value(rules).unwrap();
+        rules.compile().unwrap().build(DbBackend::Postgres).to_string()
+    }
+
+    fn error(rules: serde_json::Value) -> String {
+        let rules: SmartRules = serde_json::from_value(rules).unwrap();
+        rules.validate().unwrap_err().0
+    }
+
+    #[test]
+    fn compiles_nested_groups_sort_and_limit() {
+        let sql = sql(json!({
+            "filter": {"combinator": "and", "rules": [
+                {"field": "genre", "operator": "eq", "value": " Jazz "},
+                {"combinator": "or", "rules": [
+                    {"field": "duration", "operator": "gte", "value": 300},
+                    {"field": "releaseDate", "operator": "between", "value": ["2015-01-01", "2015-12-31"]}
+                ]}
+            ]},
+            "sort": [{"field": "playCount", "direction": "desc"}],
+            "limit": 25
+        }));
+
+        assert!(sql.contains(r#""song"."delete_flag" = FALSE"#));
+        assert!(sql.contains(r#"LOWER("song"."genre") = 'jazz'"#));
+        assert!(sql.contains(r#"("song"."duration" >= 300 OR ("album"."release_date" >= '2015-01-01' AND "album"."release_date" < '2016-01-01'))"#));
+        assert!(sql.contains(r#"ORDER BY COALESCE("song_play_stat"."play_count", 0) DESC, "song"."id" ASC LIMIT 25"#));
+    }
+
+    #[test]
+    fn escapes_like_wildcards_in_text_values() {
+        let sql = sql(json!({"filter": {"field": "title", "operator": "contains", "value": "100%_Off\\"}}));
+
+        assert!(sql.contains(r#"LOWER("song"."title") LIKE E'%100\\%\\_off\\\\%' ESCAPE E'\\'"#));
+    }
+
+    #[test]
+    fn escapes_like_wildcards_in_prefix_and_suffix_matches() {
+        let starts = sql(json!({"filter": {"field": "album", "operator": "startsWith", "value": "a_b"}}));
+        let ends = sql(json!({"filter": {"field": "artist", "operator": "endsWith", "value": "50%"}}));
+
+        assert!(starts.contains(r#"LIKE E'a\\_b%'"#));
+        assert!(ends.contains(r#"LIKE E'%50\\%'"#));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert_eq!(
+            error(json!({"filter": {"field": "mood", "operator": "eq", "value": "happy"}})),
+            "Unknown field 'mood'"
+        );
+        assert!(error(json!({"filter": {"field": "title", "operator": "gt", "value": "a"}}))
+            .starts_with("Invalid rule on 'title': operator 'gt' is not supported"));
+        assert_eq!(
+            error(json!({"filter": {"field": "year", "operator": "between", "value": [2020, 2010]}})),
+            "Invalid rule on 'year': range start must not be after range end"
+        );
+        assert_eq!(
+            error(json!({"filter": {"combinator": "and", "rules": []}})),
+            "A rule group must contain at least one rule"
+        );
+        assert_eq!(
+            error(json!({"filter": {"field": "title", "operator": "eq", "value": "a"}, "limit": 0})),
+            format!("Limit must be between 1 and {}", MAX_SMART_PLAYLIST_SONGS)
+        );
+    }
+
+    #[test]
+    fn limits_depth_and_condition_count() {
+        let mut node = json!({"field": "title", "operator": "eq", "value": "a"});
+        for _ in 0..MAX_RULE_DEPTH {
+            node = json!({"combinator": "and", "rules": [node]});
+        }
+        assert_eq!(
+            error(json!({"filter": node})),
+            format!("Rules may be nested at most {} levels deep", MAX_RULE_DEPTH)
+        );
+
+        let rules: Vec<_> = (0..=MAX_RULE_CONDITIONS)
+            .map(|_| json!({"field": "title", "operator": "eq", "value": "a"}))
+            .collect();
+        assert_eq!(
+            error(json!({"filter": {"combinator": "or", "rules": rules}})),
+            format!("Rules may contain at most {} conditions", MAX_RULE_CONDITIONS)
+        );
+    }
+}
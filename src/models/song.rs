@@ -1,9 +1,15 @@
 use chrono::{DateTime, Local};
 use serde::{Serialize, Deserialize};
 use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, QuerySelect};
+use sea_orm::sea_query::{Func, NullOrdering};
 use uuid::Uuid;
 use sea_orm::entity::prelude::*;
 use std::sync::Arc;
+use super::smart_rule::SmartRules;
+use super::rating::RatingItemType;
+use super::credit::{self, CreditItemType, CreditRole};
+use super::rating_summary;
+use super::song_play_stat;
 
 // 定义歌曲表实体
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
@@ -19,6 +25,12 @@ pub struct Model {
     pub quality: String,
     pub file_path: String,
     #[sea_orm(indexed)]
+    pub file_hash: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<DateTime<Local>>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    #[sea_orm(indexed)]
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
     pub created_by: String,
@@ -57,6 +69,17 @@ pub trait SongRepository: Send + Sync {
     async fn create(&self, request: &CreateSongRequest) -> Result<Song, DbErr>;
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Song>, DbErr>;
     async fn find_all(&self, params: &SongQueryParams) -> Result<Vec<Song>, DbErr>;
+    async fn find_by_file_path(&self, file_path: &str) -> Result<Option<Song>, DbErr>;
+    async fn find_by_file_hash(&self, file_hash: &str) -> Result<Vec<Song>, DbErr>;
+    async fn find_by_path_prefix(&self, prefix: &str) -> Result<Vec<Song>, DbErr>;
+    async fn update(&self, id: Uuid, data: &UpdateSongData) -> Result<Song, DbErr>;
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Song>, DbErr>;
+    async fn find_by_album(&self, album_id: Uuid) -> Result<Vec<Song>, DbErr>;
+    async fn album_summaries(&self, album_ids: &[Uuid]) -> Result<Vec<(Uuid, i64, i64)>, DbErr>;
+    async fn search(&self, keyword: &str, offset: u64, limit: u64) -> Result<Vec<Song>, DbErr>;
+    async fn find_random(&self, params: &RandomSongParams) -> Result<Vec<Song>, DbErr>;
+    async fn find_by_rules(&self, rules: &SmartRules) -> Result<Vec<Song>, DbErr>;
+    async fn find_by_file_name(&self, file_name: &str) -> Result<Vec<Song>, DbErr>;
 }
 
 // SeaORM 实现的歌曲仓库
@@ -83,6 +106,13 @@ impl SongRepository for SeaOrmSongRepository {
             duration: ActiveValue::Set(request.duration),
             quality: ActiveValue::Set(request.quality.clone()),
             file_path: ActiveValue::Set(request.file_path.clone()),
+            file_hash: ActiveValue::Set(request.file_hash.clone()),
+            file_size: ActiveValue::Set(request.file_size),
+            file_modified_at: ActiveValue::Set(request.file_modified_at),
+            track_number: ActiveValue::Set(request.track_number),
+            disc_number: ActiveValue::Set(request.disc_number),
+            created_by: ActiveValue::Set(request.created_by.clone()),
+            updated_by: ActiveValue::Set(request.created_by.clone()),
             ..ActiveModel::new()
         };
 
@@ -94,15 +124,40 @@ impl SongRepository for SeaOrmSongRepository {
     }
 
     async fn find_all(&self, params: &SongQueryParams) -> Result<Vec<Song>, DbErr> {
-        let mut query = Entity::find().order_by_desc(Column::CreatedAt);
+        let mut query = Entity::find();
+
+        // 按评分筛选或排序时连接评分汇总表
+        if params.min_rating.is_some() || params.sort == SongSort::Rating {
+            query = rating_summary::join_summary(query, RatingItemType::Song);
+        }
+        if let Some(min_rating) = params.min_rating {
+            query = query.filter(rating_summary::average_rating().gte(min_rating));
+        }
+        if params.sort == SongSort::Rating {
+            // 未评分的歌曲排在最后，平均分相同时评分人数多的在前
+            query = query
+                .order_by_with_nulls(rating_summary::average_rating(), sea_orm::Order::Desc, NullOrdering::Last)
+                .order_by_with_nulls(Expr::col((rating_summary::Entity, rating_summary::Column::RatingCount)), sea_orm::Order::Desc, NullOrdering::Last);
+        }
+        if matches!(params.sort, SongSort::PlayCount | SongSort::LastPlayed) {
+            // 未播放过的歌曲排在最后
+            query = song_play_stat::join_stats(query);
+            query = match params.sort {
+                SongSort::PlayCount => query
+                    .order_by_with_nulls(song_play_stat::play_count(), sea_orm::Order::Desc, NullOrdering::Last)
+                    .order_by_with_nulls(song_play_stat::last_played_at(), sea_orm::Order::Desc, NullOrdering::Last),
+                _ => query.order_by_with_nulls(song_play_stat::last_played_at(), sea_orm::Order::Desc, NullOrdering::Last),
+            };
+        }
+        query = query.order_by_desc(Column::CreatedAt);
 
         // 添加筛选条件
         if let Some(album_id) = &params.album_id {
             query = query.filter(Column::AlbumId.eq(*album_id));
         }
 
-        if let Some(artist_id) = &params.artist_id {
-            query = query.filter(Column::ArtistId.eq(*artist_id));
+        if let Some(artist_id) = params.artist_id {
+            query = query.filter(credit::credited_condition(CreditItemType::Song, Column::Id, Column::ArtistId, artist_id, params.artist_role));
         }
 
         if let Some(genre) = &params.genre {
@@ -124,6 +179,169 @@ impl SongRepository for SeaOrmSongRepository {
             .all(&*self.db)
             .await
     }
+
+    async fn find_by_file_path(&self, file_path: &str) -> Result<Option<Song>, DbErr> {
+        Entity::find()
+            .filter(Column::FilePath.eq(file_path))
+            .order_by_asc(Column::DeleteFlag)
+            .one(&*self.db)
+            .await
+    }
+
+    async fn find_by_file_hash(&self, file_hash: &str) -> Result<Vec<Song>, DbErr> {
+        Entity::find()
+            .filter(Column::FileHash.eq(file_hash))
+            .order_by_asc(Column::DeleteFlag)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_by_path_prefix(&self, prefix: &str) -> Result<Vec<Song>, DbErr> {
+        Entity::find()
+            .filter(Column::FilePath.starts_with(prefix))
+            .filter(Column::DeleteFlag.eq(false))
+            .all(&*self.db)
+            .await
+    }
+
+    async fn update(&self, id: Uuid, data: &UpdateSongData) -> Result<Song, DbErr> {
+        let mut song = ActiveModel {
+            id: ActiveValue::Unchanged(id),
+            updated_at: ActiveValue::Set(Local::now()),
+            updated_by: ActiveValue::Set(data.updated_by.clone()),
+            ..Default::default()
+        };
+
+        if let Some(album_id) = data.album_id {
+            song.album_id = ActiveValue::Set(album_id);
+        }
+        if let Some(artist_id) = data.artist_id {
+            song.artist_id = ActiveValue::Set(artist_id);
+        }
+        if let Some(title) = &data.title {
+            song.title = ActiveValue::Set(title.clone());
+        }
+        if let Some(genre) = &data.genre {
+            song.genre = ActiveValue::Set(Some(genre.clone()));
+        }
+        if let Some(duration) = data.duration {
+            song.duration = ActiveValue::Set(duration);
+        }
+        if let Some(quality) = &data.quality {
+            song.quality = ActiveValue::Set(quality.clone());
+        }
+        if let Some(file_path) = &data.file_path {
+            song.file_path = ActiveValue::Set(file_path.clone());
+        }
+        if let Some(file_hash) = &data.file_hash {
+            song.file_hash = ActiveValue::Set(Some(file_hash.clone()));
+        }
+        if let Some(file_size) = data.file_size {
+            song.file_size = ActiveValue::Set(Some(file_size));
+        }
+        if let Some(file_modified_at) = data.file_modified_at {
+            song.file_modified_at = ActiveValue::Set(Some(file_modified_at));
+        }
+        if let Some(track_number) = data.track_number {
+            song.track_number = ActiveValue::Set(Some(track_number));
+        }
+        if let Some(disc_number) = data.disc_number {
+            song.disc_number = ActiveValue::Set(Some(disc_number));
+        }
+        if let Some(delete_flag) = data.delete_flag {
+            song.delete_flag = ActiveValue::Set(delete_flag);
+        }
+
+        song.update(&*self.db).await
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Song>, DbErr> {
+        Entity::find()
+            .filter(Column::Id.is_in(ids.iter().copied()))
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_by_album(&self, album_id: Uuid) -> Result<Vec<Song>, DbErr> {
+        Entity::find()
+            .filter(Column::AlbumId.eq(album_id))
+            .filter(Column::DeleteFlag.eq(false))
+            .order_by_asc(Column::DiscNumber)
+            .order_by_asc(Column::TrackNumber)
+            .order_by_asc(Column::Title)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn album_summaries(&self, album_ids: &[Uuid]) -> Result<Vec<(Uuid, i64, i64)>, DbErr> {
+        // 返回 (专辑ID, 歌曲数, 总时长)
+        Entity::find()
+            .select_only()
+            .column(Column::AlbumId)
+            .column_as(Column::Id.count(), "song_count")
+            .column_as(Expr::expr(Func::coalesce([Expr::col(Column::Duration).sum(), Expr::val(0).into()])).cast_as("bigint"), "duration")
+            .filter(Column::AlbumId.is_in(album_ids.iter().copied()))
+            .filter(Column::DeleteFlag.eq(false))
+            .group_by(Column::AlbumId)
+            .into_tuple()
+            .all(&*self.db)
+            .await
+    }
+
+    async fn search(&self, keyword: &str, offset: u64, limit: u64) -> Result<Vec<Song>, DbErr> {
+        Entity::find()
+            .filter(Column::DeleteFlag.eq(false))
+            .filter(Expr::expr(Func::lower(Expr::col(Column::Title))).like(format!("%{}%", keyword.to_lowercase())))
+            .order_by_asc(Column::Title)
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_random(&self, params: &RandomSongParams) -> Result<Vec<Song>, DbErr> {
+        let mut query = Entity::find().filter(Column::DeleteFlag.eq(false));
+
+        if let Some(genre) = &params.genre {
+            query = query.filter(Column::Genre.eq(genre));
+        }
+        // 年份按所属专辑的发行日期筛选
+        if params.from_year.is_some() || params.to_year.is_some() {
+            query = query.join(sea_orm::JoinType::InnerJoin, Relation::Album.def());
+            if let Some(from_year) = params.from_year.and_then(|year| chrono::NaiveDate::from_ymd_opt(year, 1, 1)) {
+                query = query.filter(super::album::Column::ReleaseDate.gte(from_year));
+            }
+            if let Some(to_year) = params.to_year.and_then(|year| chrono::NaiveDate::from_ymd_opt(year, 12, 31)) {
+                query = query.filter(super::album::Column::ReleaseDate.lte(to_year));
+            }
+        }
+
+        query
+            .order_by(Expr::cust("RANDOM()"), sea_orm::Order::Asc)
+            .limit(params.limit)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_by_rules(&self, rules: &SmartRules) -> Result<Vec<Song>, DbErr> {
+        rules.compile()
+            .map_err(|e| DbErr::Custom(e.to_string()))?
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_by_file_name(&self, file_name: &str) -> Result<Vec<Song>, DbErr> {
+        // LIKE中的通配符可能多匹配，调用方需再按完整文件名过滤
+        Entity::find()
+            .filter(Column::DeleteFlag.eq(false))
+            .filter(
+                sea_orm::Condition::any()
+                    .add(Column::FilePath.eq(file_name))
+                    .add(Column::FilePath.like(format!("%/{}", file_name)))
+            )
+            .all(&*self.db)
+            .await
+    }
 }
 
 // 重命名为Song以保持兼容性
@@ -139,19 +357,74 @@ pub struct CreateSongRequest {
     pub duration: u32,
     pub quality: String,
     pub file_path: String,
+    pub file_hash: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<DateTime<Local>>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub created_by: String,
+}
+
+// 歌曲更新数据（仅更新有值的字段）
+#[derive(Debug, Default)]
+pub struct UpdateSongData {
+    pub album_id: Option<Uuid>,
+    pub artist_id: Option<Uuid>,
+    pub title: Option<String>,
+    pub genre: Option<String>,
+    pub duration: Option<u32>,
+    pub quality: Option<String>,
+    pub file_path: Option<String>,
+    pub file_hash: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<DateTime<Local>>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub delete_flag: Option<bool>,
+    pub updated_by: String,
+}
+
+// 随机歌曲查询参数
+#[derive(Debug)]
+pub struct RandomSongParams {
+    pub genre: Option<String>,
+    pub from_year: Option<i32>,
+    pub to_year: Option<i32>,
+    pub limit: u64,
 }
 
 // 歌曲查询参数
 #[derive(Debug, Deserialize)]
 pub struct SongQueryParams {
     pub album_id: Option<Uuid>,
+    // 匹配主歌手及任一署名歌手
     pub artist_id: Option<Uuid>,
+    // 只匹配以该角色署名的歌手
+    pub artist_role: Option<CreditRole>,
     pub genre: Option<String>,
     pub quality: Option<String>,
+    // 最低平均评分
+    pub min_rating: Option<f64>,
+    pub sort: SongSort,
     pub page: Option<u64>,
     pub page_size: Option<u64>,
 }
 
+// 歌曲列表排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SongSort {
+    // 按添加时间倒序
+    #[default]
+    CreatedAt,
+    // 按平均评分倒序
+    Rating,
+    // 按播放次数倒序
+    PlayCount,
+    // 按最近播放时间倒序
+    LastPlayed,
+}
+
 // 为Song模型添加数据访问方法
 impl Song {
     // 创建新歌曲
@@ -164,6 +437,13 @@ impl Song {
             duration: ActiveValue::Set(request.duration),
             quality: ActiveValue::Set(request.quality.clone()),
             file_path: ActiveValue::Set(request.file_path.clone()),
+            file_hash: ActiveValue::Set(request.file_hash.clone()),
+            file_size: ActiveValue::Set(request.file_size),
+            file_modified_at: ActiveValue::Set(request.file_modified_at),
+            track_number: ActiveValue::Set(request.track_number),
+            disc_number: ActiveValue::Set(request.disc_number),
+            created_by: ActiveValue::Set(request.created_by.clone()),
+            updated_by: ActiveValue::Set(request.created_by.clone()),
             ..ActiveModel::new()
 
         };
@@ -188,8 +468,8 @@ impl Song {
             query = query.filter(Column::AlbumId.eq(album_id.to_owned()));
         }
 
-        if let Some(artist_id) = &params.artist_id {
-            query = query.filter(Column::ArtistId.eq(artist_id.to_owned()));
+        if let Some(artist_id) = params.artist_id {
+            query = query.filter(credit::credited_condition(CreditItemType::Song, Column::Id, Column::ArtistId, artist_id, params.artist_role));
         }
 
         if let Some(genre) = &params.genre {
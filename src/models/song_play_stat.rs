@@ -0,0 +1,42 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{DeriveEntityModel, DeriveRelation, EnumIter, JoinType, QuerySelect, Select};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+// 定义歌曲播放统计表实体，每首播放过的歌曲一行
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "song_play_stat")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub song_id: Uuid,
+    pub play_count: i64,
+    pub last_played_at: DateTime<Local>,
+}
+
+// 定义关联
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::song::Entity", from = "Column::SongId", to = "super::song::Column::Id")]
+    Song,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// 重命名为SongPlayStat以保持兼容性
+pub type SongPlayStat = Model;
+
+// 为歌曲查询左连接播放统计，之后可使用play_count和last_played_at列筛选和排序
+pub fn join_stats(query: Select<super::song::Entity>) -> Select<super::song::Entity> {
+    query.join(JoinType::LeftJoin, Relation::Song.def().rev())
+}
+
+// 播放次数列（带表名，避免与其他表的同名列冲突）
+pub fn play_count() -> Expr {
+    Expr::col((Entity, Column::PlayCount))
+}
+
+// 最近播放时间列
+pub fn last_played_at() -> Expr {
+    Expr::col((Entity, Column::LastPlayedAt))
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, QuerySelect, TransactionTrait};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+use super::song::{self, Song};
+
+// 定义歌曲版本表实体，保存歌曲被替换前的音频文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "song_version")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub song_id: Uuid,
+    // 同一歌曲内递增，越新归档的文件版本号越大
+    pub version: i32,
+    pub file_path: String,
+    pub file_hash: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<DateTime<Local>>,
+    pub duration: u32,
+    pub quality: String,
+    // 归档时间和操作人
+    pub created_at: DateTime<Local>,
+    pub created_by: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// 重命名为SongVersion以保持兼容性
+pub type SongVersion = Model;
+
+// 歌曲的音频文件及由文件得出的信息
+#[derive(Debug, Clone)]
+pub struct SongFile {
+    pub file_path: String,
+    pub file_hash: Option<String>,
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<DateTime<Local>>,
+    pub duration: u32,
+    pub quality: String,
+}
+
+impl From<&Song> for SongFile {
+    fn from(song: &Song) -> Self {
+        SongFile {
+            file_path: song.file_path.clone(),
+            file_hash: song.file_hash.clone(),
+            file_size: song.file_size,
+            file_modified_at: song.file_modified_at,
+            duration: song.duration,
+            quality: song.quality.clone(),
+        }
+    }
+}
+
+impl From<&SongVersion> for SongFile {
+    fn from(version: &SongVersion) -> Self {
+        SongFile {
+            file_path: version.file_path.clone(),
+            file_hash: version.file_hash.clone(),
+            file_size: version.file_size,
+            file_modified_at: version.file_modified_at,
+            duration: version.duration,
+            quality: version.quality.clone(),
+        }
+    }
+}
+
+// 定义歌曲版本仓库 trait
+#[async_trait::async_trait]
+pub trait SongVersionRepository: Send + Sync {
+    // 歌曲的历史版本，按版本号倒序
+    async fn find_by_song(&self, song_id: Uuid) -> Result<Vec<SongVersion>, DbErr>;
+    async fn find_by_file_path(&self, file_path: &str) -> Result<Option<SongVersion>, DbErr>;
+    // 替换歌曲的音频文件，当前文件归档为新版本
+    async fn replace_file(&self, song_id: Uuid, file: &SongFile, updated_by: &str) -> Result<Song, DbErr>;
+    // 恢复历史版本：该版本成为当前文件，当前文件归档为新版本；版本不存在时返回None
+    async fn restore(&self, song_id: Uuid, version_id: Uuid, updated_by: &str) -> Result<Option<Song>, DbErr>;
+}
+
+// SeaORM 实现的歌曲版本仓库
+pub struct SeaOrmSongVersionRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmSongVersionRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+// 锁定歌曲后把当前文件归档为新版本并换成新文件，并发替换同一歌曲时依次执行
+async fn swap_file(txn: &DatabaseTransaction, song_id: Uuid, file: &SongFile, updated_by: &str) -> Result<Song, DbErr> {
+    let current = song::Entity::find_by_id(song_id)
+        .lock_exclusive()
+        .one(txn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("song".to_string()))?;
+
+    let latest: Option<i32> = Entity::find()
+        .select_only()
+        .column_as(Column::Version.max(), "latest")
+        .filter(Column::SongId.eq(song_id))
+        .into_tuple()
+        .one(txn)
+        .await?
+        .flatten();
+    let archived = SongFile::from(&current);
+    ActiveModel {
+        id: ActiveValue::Set(Uuid::now_v7()),
+        song_id: ActiveValue::Set(song_id),
+        version: ActiveValue::Set(latest.unwrap_or(0) + 1),
+        file_path: ActiveValue::Set(archived.file_path),
+        file_hash: ActiveValue::Set(archived.file_hash),
+        file_size: ActiveValue::Set(archived.file_size),
+        file_modified_at: ActiveValue::Set(archived.file_modified_at),
+        duration: ActiveValue::Set(archived.duration),
+        quality: ActiveValue::Set(archived.quality),
+        created_at: ActiveValue::Set(Local::now()),
+        created_by: ActiveValue::Set(updated_by.to_string()),
+    }
+    .insert(txn)
+    .await?;
+
+    song::ActiveModel {
+        id: ActiveValue::Unchanged(song_id),
+        file_path: ActiveValue::Set(file.file_path.clone()),
+        file_hash: ActiveValue::Set(file.file_hash.clone()),
+        file_size: ActiveValue::Set(file.file_size),
+        file_modified_at: ActiveValue::Set(file.file_modified_at),
+        duration: ActiveValue::Set(file.duration),
+        quality: ActiveValue::Set(file.quality.clone()),
+        updated_at: ActiveValue::Set(Local::now()),
+        updated_by: ActiveValue::Set(updated_by.to_string()),
+        ..Default::default()
+    }
+    .update(txn)
+    .await
+}
+
+#[async_trait::async_trait]
+impl SongVersionRepository for SeaOrmSongVersionRepository {
+    async fn find_by_song(&self, song_id: Uuid) -> Result<Vec<SongVersion>, DbErr> {
+        Entity::find()
+            .filter(Column::SongId.eq(song_id))
+            .order_by_desc(Column::Version)
+            .all(&*self.db)
+            .await
+    }
+
+    async fn find_by_file_path(&self, file_path: &str) -> Result<Option<SongVersion>, DbErr> {
+        Entity::find()
+            .filter(Column::FilePath.eq(file_path))
+            .one(&*self.db)
+            .await
+    }
+
+    async fn replace_file(&self, song_id: Uuid, file: &SongFile, updated_by: &str) -> Result<Song, DbErr> {
+        let txn = self.db.begin().await?;
+        let song = swap_file(&txn, song_id, file, updated_by).await?;
+        txn.commit().await?;
+        Ok(song)
+    }
+
+    async fn restore(&self, song_id: Uuid, version_id: Uuid, updated_by: &str) -> Result<Option<Song>, DbErr> {
+        let txn = self.db.begin().await?;
+        let version = Entity::find_by_id(version_id)
+            .filter(Column::SongId.eq(song_id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?;
+        let Some(version) = version else {
+            return Ok(None);
+        };
+
+        // 恢复后该版本即为当前文件，不再保留在历史中
+        Entity::delete_by_id(version.id).exec(&txn).await?;
+        let song = swap_file(&txn, song_id, &SongFile::from(&version), updated_by).await?;
+        txn.commit().await?;
+        Ok(Some(song))
+    }
+}
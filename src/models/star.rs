@@ -0,0 +1,207 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, DeriveEntityModel, DeriveRelation, EntityTrait, EnumIter, QueryFilter, QueryOrder, QuerySelect};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+use std::sync::Arc;
+use super::album::{self, Album};
+use super::artist::{self, Artist};
+use super::song::{self, Song};
+
+// 定义收藏表实体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "star")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(indexed)]
+    pub user_id: Uuid,
+    // 收藏对象类型：song、album、artist
+    pub item_type: String,
+    pub item_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+// 定义关联（item_id按item_type指向不同的表）
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "super::song::Entity", from = "Column::ItemId", to = "super::song::Column::Id")]
+    Song,
+    #[sea_orm(belongs_to = "super::album::Entity", from = "Column::ItemId", to = "super::album::Column::Id")]
+    Album,
+    #[sea_orm(belongs_to = "super::artist::Entity", from = "Column::ItemId", to = "super::artist::Column::Id")]
+    Artist,
+}
+
+impl Related<super::song::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Song.def()
+    }
+}
+
+impl Related<super::album::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Album.def()
+    }
+}
+
+impl Related<super::artist::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Artist.def()
+    }
+}
+
+impl sea_orm::ActiveModelBehavior for ActiveModel {
+    fn new() -> Self {
+        Self {
+            id: ActiveValue::Set(Uuid::now_v7()),
+            created_at: ActiveValue::Set(Local::now()),
+            ..ActiveModelTrait::default()
+        }
+    }
+}
+
+// 重命名为Star以保持兼容性
+pub type Star = Model;
+
+// 收藏对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StarItemType {
+    Song,
+    Album,
+    Artist,
+}
+
+impl StarItemType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StarItemType::Song => "song",
+            StarItemType::Album => "album",
+            StarItemType::Artist => "artist",
+        }
+    }
+
+    // 同时接受单数和复数形式（路由中使用songs/albums/artists）
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "song" | "songs" => Some(StarItemType::Song),
+            "album" | "albums" => Some(StarItemType::Album),
+            "artist" | "artists" => Some(StarItemType::Artist),
+            _ => None,
+        }
+    }
+}
+
+// 定义收藏仓库 trait
+#[async_trait::async_trait]
+pub trait StarRepository: Send + Sync {
+    async fn star(&self, user_id: Uuid, item_type: StarItemType, item_id: Uuid) -> Result<Star, DbErr>;
+    async fn unstar(&self, user_id: Uuid, item_type: StarItemType, item_id: Uuid) -> Result<bool, DbErr>;
+    async fn find_by_items(&self, user_id: Uuid, item_type: StarItemType, item_ids: &[Uuid]) -> Result<Vec<Star>, DbErr>;
+    async fn find_starred_songs(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(Star, Song)>, DbErr>;
+    async fn find_starred_albums(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(Star, Album)>, DbErr>;
+    async fn find_starred_artists(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(Star, Artist)>, DbErr>;
+}
+
+// SeaORM 实现的收藏仓库
+pub struct SeaOrmStarRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmStarRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+
+    fn starred(user_id: Uuid, item_type: StarItemType) -> Select<Entity> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .order_by_desc(Column::CreatedAt)
+    }
+}
+
+#[async_trait::async_trait]
+impl StarRepository for SeaOrmStarRepository {
+    async fn star(&self, user_id: Uuid, item_type: StarItemType, item_id: Uuid) -> Result<Star, DbErr> {
+        // 重复收藏保留最初的收藏时间
+        let star = ActiveModel {
+            user_id: ActiveValue::Set(user_id),
+            item_type: ActiveValue::Set(item_type.as_str().to_string()),
+            item_id: ActiveValue::Set(item_id),
+            ..ActiveModel::new()
+        };
+        Entity::insert(star)
+            .on_conflict(
+                OnConflict::columns([Column::UserId, Column::ItemType, Column::ItemId])
+                    .do_nothing()
+                    .to_owned()
+            )
+            .exec_without_returning(&*self.db)
+            .await?;
+
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.eq(item_id))
+            .one(&*self.db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("star {} {}", item_type.as_str(), item_id)))
+    }
+
+    async fn unstar(&self, user_id: Uuid, item_type: StarItemType, item_id: Uuid) -> Result<bool, DbErr> {
+        let result = Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.eq(item_id))
+            .exec(&*self.db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn find_by_items(&self, user_id: Uuid, item_type: StarItemType, item_ids: &[Uuid]) -> Result<Vec<Star>, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ItemType.eq(item_type.as_str()))
+            .filter(Column::ItemId.is_in(item_ids.iter().copied()))
+            .all(&*self.db)
+            .await
+    }
+
+    // 收藏列表不返回已删除的对象
+    async fn find_starred_songs(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(Star, Song)>, DbErr> {
+        let stars = Self::starred(user_id, StarItemType::Song)
+            .find_also_related(song::Entity)
+            .filter(song::Column::DeleteFlag.eq(false))
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.db)
+            .await?;
+        Ok(stars.into_iter().filter_map(|(star, song)| song.map(|song| (star, song))).collect())
+    }
+
+    async fn find_starred_albums(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(Star, Album)>, DbErr> {
+        let stars = Self::starred(user_id, StarItemType::Album)
+            .find_also_related(album::Entity)
+            .filter(album::Column::DeleteFlag.eq(false))
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.db)
+            .await?;
+        Ok(stars.into_iter().filter_map(|(star, album)| album.map(|album| (star, album))).collect())
+    }
+
+    async fn find_starred_artists(&self, user_id: Uuid, offset: u64, limit: u64) -> Result<Vec<(Star, Artist)>, DbErr> {
+        let stars = Self::starred(user_id, StarItemType::Artist)
+            .find_also_related(artist::Entity)
+            .filter(artist::Column::DeleteFlag.eq(false))
+            .offset(offset)
+            .limit(limit)
+            .all(&*self.db)
+            .await?;
+        Ok(stars.into_iter().filter_map(|(star, artist)| artist.map(|artist| (star, artist))).collect())
+    }
+}
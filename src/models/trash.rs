@@ -0,0 +1,464 @@
+use chrono::{DateTime, Local};
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, EntityTrait, QueryFilter, QuerySelect, Statement, TransactionTrait};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use std::collections::HashSet;
+use std::sync::Arc;
+use super::{album, artist, song, song_version, user};
+
+// 回收站对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashItemType {
+    User,
+    Artist,
+    Album,
+    Song,
+}
+
+impl TrashItemType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrashItemType::User => "user",
+            TrashItemType::Artist => "artist",
+            TrashItemType::Album => "album",
+            TrashItemType::Song => "song",
+        }
+    }
+
+    // 同时接受单数和复数形式（路由中使用users/artists/albums/songs）
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "user" | "users" => Some(TrashItemType::User),
+            "artist" | "artists" => Some(TrashItemType::Artist),
+            "album" | "albums" => Some(TrashItemType::Album),
+            "song" | "songs" => Some(TrashItemType::Song),
+            _ => None,
+        }
+    }
+}
+
+// 回收站条目，删除时间和删除人取自软删除时写入的updated_at和updated_by
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    pub item_type: String,
+    pub id: Uuid,
+    pub name: String,
+    pub deleted_at: DateTime<Local>,
+    pub deleted_by: Option<String>,
+    // 删除人为用户时的用户名
+    pub deleted_by_username: Option<String>,
+}
+
+// 恢复结果
+#[derive(Debug)]
+pub enum RestoreResult {
+    // 恢复的各类对象数量（含同一次级联删除的下级对象）
+    Restored(RestoredCounts),
+    NotFound,
+    // 所属的上级对象仍在回收站中，需先恢复上级
+    ParentDeleted(TrashItemType),
+    // 已有同名的未删除用户
+    UsernameTaken,
+}
+
+#[derive(Debug, Default)]
+pub struct RestoredCounts {
+    pub users: u64,
+    pub artists: u64,
+    pub albums: u64,
+    pub songs: u64,
+}
+
+// 清理结果
+#[derive(Debug, Default)]
+pub struct PurgeResult {
+    pub users: u64,
+    pub artists: u64,
+    pub albums: u64,
+    pub songs: u64,
+    // 不再被任何歌曲、历史版本、专辑或歌手引用的媒体文件
+    pub unreferenced_files: Vec<String>,
+}
+
+// 定义回收站仓库 trait
+#[async_trait::async_trait]
+pub trait TrashRepository: Send + Sync {
+    // 已软删除的对象，按删除时间倒序；item_type为空时列出全部类型
+    async fn find_deleted(&self, item_type: Option<TrashItemType>, offset: u64, limit: u64) -> Result<Vec<TrashEntry>, DbErr>;
+    // 恢复对象及同一次级联删除的下级对象
+    async fn restore(&self, item_type: TrashItemType, id: Uuid, restored_by: &str) -> Result<RestoreResult, DbErr>;
+    // 永久删除在指定时间之前软删除的对象及其关联数据
+    async fn purge(&self, before: DateTime<Local>) -> Result<PurgeResult, DbErr>;
+}
+
+// SeaORM 实现的回收站仓库
+pub struct SeaOrmTrashRepository {
+    db: Arc<DatabaseConnection>,
+}
+
+impl SeaOrmTrashRepository {
+    pub fn new(db: Arc<DatabaseConnection>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+// 各类型已删除对象的统一视图
+const DELETED_SQL: &str = r#"SELECT 'user' AS "item_type", "id", "username" AS "name", "updated_at" AS "deleted_at", "updated_by" AS "deleted_by" FROM "user" WHERE "delete_flag" = true
+    UNION ALL
+    SELECT 'artist', "id", "name", "updated_at", "updated_by" FROM "artist" WHERE "delete_flag" = true
+    UNION ALL
+    SELECT 'album', "id", "name", "updated_at", "updated_by" FROM "album" WHERE "delete_flag" = true
+    UNION ALL
+    SELECT 'song', "id", "title", "updated_at", "updated_by" FROM "song" WHERE "delete_flag" = true"#;
+
+// 待清理对象，$1为保留期限的截止时间。专辑和歌手在其下的歌曲清理之后判断，仍被引用时保留
+const PURGED_SONGS: &str = r#"SELECT "id" FROM "song" WHERE "delete_flag" = true AND "updated_at" < $1"#;
+const PURGED_ALBUMS: &str = r#"SELECT a."id" FROM "album" a WHERE a."delete_flag" = true AND a."updated_at" < $1
+    AND NOT EXISTS (SELECT 1 FROM "song" s WHERE s."album_id" = a."id")"#;
+const PURGED_ARTISTS: &str = r#"SELECT a."id" FROM "artist" a WHERE a."delete_flag" = true AND a."updated_at" < $1
+    AND NOT EXISTS (SELECT 1 FROM "album" b WHERE b."artist_id" = a."id")
+    AND NOT EXISTS (SELECT 1 FROM "song" s WHERE s."artist_id" = a."id")"#;
+const PURGED_USERS: &str = r#"SELECT "id" FROM "user" WHERE "delete_flag" = true AND "updated_at" < $1"#;
+
+async fn execute(txn: &DatabaseTransaction, sql: String, before: DateTime<Local>) -> Result<u64, DbErr> {
+    let result = txn.execute(Statement::from_sql_and_values(DbBackend::Postgres, sql, [before.into()])).await?;
+    Ok(result.rows_affected())
+}
+
+async fn query_paths(txn: &DatabaseTransaction, sql: String, before: DateTime<Local>) -> Result<Vec<String>, DbErr> {
+    let rows = txn.query_all(Statement::from_sql_and_values(DbBackend::Postgres, sql, [before.into()])).await?;
+    rows.iter().map(|row| row.try_get("", "path")).collect()
+}
+
+// 删除对象的收藏、评分、评分汇总和署名（歌手为其在其他对象上的署名）
+async fn purge_item_marks(txn: &DatabaseTransaction, item_type: TrashItemType, ids_sql: &str, before: DateTime<Local>) -> Result<(), DbErr> {
+    if item_type == TrashItemType::Artist {
+        execute(txn, format!(r#"DELETE FROM "credit" WHERE "artist_id" IN ({ids_sql})"#), before).await?;
+    } else {
+        execute(txn, format!(
+            r#"DELETE FROM "credit" WHERE "item_type" = '{item_type}' AND "item_id" IN ({ids_sql})"#,
+            item_type = item_type.as_str(),
+        ), before).await?;
+    }
+    for table in ["star", "rating", "rating_summary"] {
+        execute(txn, format!(
+            r#"DELETE FROM "{table}" WHERE "item_type" = '{item_type}' AND "item_id" IN ({ids_sql})"#,
+            item_type = item_type.as_str(),
+        ), before).await?;
+    }
+    Ok(())
+}
+
+// 歌曲：历史版本、收藏、评分、歌单条目和播放次数随歌曲删除，歌单内其余条目重新连续编号。
+// 用户的收听记录保留，排行榜和推荐模型由定时任务重建时自然排除
+async fn purge_songs(txn: &DatabaseTransaction, before: DateTime<Local>, result: &mut PurgeResult) -> Result<(), DbErr> {
+    result.unreferenced_files.extend(query_paths(txn, format!(
+        r#"SELECT "file_path" AS "path" FROM "song" WHERE "id" IN ({PURGED_SONGS})
+        UNION SELECT "file_path" FROM "song_version" WHERE "song_id" IN ({PURGED_SONGS})"#,
+    ), before).await?);
+
+    execute(txn, format!(r#"DELETE FROM "song_version" WHERE "song_id" IN ({PURGED_SONGS})"#), before).await?;
+    purge_item_marks(txn, TrashItemType::Song, PURGED_SONGS, before).await?;
+    execute(txn, format!(r#"DELETE FROM "song_play_stat" WHERE "song_id" IN ({PURGED_SONGS})"#), before).await?;
+    let removed_entries = execute(txn, format!(r#"DELETE FROM "playlist_entry" WHERE "song_id" IN ({PURGED_SONGS})"#), before).await?;
+    if removed_entries > 0 {
+        txn.execute_unprepared(
+            r#"UPDATE "playlist_entry" e SET "position" = o."rn"
+            FROM (
+                SELECT "id", CAST(ROW_NUMBER() OVER (PARTITION BY "playlist_id" ORDER BY "position", "id") - 1 AS integer) AS "rn"
+                FROM "playlist_entry"
+            ) o
+            WHERE e."id" = o."id" AND e."position" <> o."rn""#,
+        ).await?;
+    }
+    result.songs = execute(txn, format!(r#"DELETE FROM "song" WHERE "id" IN ({PURGED_SONGS})"#), before).await?;
+    Ok(())
+}
+
+async fn purge_albums(txn: &DatabaseTransaction, before: DateTime<Local>, result: &mut PurgeResult) -> Result<(), DbErr> {
+    result.unreferenced_files.extend(query_paths(txn, format!(
+        r#"SELECT "cover_image" AS "path" FROM "album" WHERE "id" IN ({PURGED_ALBUMS}) AND "cover_image" IS NOT NULL"#,
+    ), before).await?);
+
+    purge_item_marks(txn, TrashItemType::Album, PURGED_ALBUMS, before).await?;
+    result.albums = execute(txn, format!(r#"DELETE FROM "album" WHERE "id" IN ({PURGED_ALBUMS})"#), before).await?;
+    Ok(())
+}
+
+async fn purge_artists(txn: &DatabaseTransaction, before: DateTime<Local>, result: &mut PurgeResult) -> Result<(), DbErr> {
+    result.unreferenced_files.extend(query_paths(txn, format!(
+        r#"SELECT "avatar" AS "path" FROM "artist" WHERE "id" IN ({PURGED_ARTISTS}) AND "avatar" IS NOT NULL"#,
+    ), before).await?);
+
+    purge_item_marks(txn, TrashItemType::Artist, PURGED_ARTISTS, before).await?;
+    result.artists = execute(txn, format!(r#"DELETE FROM "artist" WHERE "id" IN ({PURGED_ARTISTS})"#), before).await?;
+    Ok(())
+}
+
+// 用户：评分先从汇总中扣除，之后删除收藏、评分、播放队列、收听记录和用户的歌单
+async fn purge_users(txn: &DatabaseTransaction, before: DateTime<Local>, result: &mut PurgeResult) -> Result<(), DbErr> {
+    execute(txn, format!(
+        r#"UPDATE "rating_summary" s SET
+            "rating_count" = s."rating_count" - d."rating_count",
+            "rating_sum" = s."rating_sum" - d."rating_sum",
+            "average_rating" = CASE WHEN s."rating_count" > d."rating_count"
+                THEN CAST(s."rating_sum" - d."rating_sum" AS float8) / (s."rating_count" - d."rating_count") END,
+            "updated_at" = NOW()
+        FROM (
+            SELECT "item_type", "item_id", COUNT(*) AS "rating_count", CAST(SUM("rating") AS bigint) AS "rating_sum"
+            FROM "rating" WHERE "user_id" IN ({PURGED_USERS})
+            GROUP BY "item_type", "item_id"
+        ) d
+        WHERE s."item_type" = d."item_type" AND s."item_id" = d."item_id""#,
+    ), before).await?;
+
+    for table in ["rating", "star", "play_queue", "play_event", "listening_daily_song", "listening_daily_hour"] {
+        execute(txn, format!(r#"DELETE FROM "{table}" WHERE "user_id" IN ({PURGED_USERS})"#), before).await?;
+    }
+    execute(txn, format!(
+        r#"DELETE FROM "playlist_entry" WHERE "playlist_id" IN (SELECT "id" FROM "playlist" WHERE "owner_id" IN ({PURGED_USERS}))"#,
+    ), before).await?;
+    execute(txn, format!(r#"DELETE FROM "playlist" WHERE "owner_id" IN ({PURGED_USERS})"#), before).await?;
+    result.users = execute(txn, format!(r#"DELETE FROM "user" WHERE "id" IN ({PURGED_USERS})"#), before).await?;
+    Ok(())
+}
+
+// 从候选文件中去掉仍被引用的文件（内容寻址的文件可能被多首歌曲共用）
+async fn unreferenced(txn: &DatabaseTransaction, paths: Vec<String>) -> Result<Vec<String>, DbErr> {
+    if paths.is_empty() {
+        return Ok(paths);
+    }
+    let mut referenced: HashSet<String> = HashSet::new();
+    referenced.extend(song::Entity::find()
+        .select_only()
+        .column(song::Column::FilePath)
+        .filter(song::Column::FilePath.is_in(paths.iter().cloned()))
+        .into_tuple::<String>()
+        .all(txn)
+        .await?);
+    referenced.extend(song_version::Entity::find()
+        .select_only()
+        .column(song_version::Column::FilePath)
+        .filter(song_version::Column::FilePath.is_in(paths.iter().cloned()))
+        .into_tuple::<String>()
+        .all(txn)
+        .await?);
+    referenced.extend(album::Entity::find()
+        .select_only()
+        .column(album::Column::CoverImage)
+        .filter(album::Column::CoverImage.is_in(paths.iter().cloned()))
+        .into_tuple::<String>()
+        .all(txn)
+        .await?);
+    referenced.extend(artist::Entity::find()
+        .select_only()
+        .column(artist::Column::Avatar)
+        .filter(artist::Column::Avatar.is_in(paths.iter().cloned()))
+        .into_tuple::<String>()
+        .all(txn)
+        .await?);
+
+    let mut seen = HashSet::new();
+    Ok(paths.into_iter()
+        .filter(|path| !referenced.contains(path) && seen.insert(path.clone()))
+        .collect())
+}
+
+#[async_trait::async_trait]
+impl TrashRepository for SeaOrmTrashRepository {
+    async fn find_deleted(&self, item_type: Option<TrashItemType>, offset: u64, limit: u64) -> Result<Vec<TrashEntry>, DbErr> {
+        let sql = format!(
+            r#"SELECT t."item_type", t."id", t."name", t."deleted_at", t."deleted_by", u."username" AS "deleted_by_username"
+            FROM ({DELETED_SQL}) t
+            LEFT JOIN "user" u ON CAST(u."id" AS text) = t."deleted_by"
+            WHERE $1::text IS NULL OR t."item_type" = $1
+            ORDER BY t."deleted_at" DESC, t."id"
+            LIMIT $2 OFFSET $3"#,
+        );
+        let rows = self.db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [item_type.map(|item_type| item_type.as_str()).into(), (limit as i64).into(), (offset as i64).into()],
+            ))
+            .await?;
+        rows.iter()
+            .map(|row| Ok(TrashEntry {
+                item_type: row.try_get("", "item_type")?,
+                id: row.try_get("", "id")?,
+                name: row.try_get("", "name")?,
+                deleted_at: row.try_get("", "deleted_at")?,
+                deleted_by: row.try_get("", "deleted_by")?,
+                deleted_by_username: row.try_get("", "deleted_by_username")?,
+            }))
+            .collect()
+    }
+
+    // 同一次级联删除的对象有相同的删除时间和删除人，据此只恢复随该对象一起删除的下级对象，
+    // 之前单独删除的下级对象仍留在回收站中
+    async fn restore(&self, item_type: TrashItemType, id: Uuid, restored_by: &str) -> Result<RestoreResult, DbErr> {
+        let now = Local::now();
+        let txn = self.db.begin().await?;
+        let mut counts = RestoredCounts::default();
+        match item_type {
+            TrashItemType::User => {
+                let Some(deleted) = user::Entity::find_by_id(id)
+                    .filter(user::Column::DeleteFlag.eq(true))
+                    .lock_exclusive()
+                    .one(&txn)
+                    .await?
+                else {
+                    return Ok(RestoreResult::NotFound);
+                };
+                let taken = user::Entity::find()
+                    .filter(user::Column::Username.eq(deleted.username))
+                    .filter(user::Column::DeleteFlag.eq(false))
+                    .one(&txn)
+                    .await?
+                    .is_some();
+                if taken {
+                    return Ok(RestoreResult::UsernameTaken);
+                }
+                counts.users = user::Entity::update_many()
+                    .col_expr(user::Column::DeleteFlag, Expr::value(false))
+                    .col_expr(user::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(user::Column::UpdatedBy, Expr::value(restored_by))
+                    .filter(user::Column::Id.eq(id))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+            }
+            TrashItemType::Artist => {
+                let Some(deleted) = artist::Entity::find_by_id(id)
+                    .filter(artist::Column::DeleteFlag.eq(true))
+                    .lock_exclusive()
+                    .one(&txn)
+                    .await?
+                else {
+                    return Ok(RestoreResult::NotFound);
+                };
+                let album_ids: Vec<Uuid> = album::Entity::find()
+                    .select_only()
+                    .column(album::Column::Id)
+                    .filter(album::Column::ArtistId.eq(id))
+                    .filter(album::Column::DeleteFlag.eq(true))
+                    .filter(album::Column::UpdatedAt.eq(deleted.updated_at))
+                    .filter(album::Column::UpdatedBy.eq(deleted.updated_by.as_str()))
+                    .into_tuple()
+                    .all(&txn)
+                    .await?;
+                counts.songs = song::Entity::update_many()
+                    .col_expr(song::Column::DeleteFlag, Expr::value(false))
+                    .col_expr(song::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(song::Column::UpdatedBy, Expr::value(restored_by))
+                    .filter(song::Column::DeleteFlag.eq(true))
+                    .filter(song::Column::UpdatedAt.eq(deleted.updated_at))
+                    .filter(song::Column::UpdatedBy.eq(deleted.updated_by.as_str()))
+                    .filter(song::Column::ArtistId.eq(id).or(song::Column::AlbumId.is_in(album_ids.iter().copied())))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+                counts.albums = album::Entity::update_many()
+                    .col_expr(album::Column::DeleteFlag, Expr::value(false))
+                    .col_expr(album::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(album::Column::UpdatedBy, Expr::value(restored_by))
+                    .filter(album::Column::Id.is_in(album_ids))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+                counts.artists = artist::Entity::update_many()
+                    .col_expr(artist::Column::DeleteFlag, Expr::value(false))
+                    .col_expr(artist::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(artist::Column::UpdatedBy, Expr::value(restored_by))
+                    .filter(artist::Column::Id.eq(id))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+            }
+            TrashItemType::Album => {
+                let Some(deleted) = album::Entity::find_by_id(id)
+                    .filter(album::Column::DeleteFlag.eq(true))
+                    .lock_exclusive()
+                    .one(&txn)
+                    .await?
+                else {
+                    return Ok(RestoreResult::NotFound);
+                };
+                if artist_deleted(&txn, deleted.artist_id).await? {
+                    return Ok(RestoreResult::ParentDeleted(TrashItemType::Artist));
+                }
+                counts.songs = song::Entity::update_many()
+                    .col_expr(song::Column::DeleteFlag, Expr::value(false))
+                    .col_expr(song::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(song::Column::UpdatedBy, Expr::value(restored_by))
+                    .filter(song::Column::AlbumId.eq(id))
+                    .filter(song::Column::DeleteFlag.eq(true))
+                    .filter(song::Column::UpdatedAt.eq(deleted.updated_at))
+                    .filter(song::Column::UpdatedBy.eq(deleted.updated_by.as_str()))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+                counts.albums = album::Entity::update_many()
+                    .col_expr(album::Column::DeleteFlag, Expr::value(false))
+                    .col_expr(album::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(album::Column::UpdatedBy, Expr::value(restored_by))
+                    .filter(album::Column::Id.eq(id))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+            }
+            TrashItemType::Song => {
+                let Some(deleted) = song::Entity::find_by_id(id)
+                    .filter(song::Column::DeleteFlag.eq(true))
+                    .lock_exclusive()
+                    .one(&txn)
+                    .await?
+                else {
+                    return Ok(RestoreResult::NotFound);
+                };
+                let album_deleted = album::Entity::find_by_id(deleted.album_id)
+                    .one(&txn)
+                    .await?
+                    .is_none_or(|album| album.delete_flag);
+                if album_deleted {
+                    return Ok(RestoreResult::ParentDeleted(TrashItemType::Album));
+                }
+                if artist_deleted(&txn, deleted.artist_id).await? {
+                    return Ok(RestoreResult::ParentDeleted(TrashItemType::Artist));
+                }
+                counts.songs = song::Entity::update_many()
+                    .col_expr(song::Column::DeleteFlag, Expr::value(false))
+                    .col_expr(song::Column::UpdatedAt, Expr::value(now))
+                    .col_expr(song::Column::UpdatedBy, Expr::value(restored_by))
+                    .filter(song::Column::Id.eq(id))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+            }
+        }
+        txn.commit().await?;
+        Ok(RestoreResult::Restored(counts))
+    }
+
+    // 按歌曲、专辑、歌手、用户的顺序在同一事务中清理，返回可以删除的媒体文件，由调用方在提交后删除
+    async fn purge(&self, before: DateTime<Local>) -> Result<PurgeResult, DbErr> {
+        let txn = self.db.begin().await?;
+        let mut result = PurgeResult::default();
+        purge_songs(&txn, before, &mut result).await?;
+        purge_albums(&txn, before, &mut result).await?;
+        purge_artists(&txn, before, &mut result).await?;
+        purge_users(&txn, before, &mut result).await?;
+        result.unreferenced_files = unreferenced(&txn, std::mem::take(&mut result.unreferenced_files)).await?;
+        txn.commit().await?;
+        Ok(result)
+    }
+}
+
+async fn artist_deleted(txn: &DatabaseTransaction, artist_id: Uuid) -> Result<bool, DbErr> {
+    Ok(artist::Entity::find_by_id(artist_id)
+        .one(txn)
+        .await?
+        .is_none_or(|artist| artist.delete_flag))
+}
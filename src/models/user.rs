@@ -1,6 +1,6 @@
 use chrono::{DateTime, Local};
 use serde::{Serialize, Deserialize};
-use sea_orm::{ ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use sea_orm::{ ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Statement};
 use sea_orm::prelude::*;
 use uuid::Uuid;
 use std::sync::Arc;
@@ -17,6 +17,10 @@ pub struct Model {
     pub email: Option<String>,
     pub password_hash: String,
     pub role: String,
+    // Subsonic客户端使用的独立密码（加密存储）
+    pub subsonic_password: Option<String>,
+    // 统计听歌数据使用的IANA时区，为空时使用默认时区
+    pub timezone: Option<String>,
     #[sea_orm(indexed)]
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
@@ -51,10 +55,15 @@ pub type User = Model;
 // 定义用户仓库trait
 #[async_trait]
 pub trait UserRepository: Send + Sync {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DbErr>;
     async fn find_by_username(&self, username: &str) -> Result<Option<User>, DbErr>;
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, DbErr>;
     async fn create(&self, data: &CreateUserData) -> Result<User, DbErr>;
     async fn get_users(&self, query: &QueryUserData) -> Result<Vec<User>, DbErr>;
+    async fn update_subsonic_password(&self, id: Uuid, subsonic_password: Option<String>) -> Result<(), DbErr>;
+    async fn update_timezone(&self, id: Uuid, timezone: Option<String>) -> Result<(), DbErr>;
+    // 时区名称是否在数据库的时区库中
+    async fn is_known_timezone(&self, timezone: &str) -> Result<bool, DbErr>;
 }
 
 // 创建用户请求
@@ -100,6 +109,13 @@ impl SeaOrmUserRepository {
 
 #[async_trait]
 impl UserRepository for SeaOrmUserRepository {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DbErr> {
+        Entity::find()
+            .filter(Column::Id.eq(id)).filter(Column::DeleteFlag.eq(false))
+            .one(&*self.db)
+            .await
+    }
+
     async fn find_by_username(&self, username: &str) -> Result<Option<User>, DbErr> {
         Entity::find()
             .filter(Column::Username.eq(username)).filter(Column::DeleteFlag.eq(false))
@@ -158,6 +174,37 @@ impl UserRepository for SeaOrmUserRepository {
             .all(&*self.db)
             .await
     }
+
+    async fn update_subsonic_password(&self, id: Uuid, subsonic_password: Option<String>) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::SubsonicPassword, Expr::value(subsonic_password))
+            .col_expr(Column::UpdatedAt, Expr::value(Local::now()))
+            .filter(Column::Id.eq(id))
+            .exec(&*self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_timezone(&self, id: Uuid, timezone: Option<String>) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Timezone, Expr::value(timezone))
+            .col_expr(Column::UpdatedAt, Expr::value(Local::now()))
+            .filter(Column::Id.eq(id))
+            .exec(&*self.db)
+            .await?;
+        Ok(())
+    }
+
+    async fn is_known_timezone(&self, timezone: &str) -> Result<bool, DbErr> {
+        let row = self.db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT 1 FROM "pg_timezone_names" WHERE "name" = $1"#,
+                [timezone.into()],
+            ))
+            .await?;
+        Ok(row.is_some())
+    }
 }
 
 // 为User模型保留旧的数据访问方法（逐步迁移）
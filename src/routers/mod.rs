@@ -1,6 +1,6 @@
 use actix_web::{web};
 use super::handlers;
-use crate::middlewares::{auth::AuthMiddleware, logger::RequestLogger};
+use crate::middlewares::{admin::AdminMiddleware, auth::AuthMiddleware, logger::RequestLogger};
 
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -14,25 +14,127 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .service(
                 web::scope("")
                     .wrap(AuthMiddleware)
-                    // 歌手管理路由
+                    // 歌手管理路由（删除、替换音频文件等破坏性操作仅管理员可用，与回收站的恢复一致）
                     .service(web::resource("/artists")
                         .route(web::get().to(handlers::artists::get_artists))
                         .route(web::post().to(handlers::artists::create_artist))
                     )
-                    .service(web::resource("/artists/{id}").route(web::get().to(handlers::artists::get_artist_by_id)))
+                    .service(web::resource("/artists/{id}")
+                        .route(web::get().to(handlers::artists::get_artist_by_id))
+                        .route(web::put().to(handlers::artists::replace_artist).wrap(AdminMiddleware))
+                        .route(web::patch().to(handlers::artists::update_artist).wrap(AdminMiddleware))
+                        .route(web::delete().to(handlers::artists::delete_artist).wrap(AdminMiddleware))
+                    )
                     // 专辑管理路由
                     .service(web::resource("/albums")
                         .route(web::get().to(handlers::albums::get_albums))
                         .route(web::post().to(handlers::albums::create_album))
                     )
-                    .service(web::resource("/albums/{id}").route(web::get().to(handlers::albums::get_album_by_id)))
+                    .service(web::resource("/albums/{id}")
+                        .route(web::get().to(handlers::albums::get_album_by_id))
+                        .route(web::put().to(handlers::albums::replace_album).wrap(AdminMiddleware))
+                        .route(web::patch().to(handlers::albums::update_album).wrap(AdminMiddleware))
+                        .route(web::delete().to(handlers::albums::delete_album).wrap(AdminMiddleware))
+                    )
+                    .service(web::resource("/albums/{id}/tracks")
+                        .route(web::get().to(handlers::albums::get_tracklist))
+                        .route(web::put().to(handlers::albums::reorder_tracks).wrap(AdminMiddleware))
+                    )
                     // 歌曲管理路由
                     .service(web::resource("/songs")
                     .route(web::get().to(handlers::songs::get_songs))
                     .route(web::post().to(handlers::songs::create_song))
                     )
-                    .service(web::resource("/songs/{id}").route(web::get().to(handlers::songs::get_song_by_id)))
+                    .service(web::resource("/songs/upload").route(web::post().to(handlers::songs::upload_song)))
+                    .service(web::resource("/songs/{id}")
+                        .route(web::get().to(handlers::songs::get_song_by_id))
+                        .route(web::patch().to(handlers::songs::update_song))
+                        .route(web::delete().to(handlers::songs::delete_song).wrap(AdminMiddleware))
+                    )
+                    .service(web::resource("/songs/{id}/file").route(web::put().to(handlers::songs::replace_song_file).wrap(AdminMiddleware)))
+                    .service(web::resource("/songs/{id}/versions").route(web::get().to(handlers::songs::get_song_versions)))
+                    .service(web::resource("/songs/{id}/versions/{version_id}/restore").route(web::post().to(handlers::songs::restore_song_version).wrap(AdminMiddleware)))
+                    .service(web::resource("/songs/{id}/move").route(web::post().to(handlers::albums::move_song).wrap(AdminMiddleware)))
+                    .service(web::resource("/songs/{id}/tags").route(web::get().to(handlers::songs::get_song_tags)))
+                    .service(web::resource("/songs/{id}/stream").route(web::get().to(handlers::stream::stream_song)))
+                    .service(web::resource("/songs/{id}/hls/index.m3u8").route(web::get().to(handlers::stream::hls_master_playlist)))
+                    .service(web::resource("/songs/{id}/hls/{bitrate}/index.m3u8").route(web::get().to(handlers::stream::hls_variant_playlist)))
+                    .service(web::resource("/songs/{id}/hls/{bitrate}/{segment}").route(web::get().to(handlers::stream::hls_segment)))
                     .service(web::resource("/users").route(web::get().to(handlers::users::get_users)))
+                    .service(web::resource("/users/me/subsonic-password").route(web::put().to(handlers::users::set_subsonic_password)))
+                    // 收藏路由
+                    .service(web::resource("/users/me/starred/{kind}").route(web::get().to(handlers::stars::get_starred)))
+                    .service(web::resource("/{kind:songs|albums|artists}/{id}/star")
+                        .route(web::put().to(handlers::stars::star_item))
+                        .route(web::delete().to(handlers::stars::unstar_item))
+                    )
+                    // 评分路由
+                    .service(web::resource("/{kind:songs|albums}/{id}/rating")
+                        .route(web::put().to(handlers::ratings::rate_item))
+                        .route(web::delete().to(handlers::ratings::clear_rating))
+                    )
+                    // 署名路由
+                    .service(web::resource("/{kind:songs|albums}/{id}/credits")
+                        .route(web::get().to(handlers::credits::get_credits))
+                        .route(web::put().to(handlers::credits::set_credits).wrap(AdminMiddleware))
+                    )
+                    // 电台路由
+                    .service(web::resource("/{kind:songs|albums|artists}/{id}/radio").route(web::get().to(handlers::radio::get_radio)))
+                    // 播放记录路由
+                    .service(web::resource("/songs/{id}/scrobble").route(web::post().to(handlers::plays::scrobble)))
+                    .service(web::resource("/users/me/history").route(web::get().to(handlers::plays::get_play_history)))
+                    // 排行榜路由
+                    .service(web::resource("/charts/{kind:songs|albums|artists}/{chart:top|trending}").route(web::get().to(handlers::charts::get_chart)))
+                    // 听歌统计路由
+                    .service(web::resource("/users/me/stats").route(web::get().to(handlers::stats::get_listening_stats)))
+                    .service(web::resource("/users/me/year-in-review/{year}").route(web::get().to(handlers::stats::get_year_in_review)))
+                    .service(web::resource("/users/me/recommendations/{kind:albums|artists}").route(web::get().to(handlers::recommendations::get_recommendations)))
+                    .service(web::resource("/users/me/timezone").route(web::put().to(handlers::stats::set_timezone)))
+                    .service(web::resource("/users/me/play-queue")
+                        .route(web::get().to(handlers::play_queue::get_play_queue))
+                        .route(web::put().to(handlers::play_queue::save_play_queue)))
+                    // 歌单路由
+                    .service(web::resource("/playlists")
+                        .route(web::get().to(handlers::playlists::get_playlists))
+                        .route(web::post().to(handlers::playlists::create_playlist))
+                    )
+                    .service(web::resource("/playlists/import")
+                        .app_data(web::PayloadConfig::new(4 * 1024 * 1024))
+                        .route(web::post().to(handlers::playlists::import_playlist))
+                    )
+                    .service(web::resource("/playlists/{id}")
+                        .route(web::get().to(handlers::playlists::get_playlist_by_id))
+                        .route(web::patch().to(handlers::playlists::update_playlist))
+                        .route(web::delete().to(handlers::playlists::delete_playlist))
+                    )
+                    .service(web::resource("/playlists/{id}/export").route(web::get().to(handlers::playlists::export_playlist)))
+                    .service(web::resource("/playlists/{id}/entries").route(web::post().to(handlers::playlists::add_playlist_entries)))
+                    .service(web::resource("/playlists/{id}/entries/order").route(web::put().to(handlers::playlists::reorder_playlist)))
+                    .service(web::resource("/playlists/{id}/entries/{entry_id}").route(web::delete().to(handlers::playlists::remove_playlist_entry)))
+                    // 管理员路由
+                    .service(
+                        web::scope("/admin")
+                            .wrap(AdminMiddleware)
+                            .service(web::resource("/library/scan")
+                                .route(web::get().to(handlers::library::get_library_scan_status))
+                                .route(web::post().to(handlers::library::start_library_scan))
+                            )
+                            .service(web::resource("/transcode-cache")
+                                .route(web::get().to(handlers::transcode::get_transcode_cache_stats))
+                                .route(web::delete().to(handlers::transcode::purge_transcode_cache))
+                            )
+                            .service(web::resource("/trash").route(web::get().to(handlers::trash::list_trash)))
+                            .service(web::resource("/trash/{kind}/{id}/restore").route(web::post().to(handlers::trash::restore_trash_item)))
+                    )
+            )
+    );
+    // Subsonic兼容接口(使用Subsonic自身的认证参数)
+    cfg.service(
+        web::scope("/rest")
+            .wrap(RequestLogger)
+            .service(web::resource("/{method}")
+                .route(web::get().to(handlers::subsonic::subsonic_api))
+                .route(web::post().to(handlers::subsonic::subsonic_api))
             )
     );
 }
\ No newline at end of file
@@ -5,15 +5,31 @@ use self::model::*;
 use crate::models::{self, CreateAlbumData};
 use std::fmt;
 use std::sync::Arc;
-use crate::models::album::AlbumRepository;
+use crate::models::album::{AlbumRepository, AlbumSongsPolicy, TrackPosition, UpdateAlbumData};
+use crate::models::song::SongRepository;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
 use crate::models::artist::ArtistRepository;
-use crate::models::AlbumQueryData;
+use crate::models::star::{StarItemType, StarRepository};
+use crate::models::rating::{RatingItemType, RatingRepository};
+use crate::services::stars::star_status;
+use crate::services::ratings::rating_views;
+use crate::services::credits::credit_views;
+use crate::services::media;
+use crate::models::credit::{CreditItemType, CreditRepository};
 
 #[derive(Debug)]
 pub enum AlbumServiceError {
     DatabaseError(sea_orm::DbErr),
     ArtistNotFound,
     AlbumNotFound,
+    SongNotFound(Uuid),
+    InvalidName,
+    // 专辑还有未删除的歌曲，需要指定songs参数
+    AlbumHasSongs(usize),
+    InvalidTracklist(String),
+    InvalidPosition,
+    InvalidCoverImage(String),
 }
 
 impl fmt::Display for AlbumServiceError {
@@ -22,72 +38,109 @@ impl fmt::Display for AlbumServiceError {
             AlbumServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
             AlbumServiceError::ArtistNotFound => write!(f, "Artist not found"),
             AlbumServiceError::AlbumNotFound => write!(f, "Album not found"),
+            AlbumServiceError::SongNotFound(id) => write!(f, "Song not found: {}", id),
+            AlbumServiceError::InvalidName => write!(f, "Album name must not be empty"),
+            AlbumServiceError::AlbumHasSongs(count) => write!(
+                f,
+                "Album still has {} songs, pass songs=delete or songs=detach",
+                count
+            ),
+            AlbumServiceError::InvalidTracklist(message) => write!(f, "Invalid tracklist: {}", message),
+            AlbumServiceError::InvalidPosition => write!(f, "Disc and track numbers must be positive"),
+            AlbumServiceError::InvalidCoverImage(cover) => write!(f, "Invalid cover image: {}, expected an http(s) URL or a stored cover", cover),
         }
     }
 }
 
-/// 获取专辑列表服务
+impl From<sea_orm::DbErr> for AlbumServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        AlbumServiceError::DatabaseError(e)
+    }
+}
+
+/// 获取专辑列表服务（包含评分信息）
 pub async fn get_albums_service(
     query: AlbumQueryViewObject,
-    album_repo: Arc<dyn AlbumRepository + Send + Sync>
+    user_id: uuid::Uuid,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    rating_repo: Arc<dyn RatingRepository + Send + Sync>,
 ) -> Result<Vec<AlbumDetailViewObject>, AlbumServiceError> {
 
     let data_object = models::AlbumQueryData {
         artist_id: query.artist_id,
+        artist_role: query.artist_role,
         name: query.name.clone(),
         release_date: query.release_year.map(|year| NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or_default()),
-        page: query.page.map(|p| p as u32),
-        page_size: query.page_size.map(|l| l as u32),
+        min_rating: query.min_rating,
+        sort: query.sort.unwrap_or_default(),
+        page: query.page,
+        page_size: query.page_size,
     };
     let albums = album_repo.find_all(&data_object)
         .await
         .map_err(AlbumServiceError::DatabaseError)?;
 
+    let album_ids: Vec<uuid::Uuid> = albums.iter().map(|album| album.id).collect();
+    let mut ratings = rating_views(&rating_repo, user_id, RatingItemType::Album, &album_ids)
+        .await
+        .map_err(AlbumServiceError::DatabaseError)?;
+
     // 将DataObject转换为ViewObject
     let album_view_objects = albums
         .into_iter()
         .map(|album| AlbumDetailViewObject {
-            id: album.id,
-            name: album.name,
-            artist_id: album.artist_id,
-            cover_image: album.cover_image.unwrap_or_default(),
-            release_date: album.release_date,
-            description: album.description,
-            created_at: album.created_at.into(),
-            updated_at: album.updated_at.into(),
+            rating: Some(ratings.remove(&album.id).unwrap_or_default()),
+            ..album.into()
         })
         .collect();
 
     Ok(album_view_objects)
 }
 
-/// 根据ID获取专辑详情服务
+/// 根据ID获取专辑详情服务（包含评分信息、当前用户的收藏状态和署名）
 pub async fn get_album_by_id_service(
     album_id: uuid::Uuid,
-    album_repo: Arc<dyn AlbumRepository + Send + Sync>
+    user_id: uuid::Uuid,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    star_repo: Arc<dyn StarRepository + Send + Sync>,
+    rating_repo: Arc<dyn RatingRepository + Send + Sync>,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+    credit_repo: Arc<dyn CreditRepository + Send + Sync>,
 ) -> Result<Option<AlbumDetailViewObject>, AlbumServiceError> {
     let album = album_repo.find_by_id(album_id)
         .await
         .map_err(AlbumServiceError::DatabaseError)?;
+    let Some(album) = album else {
+        return Ok(None);
+    };
 
-    Ok(album.map(|album| AlbumDetailViewObject {
-        id: album.id,
-        name: album.name,
-        artist_id: album.artist_id,
-        cover_image: album.cover_image.unwrap_or_default(),
-        release_date: album.release_date,
-        description: album.description,
-        created_at: album.created_at.into(),
-        updated_at: album.updated_at.into(),
+    let star = star_status(&star_repo, user_id, StarItemType::Album, album.id)
+        .await
+        .map_err(AlbumServiceError::DatabaseError)?;
+    let rating = rating_views(&rating_repo, user_id, RatingItemType::Album, &[album.id])
+        .await
+        .map_err(AlbumServiceError::DatabaseError)?
+        .remove(&album.id)
+        .unwrap_or_default();
+    let credits = credit_views(&credit_repo, &artist_repo, CreditItemType::Album, album.id, album.artist_id).await?;
+    Ok(Some(AlbumDetailViewObject {
+        rating: Some(rating),
+        star: Some(star),
+        credits: Some(credits),
+        ..album.into()
     }))
 }
 
 /// 创建专辑服务
 pub async fn create_album_service(
     data: model::CreateAlbumViewObject,
+    created_by: String,
+    media_root: &str,
     album_repo: Arc<dyn AlbumRepository + Send + Sync>,
     artist_repo: Arc<dyn ArtistRepository + Send + Sync>
 ) -> Result<AlbumDetailViewObject, AlbumServiceError> {
+    check_cover_image(media_root, &data.cover_image)?;
+
     // 验证歌手是否存在
     let artist_exists = artist_repo.find_by_id(data.artist_id)
         .await
@@ -104,20 +157,274 @@ pub async fn create_album_service(
         release_date: data.release_date,
         description: data.description.clone(),
         genre: data.genre.clone(),
+        created_by,
     };
     // 创建专辑
     let album = album_repo.create(&data)
         .await
         .map_err(AlbumServiceError::DatabaseError)?;
 
-    Ok(AlbumDetailViewObject {
-        id: album.id,
-        name: album.name,
-        artist_id: album.artist_id,
-        cover_image: album.cover_image.unwrap_or_default(),
-        release_date: album.release_date,
-        description: album.description,
-        created_at: album.created_at.into(),
-        updated_at: album.updated_at.into(),
+    Ok(album.into())
+}
+
+/// 替换专辑服务（PUT），未传的可选字段被清空
+pub async fn replace_album_service(
+    album_id: Uuid,
+    data: CreateAlbumViewObject,
+    updated_by: String,
+    media_root: &str,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+) -> Result<AlbumDetailViewObject, AlbumServiceError> {
+    check_cover_image(media_root, &data.cover_image)?;
+    let update = UpdateAlbumData {
+        artist_id: Some(data.artist_id),
+        name: Some(album_name(&data.name)?),
+        description: Some(data.description),
+        cover_image: Some(Some(data.cover_image).filter(|cover| !cover.is_empty())),
+        genre: Some(data.genre),
+        release_date: Some(data.release_date),
+        updated_by,
+    };
+    update_album(album_id, update, album_repo, artist_repo).await
+}
+
+/// 部分更新专辑服务（PATCH），只更新传入的字段
+pub async fn update_album_service(
+    album_id: Uuid,
+    data: UpdateAlbumViewObject,
+    updated_by: String,
+    media_root: &str,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+) -> Result<AlbumDetailViewObject, AlbumServiceError> {
+    if let Some(Some(cover)) = &data.cover_image {
+        check_cover_image(media_root, cover)?;
+    }
+    let update = UpdateAlbumData {
+        artist_id: data.artist_id,
+        name: data.name.as_deref().map(album_name).transpose()?,
+        description: data.description,
+        cover_image: data.cover_image.map(|cover| cover.filter(|cover| !cover.is_empty())),
+        genre: data.genre,
+        release_date: data.release_date,
+        updated_by,
+    };
+    update_album(album_id, update, album_repo, artist_repo).await
+}
+
+/// 删除专辑服务（软删除）
+///
+/// 专辑中的歌曲按songs一并删除或移到歌手的"Unknown Album"，与专辑的删除在同一事务中完成
+pub async fn delete_album_service(
+    album_id: Uuid,
+    songs: Option<AlbumSongsPolicy>,
+    deleted_by: String,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<(), AlbumServiceError> {
+    if album_repo.find_by_id(album_id).await?.is_none() {
+        return Err(AlbumServiceError::AlbumNotFound);
+    }
+    let policy = match songs {
+        Some(policy) => policy,
+        None => {
+            let count = song_repo.find_by_album(album_id).await?.len();
+            if count > 0 {
+                return Err(AlbumServiceError::AlbumHasSongs(count));
+            }
+            AlbumSongsPolicy::Delete
+        }
+    };
+    album_repo.delete(album_id, &deleted_by, policy).await?;
+    Ok(())
+}
+
+/// 获取专辑曲目表服务
+pub async fn get_tracklist_service(
+    album_id: Uuid,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<TracklistViewObject, AlbumServiceError> {
+    if album_repo.find_by_id(album_id).await?.is_none() {
+        return Err(AlbumServiceError::AlbumNotFound);
+    }
+    tracklist(album_id, &song_repo).await
+}
+
+/// 重排专辑曲目服务，必须包含专辑的全部曲目
+pub async fn reorder_tracks_service(
+    album_id: Uuid,
+    data: ReorderTracksViewObject,
+    updated_by: String,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<TracklistViewObject, AlbumServiceError> {
+    if album_repo.find_by_id(album_id).await?.is_none() {
+        return Err(AlbumServiceError::AlbumNotFound);
+    }
+    let songs: HashMap<Uuid, Option<i32>> = song_repo.find_by_album(album_id)
+        .await?
+        .into_iter()
+        .map(|song| (song.id, song.disc_number))
+        .collect();
+    let positions = track_positions(&data.tracks, &songs)?;
+
+    album_repo.reorder_tracks(album_id, &positions, &updated_by).await?;
+    tracklist(album_id, &song_repo).await
+}
+
+/// 移动歌曲服务，返回目标专辑的曲目表
+pub async fn move_song_service(
+    song_id: Uuid,
+    data: MoveSongViewObject,
+    updated_by: String,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<TracklistViewObject, AlbumServiceError> {
+    let disc_number = data.disc_number.unwrap_or(1);
+    if disc_number < 1 || data.track_number.is_some_and(|track| track < 1) {
+        return Err(AlbumServiceError::InvalidPosition);
+    }
+    if song_repo.find_by_id(song_id).await?.is_none_or(|song| song.delete_flag) {
+        return Err(AlbumServiceError::SongNotFound(song_id));
+    }
+    if album_repo.find_by_id(data.album_id).await?.is_none() {
+        return Err(AlbumServiceError::AlbumNotFound);
+    }
+
+    album_repo.move_song(song_id, data.album_id, disc_number, data.track_number, &updated_by).await?;
+    tracklist(data.album_id, &song_repo).await
+}
+
+// 校验新的曲目顺序并计算每首歌曲的碟号和曲目号，songs为专辑全部歌曲及其当前碟号
+fn track_positions(
+    tracks: &[TrackOrderViewObject],
+    songs: &HashMap<Uuid, Option<i32>>,
+) -> Result<Vec<TrackPosition>, AlbumServiceError> {
+    let mut seen = HashSet::new();
+    if let Some(track) = tracks.iter().find(|track| !seen.insert(track.song_id)) {
+        return Err(AlbumServiceError::InvalidTracklist(format!("song {} appears more than once", track.song_id)));
+    }
+    if let Some(track) = tracks.iter().find(|track| !songs.contains_key(&track.song_id)) {
+        return Err(AlbumServiceError::InvalidTracklist(format!("song {} is not on this album", track.song_id)));
+    }
+    if seen.len() != songs.len() {
+        return Err(AlbumServiceError::InvalidTracklist("all songs of the album must be listed".to_string()));
+    }
+
+    let mut next_track: HashMap<i32, i32> = HashMap::new();
+    let mut positions = Vec::with_capacity(tracks.len());
+    for track in tracks {
+        let disc_number = track.disc_number
+            .or_else(|| songs.get(&track.song_id).copied().flatten())
+            .unwrap_or(1);
+        if disc_number < 1 {
+            return Err(AlbumServiceError::InvalidPosition);
+        }
+        let track_number = next_track.entry(disc_number).or_insert(0);
+        *track_number += 1;
+        positions.push(TrackPosition {
+            song_id: track.song_id,
+            disc_number,
+            track_number: *track_number,
+        });
+    }
+    Ok(positions)
+}
+
+async fn update_album(
+    album_id: Uuid,
+    data: UpdateAlbumData,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+) -> Result<AlbumDetailViewObject, AlbumServiceError> {
+    if album_repo.find_by_id(album_id).await?.is_none() {
+        return Err(AlbumServiceError::AlbumNotFound);
+    }
+    if let Some(artist_id) = data.artist_id
+        && artist_repo.find_by_id(artist_id).await?.is_none()
+    {
+        return Err(AlbumServiceError::ArtistNotFound);
+    }
+    let album = album_repo.update(album_id, &data).await?;
+    Ok(album.into())
+}
+
+async fn tracklist(
+    album_id: Uuid,
+    song_repo: &Arc<dyn SongRepository + Send + Sync>,
+) -> Result<TracklistViewObject, AlbumServiceError> {
+    let songs = song_repo.find_by_album(album_id).await?;
+    let song_count = songs.len();
+    let duration = songs.iter().map(|song| song.duration as u64).sum();
+
+    let mut discs: BTreeMap<i32, Vec<_>> = BTreeMap::new();
+    for song in songs {
+        discs.entry(song.disc_number.unwrap_or(1)).or_default().push(song);
+    }
+    Ok(TracklistViewObject {
+        album_id,
+        song_count,
+        duration,
+        discs: discs.into_iter()
+            .map(|(disc_number, songs)| DiscViewObject { disc_number, songs })
+            .collect(),
     })
-}
\ No newline at end of file
+}
+
+fn album_name(name: &str) -> Result<String, AlbumServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AlbumServiceError::InvalidName);
+    }
+    Ok(name.to_string())
+}
+
+// 封面只能是http(s)地址或媒体库封面目录内的文件
+fn check_cover_image(media_root: &str, cover: &str) -> Result<(), AlbumServiceError> {
+    if !media::is_valid_image_ref(media_root, cover) {
+        return Err(AlbumServiceError::InvalidCoverImage(cover.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(song_id: Uuid, disc_number: Option<i32>) -> TrackOrderViewObject {
+        TrackOrderViewObject { song_id, disc_number }
+    }
+
+    fn numbering(positions: &[TrackPosition]) -> Vec<(Uuid, i32, i32)> {
+        positions.iter().map(|position| (position.song_id, position.disc_number, position.track_number)).collect()
+    }
+
+    #[test]
+    fn numbers_tracks_per_disc_in_list_order() {
+        let (a, b, c, d) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let songs = HashMap::from([(a, Some(1)), (b, Some(2)), (c, None), (d, Some(1))]);
+        // c没有碟号时默认第1碟，d显式移到第2碟
+        let tracks = [track(b, None), track(c, None), track(d, Some(2)), track(a, None)];
+
+        let positions = track_positions(&tracks, &songs).unwrap();
+
+        assert_eq!(numbering(&positions), vec![(b, 2, 1), (c, 1, 1), (d, 2, 2), (a, 1, 2)]);
+    }
+
+    #[test]
+    fn rejects_incomplete_or_foreign_tracklists() {
+        let (a, b, other) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let songs = HashMap::from([(a, Some(1)), (b, Some(1))]);
+
+        let duplicate = track_positions(&[track(a, None), track(a, None)], &songs);
+        assert!(matches!(duplicate, Err(AlbumServiceError::InvalidTracklist(message)) if message.contains("more than once")));
+        let foreign = track_positions(&[track(a, None), track(other, None)], &songs);
+        assert!(matches!(foreign, Err(AlbumServiceError::InvalidTracklist(message)) if message.contains("not on this album")));
+        let missing = track_positions(&[track(a, None)], &songs);
+        assert!(matches!(missing, Err(AlbumServiceError::InvalidTracklist(message)) if message.contains("must be listed")));
+        let invalid_disc = track_positions(&[track(a, Some(0)), track(b, None)], &songs);
+        assert!(matches!(invalid_disc, Err(AlbumServiceError::InvalidPosition)));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{ DateTime, Local, NaiveDate };
+use crate::models::Song;
+use crate::models::album::AlbumSongsPolicy;
 
 /// 专辑查询视图对象
 #[derive(Debug, Deserialize)]
@@ -8,12 +10,18 @@ use chrono::{ DateTime, Local, NaiveDate };
 pub struct AlbumQueryViewObject {
     /// 专辑ID
     pub id: Option<Uuid>,
-    /// 歌手ID
+    /// 歌手ID，匹配主歌手及任一署名歌手
     pub artist_id: Option<Uuid>,
+    /// 只匹配以该角色署名的歌手，如artistRole=producer
+    pub artist_role: Option<crate::models::credit::CreditRole>,
     /// 专辑名称
     pub name: Option<String>,
     /// 发行年份
     pub release_year: Option<i32>,
+    /// 最低平均评分，如minRating=4
+    pub min_rating: Option<f64>,
+    /// 排序方式：releaseDate（默认）或rating
+    pub sort: Option<crate::models::album::AlbumSort>,
     /// 页码
     pub page: Option<u32>,
     /// 每页数量
@@ -37,6 +45,77 @@ pub struct CreateAlbumViewObject {
     pub  genre: Option<String>,
 }
 
+/// 部分更新专辑视图对象，未传的字段保持不变，可选字段传null时清空
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAlbumViewObject {
+    pub name: Option<String>,
+    pub artist_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    pub cover_image: Option<Option<String>>,
+    pub release_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    pub description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    pub genre: Option<Option<String>>,
+}
+
+/// 删除专辑查询参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAlbumQueryViewObject {
+    /// 专辑中歌曲的处理方式：delete一并删除，detach移到歌手的"Unknown Album"；专辑还有歌曲时必须指定
+    pub songs: Option<AlbumSongsPolicy>,
+}
+
+/// 曲目表视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracklistViewObject {
+    pub album_id: Uuid,
+    pub song_count: usize,
+    /// 总时长（秒）
+    pub duration: u64,
+    /// 按碟号分组，每碟内按曲目号排序
+    pub discs: Vec<DiscViewObject>,
+}
+
+/// 一张碟的曲目
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscViewObject {
+    pub disc_number: i32,
+    pub songs: Vec<Song>,
+}
+
+/// 重排曲目视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderTracksViewObject {
+    /// 专辑的全部曲目，按新顺序排列，曲目号按顺序在每碟内从1开始重新编号
+    pub tracks: Vec<TrackOrderViewObject>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackOrderViewObject {
+    pub song_id: Uuid,
+    /// 碟号，默认为歌曲当前的碟号
+    pub disc_number: Option<i32>,
+}
+
+/// 移动歌曲视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveSongViewObject {
+    /// 目标专辑，可以是歌曲当前所在的专辑
+    pub album_id: Uuid,
+    /// 目标碟号，默认为1
+    pub disc_number: Option<i32>,
+    /// 目标曲目号，默认放到该碟末尾
+    pub track_number: Option<i32>,
+}
+
 /// 专辑详情视图对象
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -58,4 +137,31 @@ pub struct AlbumDetailViewObject {
     pub created_at: DateTime<Local>,
     #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
     pub updated_at: DateTime<Local>,
+    /// 评分信息，列表和详情接口返回
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<crate::services::ratings::model::RatingViewObject>,
+    /// 当前用户的收藏状态，仅详情接口返回
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub star: Option<crate::services::stars::model::StarStatusViewObject>,
+    /// 署名歌手，仅详情接口返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credits: Option<Vec<crate::services::credits::model::CreditViewObject>>,
+}
+
+impl From<crate::models::album::Album> for AlbumDetailViewObject {
+    fn from(album: crate::models::album::Album) -> Self {
+        AlbumDetailViewObject {
+            id: album.id,
+            name: album.name,
+            artist_id: album.artist_id,
+            cover_image: album.cover_image.unwrap_or_default(),
+            release_date: album.release_date,
+            description: album.description,
+            created_at: album.created_at,
+            updated_at: album.updated_at,
+            rating: None,
+            star: None,
+            credits: None,
+        }
+    }
 }
\ No newline at end of file
@@ -1,13 +1,23 @@
 pub mod model;
 use std::sync::Arc;
-use crate::models::artist::{ArtistRepository, CreateArtistData, ArtistQueryData};
-use crate::services::artists::model::ArtistDetailViewObject;
+use chrono::NaiveDate;
+use uuid::Uuid;
+use crate::models::artist::{ArtistRepository, CreateArtistData, ArtistQueryData, UpdateArtistData};
+use crate::services::artists::model::{ArtistDetailViewObject, CreateArtistViewObject, UpdateArtistViewObject};
+use crate::models::star::{StarItemType, StarRepository};
+use crate::services::stars::star_status;
+use crate::services::media;
 use std::fmt;
 
 #[derive(Debug)]
 pub enum ArtistServiceError {
     DatabaseError(sea_orm::DbErr),
     ArtistNotFound,
+    InvalidName,
+    InvalidBirthDate(String),
+    InvalidAvatar(String),
+    // 歌手还有未删除的专辑或歌曲
+    ArtistInUse { albums: u64, songs: u64 },
 }
 
 impl fmt::Display for ArtistServiceError {
@@ -15,10 +25,24 @@ impl fmt::Display for ArtistServiceError {
         match self {
             ArtistServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
             ArtistServiceError::ArtistNotFound => write!(f, "Artist not found"),
+            ArtistServiceError::InvalidName => write!(f, "Artist name must not be empty"),
+            ArtistServiceError::InvalidBirthDate(date) => write!(f, "Invalid birth date: {}, expected YYYY-MM-DD", date),
+            ArtistServiceError::InvalidAvatar(avatar) => write!(f, "Invalid avatar: {}, expected an http(s) URL or a stored cover", avatar),
+            ArtistServiceError::ArtistInUse { albums, songs } => write!(
+                f,
+                "Artist still has {} albums and {} songs, pass cascade=true to delete them as well",
+                albums, songs
+            ),
         }
     }
 }
 
+impl From<sea_orm::DbErr> for ArtistServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        ArtistServiceError::DatabaseError(e)
+    }
+}
+
 /// 获取歌手列表服务
 pub async fn get_artists_service(
     query: ArtistQueryData,
@@ -31,61 +55,146 @@ pub async fn get_artists_service(
     // 将DataObject转换为ViewObject
     let artist_view_objects = artists
         .into_iter()
-        .map(|artist| model::ArtistDetailViewObject {
-              id: artist.id,
-              name: artist.name,
-              bio: artist.nationality.clone().unwrap_or_default(),
-              birth_date: Some(artist.birth_date.unwrap_or_default()),
-              avatar_url: Some(artist.avatar.clone().unwrap_or_default()),
-              sex: Some(artist.sex.clone().unwrap_or_default()),
-              created_at: artist.created_at.into(),
-              updated_at: artist.updated_at.into(),
-          })
+        .map(model::ArtistDetailViewObject::from)
         .collect();
 
     Ok(artist_view_objects)
 }
 
-/// 根据ID获取歌手详情服务
+/// 根据ID获取歌手详情服务（包含当前用户的收藏状态）
 pub async fn get_artist_by_id_service(
     artist_id: uuid::Uuid,
+    user_id: uuid::Uuid,
     artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+    star_repo: Arc<dyn StarRepository + Send + Sync>,
 ) -> Result<Option<ArtistDetailViewObject>, ArtistServiceError> {
     let artist = artist_repo.find_by_id(artist_id)
         .await
         .map_err(ArtistServiceError::DatabaseError)?;
+    let Some(artist) = artist else {
+        return Ok(None);
+    };
 
-    Ok(artist.map(|artist| ArtistDetailViewObject {
-        id: artist.id,
-        name: artist.name,
-        bio: artist.nationality.clone().unwrap_or_default(),
-        birth_date: Some(artist.birth_date.unwrap_or_default()),
-        avatar_url: Some(artist.avatar.clone().unwrap_or_default()),
-        sex: Some(artist.sex.clone().unwrap_or_default()),
-        created_at: artist.created_at.into(),
-        updated_at: artist.updated_at.into(),
+    let star = star_status(&star_repo, user_id, StarItemType::Artist, artist.id)
+        .await
+        .map_err(ArtistServiceError::DatabaseError)?;
+    Ok(Some(ArtistDetailViewObject {
+        star: Some(star),
+        ..artist.into()
     }))
 }
 
 /// 创建歌手服务
 pub async fn create_artist_service(
     data: CreateArtistData,
+    media_root: &str,
     artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
 ) -> Result<ArtistDetailViewObject, ArtistServiceError> {
+    check_avatar(media_root, data.avatar.as_deref())?;
     // 创建歌手
     let artist = artist_repo.create(&data)
         .await
         .map_err(ArtistServiceError::DatabaseError)?;
 
-    Ok(ArtistDetailViewObject {
-        id: artist.id,
-        name: artist.name,
-        bio: artist.nationality.clone().unwrap_or_default(),
-        birth_date: Some(artist.birth_date.unwrap_or_default()),
-        avatar_url: Some(artist.avatar.clone().unwrap_or_default()),
-        created_at: artist.created_at,
-        updated_at: artist.updated_at,
-        sex: Some(artist.sex.clone().unwrap_or_default()),
-
-    })
-}
\ No newline at end of file
+    Ok(artist.into())
+}
+
+/// 替换歌手服务（PUT），未传的可选字段被清空
+pub async fn replace_artist_service(
+    artist_id: Uuid,
+    data: CreateArtistViewObject,
+    updated_by: String,
+    media_root: &str,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+) -> Result<ArtistDetailViewObject, ArtistServiceError> {
+    check_avatar(media_root, data.avatar.as_deref())?;
+    let update = UpdateArtistData {
+        name: Some(artist_name(&data.name)?),
+        nationality: Some(data.nationality),
+        birth_date: Some(parse_birth_date(data.birth_date)?),
+        avatar: Some(data.avatar),
+        sex: Some(data.sex),
+        updated_by,
+    };
+    update_artist(artist_id, update, artist_repo).await
+}
+
+/// 部分更新歌手服务（PATCH），只更新传入的字段
+pub async fn update_artist_service(
+    artist_id: Uuid,
+    data: UpdateArtistViewObject,
+    updated_by: String,
+    media_root: &str,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+) -> Result<ArtistDetailViewObject, ArtistServiceError> {
+    check_avatar(media_root, data.avatar.as_ref().and_then(|avatar| avatar.as_deref()))?;
+    let update = UpdateArtistData {
+        name: data.name.as_deref().map(artist_name).transpose()?,
+        nationality: data.nationality,
+        birth_date: data.birth_date.map(parse_birth_date).transpose()?,
+        avatar: data.avatar,
+        sex: data.sex,
+        updated_by,
+    };
+    update_artist(artist_id, update, artist_repo).await
+}
+
+/// 删除歌手服务（软删除）
+///
+/// 歌手还有专辑或歌曲时，cascade为true则在同一事务中一并软删除，否则拒绝删除
+pub async fn delete_artist_service(
+    artist_id: Uuid,
+    cascade: bool,
+    deleted_by: String,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+) -> Result<(), ArtistServiceError> {
+    if artist_repo.find_by_id(artist_id).await?.is_none() {
+        return Err(ArtistServiceError::ArtistNotFound);
+    }
+    if !cascade {
+        let (albums, songs) = artist_repo.count_dependants(artist_id).await?;
+        if albums > 0 || songs > 0 {
+            return Err(ArtistServiceError::ArtistInUse { albums, songs });
+        }
+    }
+    artist_repo.delete(artist_id, &deleted_by, cascade).await?;
+    Ok(())
+}
+
+async fn update_artist(
+    artist_id: Uuid,
+    data: UpdateArtistData,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+) -> Result<ArtistDetailViewObject, ArtistServiceError> {
+    if artist_repo.find_by_id(artist_id).await?.is_none() {
+        return Err(ArtistServiceError::ArtistNotFound);
+    }
+    let artist = artist_repo.update(artist_id, &data).await?;
+    Ok(artist.into())
+}
+
+fn artist_name(name: &str) -> Result<String, ArtistServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ArtistServiceError::InvalidName);
+    }
+    Ok(name.to_string())
+}
+
+// 头像只能是http(s)地址或媒体库封面目录内的文件
+fn check_avatar(media_root: &str, avatar: Option<&str>) -> Result<(), ArtistServiceError> {
+    match avatar {
+        Some(avatar) if !media::is_valid_image_ref(media_root, avatar) => Err(ArtistServiceError::InvalidAvatar(avatar.to_string())),
+        _ => Ok(()),
+    }
+}
+
+// 空字符串表示清空出生日期
+fn parse_birth_date(date: Option<String>) -> Result<Option<NaiveDate>, ArtistServiceError> {
+    match date.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map(Some)
+            .map_err(|_| ArtistServiceError::InvalidBirthDate(date.to_string())),
+    }
+}
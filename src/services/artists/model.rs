@@ -11,6 +11,29 @@ pub struct CreateArtistViewObject {
     pub sex: Option<String>,
 }
 
+/// 部分更新歌手视图对象，未传的字段保持不变，可选字段传null时清空
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateArtistViewObject {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    pub nationality: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    pub birth_date: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    pub avatar: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::utils::nullable::deserialize")]
+    pub sex: Option<Option<String>>,
+}
+
+/// 删除歌手查询参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteArtistQueryViewObject {
+    /// 歌手还有专辑或歌曲时，为true则一并删除，否则拒绝删除
+    pub cascade: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ArtistQueryViewObject {
@@ -35,4 +58,23 @@ pub struct ArtistDetailViewObject {
     pub created_at: DateTime<Local>,
     #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
     pub updated_at: DateTime<Local>,
+    /// 当前用户的收藏状态，仅详情接口返回
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub star: Option<crate::services::stars::model::StarStatusViewObject>,
+}
+
+impl From<crate::models::artist::Artist> for ArtistDetailViewObject {
+    fn from(artist: crate::models::artist::Artist) -> Self {
+        ArtistDetailViewObject {
+            id: artist.id,
+            name: artist.name,
+            bio: artist.nationality.unwrap_or_default(),
+            birth_date: Some(artist.birth_date.unwrap_or_default()),
+            avatar_url: Some(artist.avatar.unwrap_or_default()),
+            sex: Some(artist.sex.unwrap_or_default()),
+            created_at: artist.created_at,
+            updated_at: artist.updated_at,
+            star: None,
+        }
+    }
 }
\ No newline at end of file
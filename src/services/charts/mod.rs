@@ -0,0 +1,141 @@
+pub mod model;
+use self::model::*;
+use chrono::Local;
+use crate::config::AppConfig;
+use crate::models::chart_entry::{CHART_SIZE, ChartEntry, ChartItemType, ChartOrder};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use uuid::Uuid;
+
+// 默认返回数量
+const DEFAULT_CHART_LIMIT: u64 = 50;
+
+#[derive(Debug)]
+pub enum ChartServiceError {
+    DatabaseError(sea_orm::DbErr),
+    InvalidItemType(String),
+    InvalidChart(String),
+}
+
+impl fmt::Display for ChartServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChartServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            ChartServiceError::InvalidItemType(kind) => write!(f, "Invalid item type: {}", kind),
+            ChartServiceError::InvalidChart(chart) => write!(f, "Invalid chart: {}", chart),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for ChartServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        ChartServiceError::DatabaseError(e)
+    }
+}
+
+/// 获取排行榜服务，读取定时任务预先计算的结果，已删除的对象被跳过
+pub async fn get_chart_service(
+    kind: &str,
+    chart: &str,
+    query: ChartQueryViewObject,
+    config: &AppConfig,
+) -> Result<ChartViewObject, ChartServiceError> {
+    let item_type = ChartItemType::parse(kind).ok_or_else(|| ChartServiceError::InvalidItemType(kind.to_string()))?;
+    let order = ChartOrder::parse(chart).ok_or_else(|| ChartServiceError::InvalidChart(chart.to_string()))?;
+    let period = query.period.unwrap_or_default();
+    let genre = query.genre
+        .map(|genre| genre.trim().to_lowercase())
+        .filter(|genre| !genre.is_empty());
+    let limit = query.limit.unwrap_or(DEFAULT_CHART_LIMIT).clamp(1, CHART_SIZE as u64);
+
+    let entries = config.chart_repo
+        .find_entries(item_type, period, genre.as_deref().unwrap_or_default(), order, limit)
+        .await?;
+    let computed_at = entries.first().map(|entry| entry.computed_at);
+    let ids: Vec<Uuid> = entries.iter().map(|entry| entry.item_id).collect();
+    let mut items: HashMap<Uuid, ChartItemViewObject> = match item_type {
+        ChartItemType::Song => config.song_repo.find_by_ids(&ids)
+            .await?
+            .into_iter()
+            .filter(|song| !song.delete_flag)
+            .map(|song| (song.id, ChartItemViewObject::Song(song)))
+            .collect(),
+        ChartItemType::Album => config.album_repo.find_by_ids(&ids)
+            .await?
+            .into_iter()
+            .filter(|album| !album.delete_flag)
+            .map(|album| (album.id, ChartItemViewObject::Album(album.into())))
+            .collect(),
+        ChartItemType::Artist => config.artist_repo.find_by_ids(&ids)
+            .await?
+            .into_iter()
+            .filter(|artist| !artist.delete_flag)
+            .map(|artist| (artist.id, ChartItemViewObject::Artist(artist.into())))
+            .collect(),
+    };
+
+    let entries = entries.into_iter()
+        .filter_map(|entry| {
+            let item = items.remove(&entry.item_id)?;
+            Some(entry_view(entry, order, item))
+        })
+        .collect();
+
+    Ok(ChartViewObject {
+        chart: order,
+        period,
+        genre,
+        computed_at,
+        entries,
+    })
+}
+
+/// 重新计算全部排行榜
+pub async fn refresh_charts_service(config: &AppConfig) -> Result<(), ChartServiceError> {
+    config.chart_repo.refresh(Local::now()).await?;
+    Ok(())
+}
+
+/// 启动排行榜定时刷新后台任务，启动时立即计算一次
+pub fn start_chart_scheduler(config: &AppConfig) {
+    let config = config.clone();
+    let period = Duration::from_secs(config.chart_refresh_minutes.max(1) * 60);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match refresh_charts_service(&config).await {
+                Ok(()) => log::info!("Charts refreshed"),
+                Err(e) => log::error!("Failed to refresh charts: {}", e),
+            }
+        }
+    });
+}
+
+// 热门榜与上一周期比较，趋势榜与上次刷新比较
+fn entry_view(entry: ChartEntry, order: ChartOrder, item: ChartItemViewObject) -> ChartEntryViewObject {
+    let (position, previous_position) = match order {
+        ChartOrder::Top => (entry.position, entry.previous_position),
+        ChartOrder::Trending => (entry.trending_position, entry.previous_trending_position),
+    };
+    let position_change = previous_position.map(|previous| previous - position);
+    let movement = match position_change {
+        None => ChartMovement::New,
+        Some(change) if change > 0 => ChartMovement::Up,
+        Some(change) if change < 0 => ChartMovement::Down,
+        Some(_) => ChartMovement::Same,
+    };
+    ChartEntryViewObject {
+        position,
+        previous_position,
+        movement,
+        position_change,
+        play_count: entry.play_count,
+        previous_play_count: entry.previous_play_count,
+        listener_count: entry.listener_count,
+        trending_score: entry.trending_score,
+        item,
+    }
+}
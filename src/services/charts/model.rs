@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local};
+use crate::models::Song;
+use crate::models::chart_entry::{ChartOrder, ChartPeriod};
+use crate::services::albums::model::AlbumDetailViewObject;
+use crate::services::artists::model::ArtistDetailViewObject;
+
+/// 排行榜查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartQueryViewObject {
+    /// 统计周期：day、week（默认）、month
+    pub period: Option<ChartPeriod>,
+    /// 流派，不区分大小写，为空时返回总榜
+    pub genre: Option<String>,
+    /// 返回数量，默认50
+    pub limit: Option<u64>,
+}
+
+/// 相对上一周期的名次变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChartMovement {
+    Up,
+    Down,
+    Same,
+    /// 上一周期不在榜单中
+    New,
+}
+
+/// 榜单条目对象
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum ChartItemViewObject {
+    Song(Song),
+    Album(AlbumDetailViewObject),
+    Artist(ArtistDetailViewObject),
+}
+
+/// 榜单条目视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartEntryViewObject {
+    /// 当前名次，从1开始
+    pub position: i32,
+    /// 上一周期的名次（热门榜）或上次刷新时的名次（趋势榜）
+    pub previous_position: Option<i32>,
+    pub movement: ChartMovement,
+    /// 名次变化，正数表示上升
+    pub position_change: Option<i32>,
+    /// 本周期收听次数
+    pub play_count: i64,
+    /// 上一周期收听次数
+    pub previous_play_count: i64,
+    /// 本周期收听人数
+    pub listener_count: i64,
+    pub trending_score: f64,
+    #[serde(flatten)]
+    pub item: ChartItemViewObject,
+}
+
+/// 排行榜视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartViewObject {
+    pub chart: ChartOrder,
+    pub period: ChartPeriod,
+    pub genre: Option<String>,
+    /// 榜单计算时间，尚未计算时为空
+    #[serde(serialize_with = "crate::utils::date_time::format_optional_datetime")]
+    pub computed_at: Option<DateTime<Local>>,
+    pub entries: Vec<ChartEntryViewObject>,
+}
@@ -0,0 +1,137 @@
+pub mod model;
+use self::model::*;
+use crate::config::AppConfig;
+use crate::models::artist::ArtistRepository;
+use crate::models::credit::{CreditItemType, CreditRepository, CreditRole};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// 单个对象的署名数量上限
+const MAX_CREDITS: usize = 100;
+
+#[derive(Debug)]
+pub enum CreditServiceError {
+    DatabaseError(sea_orm::DbErr),
+    InvalidItemType(String),
+    ItemNotFound(CreditItemType),
+    ArtistNotFound(Uuid),
+    DuplicateCredit(Uuid, CreditRole),
+    TooManyCredits,
+}
+
+impl fmt::Display for CreditServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CreditServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            CreditServiceError::InvalidItemType(kind) => write!(f, "Invalid item type: {}", kind),
+            CreditServiceError::ItemNotFound(item_type) => match item_type {
+                CreditItemType::Song => write!(f, "Song not found"),
+                CreditItemType::Album => write!(f, "Album not found"),
+            },
+            CreditServiceError::ArtistNotFound(id) => write!(f, "Artist not found: {}", id),
+            CreditServiceError::DuplicateCredit(id, role) => write!(f, "Artist {} is credited as {} more than once", id, role.as_str()),
+            CreditServiceError::TooManyCredits => write!(f, "At most {} credits are allowed", MAX_CREDITS),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for CreditServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        CreditServiceError::DatabaseError(e)
+    }
+}
+
+fn parse_item_type(kind: &str) -> Result<CreditItemType, CreditServiceError> {
+    CreditItemType::parse(kind).ok_or_else(|| CreditServiceError::InvalidItemType(kind.to_string()))
+}
+
+// 未删除的歌曲或专辑的主歌手
+async fn primary_artist(item_type: CreditItemType, item_id: Uuid, config: &AppConfig) -> Result<Uuid, CreditServiceError> {
+    let artist_id = match item_type {
+        CreditItemType::Song => config.song_repo.find_by_id(item_id)
+            .await?
+            .filter(|song| !song.delete_flag)
+            .map(|song| song.artist_id),
+        CreditItemType::Album => config.album_repo.find_by_id(item_id)
+            .await?
+            .map(|album| album.artist_id),
+    };
+    artist_id.ok_or(CreditServiceError::ItemNotFound(item_type))
+}
+
+/// 获取歌曲或专辑的署名服务
+pub async fn get_credits_service(
+    kind: &str,
+    item_id: Uuid,
+    config: &AppConfig,
+) -> Result<Vec<CreditViewObject>, CreditServiceError> {
+    let item_type = parse_item_type(kind)?;
+    let primary_artist_id = primary_artist(item_type, item_id, config).await?;
+    Ok(credit_views(&config.credit_repo, &config.artist_repo, item_type, item_id, primary_artist_id).await?)
+}
+
+/// 设置歌曲或专辑的署名服务，主歌手不变，已删除的歌手不能署名
+pub async fn set_credits_service(
+    kind: &str,
+    item_id: Uuid,
+    data: SetCreditsViewObject,
+    created_by: &str,
+    config: &AppConfig,
+) -> Result<Vec<CreditViewObject>, CreditServiceError> {
+    let item_type = parse_item_type(kind)?;
+    let primary_artist_id = primary_artist(item_type, item_id, config).await?;
+    if data.credits.len() > MAX_CREDITS {
+        return Err(CreditServiceError::TooManyCredits);
+    }
+
+    // 主歌手已隐含primary署名
+    let mut seen = HashSet::from([(primary_artist_id, CreditRole::Primary)]);
+    for credit in &data.credits {
+        if !seen.insert((credit.artist_id, credit.role)) {
+            return Err(CreditServiceError::DuplicateCredit(credit.artist_id, credit.role));
+        }
+    }
+    let artist_ids: Vec<Uuid> = data.credits.iter().map(|credit| credit.artist_id).collect();
+    let existing: HashSet<Uuid> = config.artist_repo.find_by_ids(&artist_ids)
+        .await?
+        .into_iter()
+        .map(|artist| artist.id)
+        .collect();
+    if let Some(missing) = artist_ids.iter().find(|id| !existing.contains(id)) {
+        return Err(CreditServiceError::ArtistNotFound(*missing));
+    }
+
+    let credits: Vec<(Uuid, CreditRole)> = data.credits.iter().map(|credit| (credit.artist_id, credit.role)).collect();
+    config.credit_repo.replace(item_type, item_id, &credits, created_by).await?;
+    Ok(credit_views(&config.credit_repo, &config.artist_repo, item_type, item_id, primary_artist_id).await?)
+}
+
+/// 查询对象的署名，供详情接口使用；主歌手以primary角色排在最前，已删除的歌手被跳过
+pub async fn credit_views(
+    credit_repo: &Arc<dyn CreditRepository + Send + Sync>,
+    artist_repo: &Arc<dyn ArtistRepository + Send + Sync>,
+    item_type: CreditItemType,
+    item_id: Uuid,
+    primary_artist_id: Uuid,
+) -> Result<Vec<CreditViewObject>, sea_orm::DbErr> {
+    let credits = credit_repo.find_by_item(item_type, item_id).await?;
+    let mut artist_ids = vec![primary_artist_id];
+    artist_ids.extend(credits.iter().map(|credit| credit.artist_id));
+    let names: HashMap<Uuid, String> = artist_repo.find_by_ids(&artist_ids)
+        .await?
+        .into_iter()
+        .map(|artist| (artist.id, artist.name))
+        .collect();
+
+    let credits = std::iter::once((primary_artist_id, Some(CreditRole::Primary)))
+        .chain(credits.iter().map(|credit| (credit.artist_id, CreditRole::parse(&credit.role))))
+        .filter_map(|(artist_id, role)| Some(CreditViewObject {
+            artist_id,
+            artist_name: names.get(&artist_id)?.clone(),
+            role: role?,
+        }))
+        .collect();
+    Ok(credits)
+}
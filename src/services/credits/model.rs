@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::models::credit::CreditRole;
+
+/// 署名视图对象，主歌手排在最前
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditViewObject {
+    pub artist_id: Uuid,
+    pub artist_name: String,
+    pub role: CreditRole,
+}
+
+/// 设置署名的单个条目
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreditInputViewObject {
+    pub artist_id: Uuid,
+    /// primary、featuring、composer、lyricist、producer、remixer
+    pub role: CreditRole,
+}
+
+/// 设置署名视图对象，整体替换主歌手之外的署名，列表顺序即显示顺序
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCreditsViewObject {
+    pub credits: Vec<CreditInputViewObject>,
+}
@@ -0,0 +1,567 @@
+pub mod model;
+pub mod watcher;
+use self::model::ScanStatusViewObject;
+use crate::config::AppConfig;
+use crate::models::album::{Album, AlbumRepository, UNKNOWN_ALBUM};
+use crate::models::artist::ArtistRepository;
+use crate::models::song::{Song, SongRepository};
+use crate::models::song_version::SongVersionRepository;
+use crate::models::{ArtistQueryData, CreateAlbumData, CreateArtistData, CreateSongRequest, UpdateSongData};
+use crate::services::media::{self, MediaServiceError, ALLOWED_EXTENSIONS};
+use crate::services::media::model::AudioTags;
+use chrono::{DateTime, Local, NaiveDate, Timelike};
+use sea_orm::DbErr;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::fs;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// 扫描器写入数据时记录的操作人
+pub const SCANNER_USER: &str = "library-scanner";
+
+/// 共享的扫描状态
+pub type ScanStatus = Arc<RwLock<ScanStatusViewObject>>;
+
+#[derive(Debug)]
+pub enum LibraryServiceError {
+    DatabaseError(DbErr),
+    MediaError(MediaServiceError),
+    ScanAlreadyRunning,
+    WatcherError(notify_debouncer_mini::notify::Error),
+}
+
+impl fmt::Display for LibraryServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LibraryServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            LibraryServiceError::MediaError(e) => write!(f, "{}", e),
+            LibraryServiceError::ScanAlreadyRunning => write!(f, "A library scan is already running"),
+            LibraryServiceError::WatcherError(e) => write!(f, "Watcher error: {}", e),
+        }
+    }
+}
+
+impl From<DbErr> for LibraryServiceError {
+    fn from(e: DbErr) -> Self {
+        LibraryServiceError::DatabaseError(e)
+    }
+}
+
+impl From<MediaServiceError> for LibraryServiceError {
+    fn from(e: MediaServiceError) -> Self {
+        LibraryServiceError::MediaError(e)
+    }
+}
+
+impl From<notify_debouncer_mini::notify::Error> for LibraryServiceError {
+    fn from(e: notify_debouncer_mini::notify::Error) -> Self {
+        LibraryServiceError::WatcherError(e)
+    }
+}
+
+impl From<std::io::Error> for LibraryServiceError {
+    fn from(e: std::io::Error) -> Self {
+        LibraryServiceError::MediaError(MediaServiceError::IoError(e))
+    }
+}
+
+/// 单个文件的导入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Added,
+    Updated,
+    Unchanged,
+}
+
+/// 媒体库扫描器
+///
+/// 按规范化名称匹配已有歌手和专辑，名称缓存在扫描器生命周期内复用
+pub struct LibraryScanner {
+    media_root: PathBuf,
+    pub(crate) song_repo: Arc<dyn SongRepository + Send + Sync>,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>,
+    song_version_repo: Arc<dyn SongVersionRepository + Send + Sync>,
+    artists: Option<HashMap<String, Uuid>>,
+    albums: HashMap<Uuid, HashMap<String, Album>>,
+}
+
+impl LibraryScanner {
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            media_root: PathBuf::from(&config.media_root),
+            song_repo: config.song_repo.clone(),
+            album_repo: config.album_repo.clone(),
+            artist_repo: config.artist_repo.clone(),
+            song_version_repo: config.song_version_repo.clone(),
+            artists: None,
+            albums: HashMap::new(),
+        }
+    }
+
+    /// 扫描整个媒体根目录，并实时更新扫描状态
+    pub async fn scan(&mut self, status: &ScanStatus) -> Result<(), LibraryServiceError> {
+        let root = self.media_root.clone();
+        let files = tokio::task::spawn_blocking(move || collect_audio_files(&root))
+            .await
+            .map_err(|e| MediaServiceError::IoError(std::io::Error::other(e)))?;
+        update_status(status, |s| s.total_files = files.len() as u64);
+        log::info!("Library scan found {} audio files under {:?}", files.len(), self.media_root);
+
+        let mut seen = HashSet::with_capacity(files.len());
+        for path in files {
+            let file_path = path.to_string_lossy().to_string();
+            update_status(status, |s| s.current_file = Some(file_path.clone()));
+
+            let result = self.import_file(&path).await;
+            update_status(status, |s| {
+                s.scanned_files += 1;
+                match &result {
+                    Ok(ImportOutcome::Added) => s.added += 1,
+                    Ok(ImportOutcome::Updated) => s.updated += 1,
+                    Ok(ImportOutcome::Unchanged) => s.unchanged += 1,
+                    Err(e) => {
+                        s.failed += 1;
+                        s.last_error = Some(format!("{}: {}", file_path, e));
+                    }
+                }
+                if s.scanned_files % 100 == 0 {
+                    log::info!("Library scan progress: {}/{}", s.scanned_files, s.total_files);
+                }
+            });
+            if let Err(e) = result {
+                log::warn!("Failed to import {}: {}", file_path, e);
+            }
+            seen.insert(file_path);
+        }
+
+        update_status(status, |s| s.current_file = None);
+        let missing = self.mark_missing(&seen).await?;
+        update_status(status, |s| s.missing = missing);
+        Ok(())
+    }
+
+    /// 导入或更新单个音频文件
+    ///
+    /// 大小和修改时间未变时直接跳过；内容哈希未变时只刷新文件状态；
+    /// 哈希与某个文件已丢失的歌曲相同时视为文件被移动，保留原歌曲ID
+    pub async fn import_file(&mut self, path: &Path) -> Result<ImportOutcome, LibraryServiceError> {
+        let metadata = fs::metadata(path).await?;
+        let file_size = metadata.len() as i64;
+        let file_modified_at = metadata.modified().ok()
+            .map(DateTime::<Local>::from)
+            .and_then(|time| time.with_nanosecond(0));
+        let file_path = path.to_string_lossy().to_string();
+
+        let existing = self.song_repo.find_by_file_path(&file_path).await?;
+        if let Some(song) = &existing
+            && !song.delete_flag
+            && song.file_size == Some(file_size)
+            && song.file_modified_at == file_modified_at
+        {
+            return Ok(ImportOutcome::Unchanged);
+        }
+        // 用户删除的歌曲不因文件仍在而恢复
+        if existing.as_ref().is_some_and(deleted_by_user) {
+            return Ok(ImportOutcome::Unchanged);
+        }
+        // 歌曲被替换前的文件作为历史版本保留，不导入为新歌曲
+        if existing.is_none() && self.song_version_repo.find_by_file_path(&file_path).await?.is_some() {
+            return Ok(ImportOutcome::Unchanged);
+        }
+
+        let hash = media::hash_file(path.to_path_buf()).await?;
+
+        if let Some(song) = existing {
+            let mut data = UpdateSongData {
+                file_hash: Some(hash.clone()),
+                file_size: Some(file_size),
+                file_modified_at,
+                delete_flag: Some(false),
+                updated_by: SCANNER_USER.to_string(),
+                ..Default::default()
+            };
+
+            if song.file_hash.as_deref() == Some(hash.as_str()) {
+                self.song_repo.update(song.id, &data).await?;
+                return Ok(if song.delete_flag { ImportOutcome::Updated } else { ImportOutcome::Unchanged });
+            }
+
+            // 文件内容已变化，重新读取标签
+            let tags = media::read_tags(path.to_path_buf(), file_extension(path)).await?;
+            let request = self.song_request_from_tags(path, &tags).await?;
+            data.album_id = Some(request.album_id);
+            data.artist_id = Some(request.artist_id);
+            data.title = Some(request.title);
+            data.genre = request.genre;
+            data.duration = Some(request.duration);
+            data.quality = Some(request.quality);
+            data.track_number = request.track_number;
+            data.disc_number = request.disc_number;
+            self.song_repo.update(song.id, &data).await?;
+            return Ok(ImportOutcome::Updated);
+        }
+
+        if self.relink_moved_song(&file_path, &hash, file_size, file_modified_at).await? {
+            return Ok(ImportOutcome::Updated);
+        }
+
+        let tags = media::read_tags(path.to_path_buf(), file_extension(path)).await?;
+        let request = CreateSongRequest {
+            file_path,
+            file_hash: Some(hash),
+            file_size: Some(file_size),
+            file_modified_at,
+            ..self.song_request_from_tags(path, &tags).await?
+        };
+        self.song_repo.create(&request).await?;
+        Ok(ImportOutcome::Added)
+    }
+
+    /// 将相同内容且原文件已不存在的歌曲指向新路径
+    pub async fn relink_moved_song(
+        &self,
+        file_path: &str,
+        hash: &str,
+        file_size: i64,
+        file_modified_at: Option<DateTime<Local>>,
+    ) -> Result<bool, LibraryServiceError> {
+        for song in self.song_repo.find_by_file_hash(hash).await? {
+            if song.file_path == file_path || deleted_by_user(&song) || fs::try_exists(&song.file_path).await.unwrap_or(false) {
+                continue;
+            }
+
+            let data = UpdateSongData {
+                file_path: Some(file_path.to_string()),
+                file_size: Some(file_size),
+                file_modified_at,
+                delete_flag: Some(false),
+                updated_by: SCANNER_USER.to_string(),
+                ..Default::default()
+            };
+            self.song_repo.update(song.id, &data).await?;
+            log::info!("Song {} moved from {} to {}", song.id, song.file_path, file_path);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// 将媒体根目录下文件已消失的歌曲标记为删除
+    pub async fn mark_missing(&self, seen: &HashSet<String>) -> Result<u64, LibraryServiceError> {
+        let prefix = self.media_root.to_string_lossy().to_string();
+        let mut missing = 0;
+
+        for song in self.song_repo.find_by_path_prefix(&prefix).await? {
+            // 字符串前缀也会匹配到同名前缀的兄弟目录（如./media2），按路径组件再确认一次
+            if !Path::new(&song.file_path).starts_with(&self.media_root) {
+                continue;
+            }
+            if seen.contains(&song.file_path) || fs::try_exists(&song.file_path).await.unwrap_or(false) {
+                continue;
+            }
+            self.remove_song(song.id).await?;
+            missing += 1;
+        }
+        Ok(missing)
+    }
+
+    /// 软删除歌曲（文件已不存在）
+    pub async fn remove_song(&self, song_id: Uuid) -> Result<(), LibraryServiceError> {
+        let data = UpdateSongData {
+            delete_flag: Some(true),
+            updated_by: SCANNER_USER.to_string(),
+            ..Default::default()
+        };
+        self.song_repo.update(song_id, &data).await?;
+        Ok(())
+    }
+
+    async fn song_request_from_tags(&mut self, path: &Path, tags: &AudioTags) -> Result<CreateSongRequest, LibraryServiceError> {
+        let artist_name = tags.album_artist.as_deref()
+            .or(tags.artist.as_deref())
+            .unwrap_or("Unknown Artist");
+        let album_artist_id = self.resolve_artist(artist_name).await?;
+        let artist_id = match tags.artist.as_deref() {
+            Some(name) => self.resolve_artist(name).await?,
+            None => album_artist_id,
+        };
+        let album = self.resolve_album(album_artist_id, tags.album.as_deref().unwrap_or(UNKNOWN_ALBUM), tags).await?;
+
+        let title = tags.title.clone()
+            .or_else(|| path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.to_string()))
+            .unwrap_or_default();
+
+        Ok(CreateSongRequest {
+            album_id: album.id,
+            artist_id,
+            title,
+            genre: tags.genre.clone(),
+            duration: tags.duration.unwrap_or_default(),
+            quality: tags.quality.clone().unwrap_or_else(|| "unknown".to_string()),
+            file_path: path.to_string_lossy().to_string(),
+            file_hash: None,
+            file_size: None,
+            file_modified_at: None,
+            track_number: tags.track_number,
+            disc_number: tags.disc_number,
+            created_by: SCANNER_USER.to_string(),
+        })
+    }
+
+    // 按规范化名称查找歌手，不存在时创建
+    async fn resolve_artist(&mut self, name: &str) -> Result<Uuid, LibraryServiceError> {
+        if self.artists.is_none() {
+            let query = ArtistQueryData {
+                id: None,
+                name: None,
+                nationality: None,
+                sex: None,
+                page: None,
+                page_size: None,
+            };
+            let artists = self.artist_repo.find_all(&query).await?;
+            self.artists = Some(
+                artists.into_iter()
+                    .filter(|artist| !artist.delete_flag)
+                    .map(|artist| (normalize_name(&artist.name), artist.id))
+                    .collect(),
+            );
+        }
+
+        let key = normalize_name(name);
+        if let Some(id) = self.artists.as_ref().and_then(|artists| artists.get(&key)) {
+            return Ok(*id);
+        }
+
+        let artist = self.artist_repo.create(&CreateArtistData {
+            name: name.trim().to_string(),
+            nationality: None,
+            birth_date: None,
+            avatar: None,
+            created_by: SCANNER_USER.to_string(),
+            sex: None,
+        }).await?;
+        if let Some(artists) = self.artists.as_mut() {
+            artists.insert(key, artist.id);
+        }
+        Ok(artist.id)
+    }
+
+    // 按规范化名称查找歌手下的专辑，不存在时创建
+    async fn resolve_album(&mut self, artist_id: Uuid, name: &str, tags: &AudioTags) -> Result<Album, LibraryServiceError> {
+        if !self.albums.contains_key(&artist_id) {
+            let albums = self.album_repo.find_by_artist(artist_id).await?;
+            self.albums.insert(
+                artist_id,
+                albums.into_iter().map(|album| (normalize_name(&album.name), album)).collect(),
+            );
+        }
+
+        let key = normalize_name(name);
+        let existing = self.albums.get(&artist_id).and_then(|albums| albums.get(&key)).cloned();
+        if let Some(mut album) = existing {
+            if album.cover_image.as_deref().unwrap_or_default().is_empty()
+                && let Some(cover_image) = self.store_cover(tags).await
+            {
+                self.album_repo.update_cover_image(album.id, &cover_image).await?;
+                album.cover_image = Some(cover_image);
+                if let Some(albums) = self.albums.get_mut(&artist_id) {
+                    albums.insert(key, album.clone());
+                }
+            }
+            return Ok(album);
+        }
+
+        let album = self.album_repo.create(&CreateAlbumData {
+            artist_id,
+            name: name.trim().to_string(),
+            description: None,
+            cover_image: self.store_cover(tags).await,
+            genre: tags.genre.clone(),
+            release_date: parse_release_date(tags.date.as_deref()),
+            created_by: SCANNER_USER.to_string(),
+        }).await?;
+        if let Some(albums) = self.albums.get_mut(&artist_id) {
+            albums.insert(key, album.clone());
+        }
+        Ok(album)
+    }
+
+    async fn store_cover(&self, tags: &AudioTags) -> Option<String> {
+        let (data, media_type) = (tags.cover_art.as_ref()?, tags.cover_art_type.as_ref()?);
+        let media_root = self.media_root.to_string_lossy();
+        match media::store_cover_art(&media_root, data, media_type).await {
+            Ok(path) => Some(path.to_string_lossy().to_string()),
+            Err(e) => {
+                log::warn!("Failed to store cover art: {:?}", e);
+                None
+            }
+        }
+    }
+}
+
+/// 启动后台扫描服务，已有扫描在进行时返回错误
+pub fn start_scan_service(config: &AppConfig) -> Result<ScanStatusViewObject, LibraryServiceError> {
+    let snapshot = begin_scan(&config.scan_status)?;
+    let config = config.clone();
+    tokio::spawn(async move {
+        let _guard = config.library_lock.lock().await;
+        let mut scanner = LibraryScanner::new(&config);
+        let result = scanner.scan(&config.scan_status).await;
+        finish_scan(&config.scan_status, result);
+    });
+    Ok(snapshot)
+}
+
+/// 前台执行一次完整扫描（命令行子命令使用）
+pub async fn run_scan_service(config: &AppConfig) -> Result<ScanStatusViewObject, LibraryServiceError> {
+    begin_scan(&config.scan_status)?;
+    let _guard = config.library_lock.lock().await;
+    let mut scanner = LibraryScanner::new(config);
+    let result = scanner.scan(&config.scan_status).await;
+    finish_scan(&config.scan_status, result);
+    Ok(get_scan_status_service(config))
+}
+
+/// 获取扫描状态服务
+pub fn get_scan_status_service(config: &AppConfig) -> ScanStatusViewObject {
+    config.scan_status.read().map(|status| status.clone()).unwrap_or_default()
+}
+
+fn begin_scan(status: &ScanStatus) -> Result<ScanStatusViewObject, LibraryServiceError> {
+    let mut status = status.write().unwrap_or_else(|e| e.into_inner());
+    if status.running {
+        return Err(LibraryServiceError::ScanAlreadyRunning);
+    }
+    *status = ScanStatusViewObject {
+        running: true,
+        started_at: Some(Local::now()),
+        ..Default::default()
+    };
+    Ok(status.clone())
+}
+
+fn finish_scan(status: &ScanStatus, result: Result<(), LibraryServiceError>) {
+    update_status(status, |s| {
+        s.running = false;
+        s.finished_at = Some(Local::now());
+        if let Err(e) = &result {
+            s.last_error = Some(e.to_string());
+        }
+        log::info!(
+            "Library scan finished: added={} updated={} unchanged={} missing={} failed={}",
+            s.added, s.updated, s.unchanged, s.missing, s.failed
+        );
+    });
+}
+
+fn update_status<F: FnOnce(&mut ScanStatusViewObject)>(status: &ScanStatus, f: F) {
+    let mut status = status.write().unwrap_or_else(|e| e.into_inner());
+    f(&mut status);
+}
+
+fn file_extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+}
+
+/// 判断路径是否为可入库的音频文件
+pub fn is_audio_file(path: &Path) -> bool {
+    file_extension(path)
+        .map(|ext| ALLOWED_EXTENSIONS.contains(&ext.as_str()))
+        .unwrap_or(false)
+}
+
+// 遍历媒体根目录，跳过隐藏目录（如上传临时目录）和封面目录
+pub(crate) fn collect_audio_files(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0 || !(name.starts_with('.') || (entry.depth() == 1 && name == "covers"))
+        })
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Failed to read library entry: {:?}", e);
+                None
+            }
+        })
+        .filter(|entry| entry.file_type().is_file() && is_audio_file(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// 规范化名称用于匹配：忽略大小写、标点和多余空白
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 歌曲由用户删除（而不是扫描时发现文件丢失）
+fn deleted_by_user(song: &Song) -> bool {
+    song.delete_flag && song.updated_by != SCANNER_USER
+}
+
+// 从日期标签中解析发行日期，只有年份时取当年1月1日
+fn parse_release_date(date: Option<&str>) -> NaiveDate {
+    let date = match date {
+        Some(date) => date.trim(),
+        None => return NaiveDate::default(),
+    };
+    if let Ok(date) = NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d") {
+        return date;
+    }
+    date.get(..4)
+        .and_then(|year| year.parse::<i32>().ok())
+        .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_release_dates_from_tags() {
+        assert_eq!(parse_release_date(Some("1959-08-17")), date(1959, 8, 17));
+        assert_eq!(parse_release_date(Some(" 1959-08-17T00:00:00Z ")), date(1959, 8, 17));
+        assert_eq!(parse_release_date(Some("1959")), date(1959, 1, 1));
+        assert_eq!(parse_release_date(Some("1959-13")), date(1959, 1, 1));
+        assert_eq!(parse_release_date(Some("unknown")), NaiveDate::default());
+        assert_eq!(parse_release_date(Some("")), NaiveDate::default());
+        assert_eq!(parse_release_date(None), NaiveDate::default());
+    }
+
+    #[test]
+    fn normalizes_names_for_matching() {
+        assert_eq!(normalize_name("  The Beatles "), "the beatles");
+        assert_eq!(normalize_name("AC/DC"), "ac dc");
+        assert_eq!(normalize_name("Guns N' Roses"), "guns n roses");
+        assert_eq!(normalize_name("Sigur Rós"), "sigur rós");
+        assert_eq!(normalize_name("周杰伦"), "周杰伦");
+        assert_eq!(normalize_name("..."), "");
+    }
+
+    #[test]
+    fn recognizes_audio_files_by_extension() {
+        assert!(is_audio_file(Path::new("music/a.FLAC")));
+        assert!(is_audio_file(Path::new("music/b.mp3")));
+        assert!(!is_audio_file(Path::new("music/cover.jpg")));
+        assert!(!is_audio_file(Path::new("music/flac")));
+    }
+}
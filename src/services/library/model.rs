@@ -0,0 +1,32 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+/// 媒体库扫描状态视图对象
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanStatusViewObject {
+    /// 是否正在扫描
+    pub running: bool,
+    #[serde(serialize_with = "crate::utils::date_time::format_optional_datetime")]
+    pub started_at: Option<DateTime<Local>>,
+    #[serde(serialize_with = "crate::utils::date_time::format_optional_datetime")]
+    pub finished_at: Option<DateTime<Local>>,
+    /// 本次扫描发现的音频文件数
+    pub total_files: u64,
+    /// 已处理的文件数
+    pub scanned_files: u64,
+    /// 新增歌曲数
+    pub added: u64,
+    /// 更新歌曲数
+    pub updated: u64,
+    /// 未变化跳过的文件数
+    pub unchanged: u64,
+    /// 文件已消失而被标记删除的歌曲数
+    pub missing: u64,
+    /// 处理失败的文件数
+    pub failed: u64,
+    /// 当前正在处理的文件
+    pub current_file: Option<String>,
+    /// 最近一次错误
+    pub last_error: Option<String>,
+}
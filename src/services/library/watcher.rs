@@ -0,0 +1,137 @@
+use super::{collect_audio_files, is_audio_file, LibraryScanner, LibraryServiceError};
+use crate::config::AppConfig;
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// 启动媒体目录监听后台任务
+///
+/// 文件事件经过防抖后批量处理：存在的路径重新导入，消失的路径软删除对应歌曲。
+/// 重命名由导入时的内容哈希匹配识别，歌曲ID保持不变
+pub fn start_watcher_service(config: &AppConfig) -> Result<(), LibraryServiceError> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(config.watch_debounce_ms),
+        move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                let _ = tx.send(events.into_iter().map(|event| event.path).collect());
+            }
+            Err(e) => log::error!("Library watcher error: {:?}", e),
+        },
+    )?;
+    debouncer.watcher().watch(Path::new(&config.media_root), RecursiveMode::Recursive)?;
+    log::info!("Watching media root {} for changes", config.media_root);
+
+    let config = config.clone();
+    tokio::spawn(async move {
+        // 任务存活期间保持监听器不被释放
+        let _debouncer = debouncer;
+        while let Some(paths) = rx.recv().await {
+            if let Err(e) = sync_changed_paths(&config, paths).await {
+                log::error!("Failed to sync library changes: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+// 同步一批变化的路径
+async fn sync_changed_paths(config: &AppConfig, paths: Vec<PathBuf>) -> Result<(), LibraryServiceError> {
+    let root = PathBuf::from(&config.media_root);
+    let paths: BTreeSet<PathBuf> = paths.into_iter().filter(|path| !is_ignored(&root, path)).collect();
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    // 与全量扫描互斥，避免重复创建歌曲
+    let _guard = config.library_lock.lock().await;
+    let mut scanner = LibraryScanner::new(config);
+    let mut removed = Vec::new();
+
+    // 先导入新出现的文件，使被移动的歌曲能按哈希重新关联
+    for path in paths {
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            removed.push(path);
+            continue;
+        }
+
+        let files = if path.is_dir() {
+            let dir = path.clone();
+            tokio::task::spawn_blocking(move || collect_audio_files(&dir)).await.unwrap_or_default()
+        } else if is_audio_file(&path) {
+            vec![path]
+        } else {
+            continue;
+        };
+
+        for file in files {
+            match scanner.import_file(&file).await {
+                Ok(outcome) => log::debug!("Library watcher imported {:?}: {:?}", file, outcome),
+                Err(e) => log::warn!("Failed to import {:?}: {}", file, e),
+            }
+        }
+    }
+
+    // 路径可能是文件也可能是目录，统一按前缀查找其下的歌曲
+    for path in removed {
+        let prefix = path.to_string_lossy().to_string();
+        let dir_prefix = format!("{}{}", prefix, std::path::MAIN_SEPARATOR);
+        for song in scanner.song_repo.find_by_path_prefix(&prefix).await? {
+            if song.file_path != prefix && !song.file_path.starts_with(&dir_prefix) {
+                continue;
+            }
+            if tokio::fs::try_exists(&song.file_path).await.unwrap_or(false) {
+                continue;
+            }
+            scanner.remove_song(song.id).await?;
+            log::info!("Song {} removed, file {} no longer exists", song.id, song.file_path);
+        }
+    }
+    Ok(())
+}
+
+// 忽略隐藏目录（上传临时目录等）和封面目录下的变化
+fn is_ignored(root: &Path, path: &Path) -> bool {
+    let relative = match path.strip_prefix(root) {
+        Ok(relative) => relative,
+        Err(_) => return false,
+    };
+    relative.components().enumerate().any(|(index, component)| match component {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            name.starts_with('.') || (index == 0 && name == "covers")
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::canonical_media_root;
+
+    #[tokio::test]
+    async fn absolute_event_paths_match_stored_paths() {
+        // 与默认的./media一样使用相对路径配置媒体根目录
+        let relative_root = format!("./target/watcher-media-{}", uuid::Uuid::new_v4());
+        std::fs::create_dir_all(format!("{}/covers", relative_root)).unwrap();
+        std::fs::write(format!("{}/song.flac", relative_root), b"fLaC").unwrap();
+        std::fs::write(format!("{}/covers/cover.flac", relative_root), b"fLaC").unwrap();
+
+        let root = PathBuf::from(canonical_media_root(&relative_root).await);
+        // 扫描入库的路径
+        let stored = collect_audio_files(&root);
+        // 文件监听上报的是绝对路径
+        let event = std::fs::canonicalize(format!("{}/song.flac", relative_root)).unwrap();
+        let cover_event = std::fs::canonicalize(format!("{}/covers/cover.flac", relative_root)).unwrap();
+
+        assert!(root.is_absolute());
+        assert_eq!(stored, vec![event.clone()]);
+        assert!(!is_ignored(&root, &event));
+        assert!(is_ignored(&root, &cover_event));
+        let _ = std::fs::remove_dir_all(&relative_root);
+    }
+}
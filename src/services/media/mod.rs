@@ -0,0 +1,355 @@
+pub mod model;
+pub mod tags;
+use self::model::AudioTags;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// 允许入库的音频格式（扩展名）
+pub const ALLOWED_EXTENSIONS: &[&str] = &["mp3", "flac", "m4a", "ogg", "opus", "wav"];
+
+// 识别文件格式所需的文件头长度
+const HEADER_SIZE: usize = 12;
+
+#[derive(Debug)]
+pub enum MediaServiceError {
+    UnsupportedFormat(String),
+    FileTooLarge(u64),
+    EmptyFile,
+    IoError(io::Error),
+}
+
+impl fmt::Display for MediaServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MediaServiceError::UnsupportedFormat(format) => write!(f, "Unsupported audio format: {}", format),
+            MediaServiceError::FileTooLarge(limit) => write!(f, "File exceeds the size limit of {} bytes", limit),
+            MediaServiceError::EmptyFile => write!(f, "Uploaded file is empty"),
+            MediaServiceError::IoError(e) => write!(f, "IO error: {:?}", e),
+        }
+    }
+}
+
+impl From<io::Error> for MediaServiceError {
+    fn from(e: io::Error) -> Self {
+        MediaServiceError::IoError(e)
+    }
+}
+
+/// 已存入媒体库的文件
+#[derive(Debug, Clone)]
+pub struct StoredMedia {
+    /// 文件完整路径
+    pub path: PathBuf,
+    /// SHA-256 内容哈希（十六进制）
+    pub hash: String,
+    /// 文件大小(字节)
+    pub size: u64,
+    /// 是否为本次新写入的文件（内容相同的文件已存在时为false）
+    pub newly_created: bool,
+}
+
+// 上传临时文件的守卫：上传对象未经persist或discard就被丢弃时
+// （如读取表单出错、客户端中断请求、persist中途失败）删除临时文件
+struct TempFile {
+    path: PathBuf,
+    armed: bool,
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if self.armed
+            && let Err(e) = std::fs::remove_file(&self.path)
+            && e.kind() != io::ErrorKind::NotFound
+        {
+            log::warn!("Failed to remove temp upload {:?}: {:?}", self.path, e);
+        }
+    }
+}
+
+/// 正在接收中的上传文件
+///
+/// 数据先写入媒体根目录下的临时文件并同步计算哈希，
+/// 调用 persist 后按内容哈希移动到最终位置
+pub struct MediaUpload {
+    media_root: PathBuf,
+    file_name: String,
+    temp: TempFile,
+    file: fs::File,
+    hasher: Sha256,
+    header: Vec<u8>,
+    size: u64,
+    max_size: u64,
+    extension: String,
+}
+
+impl MediaUpload {
+    pub async fn new(media_root: &str, file_name: &str, max_size: u64) -> Result<Self, MediaServiceError> {
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_default();
+        if !ALLOWED_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(MediaServiceError::UnsupportedFormat(extension));
+        }
+
+        let media_root = PathBuf::from(media_root);
+        let temp_dir = media_root.join(".tmp");
+        fs::create_dir_all(&temp_dir).await?;
+        let temp_path = temp_dir.join(Uuid::new_v4().to_string());
+        let file = fs::File::create(&temp_path).await?;
+
+        Ok(Self {
+            media_root,
+            file_name: file_name.to_string(),
+            temp: TempFile { path: temp_path, armed: true },
+            file,
+            hasher: Sha256::new(),
+            header: Vec::with_capacity(HEADER_SIZE),
+            size: 0,
+            max_size,
+            extension,
+        })
+    }
+
+    /// 客户端提供的原始文件名
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn extension(&self) -> &str {
+        &self.extension
+    }
+
+    /// 尚未持久化的临时文件路径，可用于入库前读取标签
+    pub fn temp_path(&self) -> &Path {
+        &self.temp.path
+    }
+
+    /// 写入一段数据，超过大小上限时返回错误
+    pub async fn write_chunk(&mut self, chunk: &[u8]) -> Result<(), MediaServiceError> {
+        // 收集文件头用于校验，防止仅修改扩展名的非音频文件入库
+        if self.header.len() < HEADER_SIZE {
+            let take = (HEADER_SIZE - self.header.len()).min(chunk.len());
+            self.header.extend_from_slice(&chunk[..take]);
+            if self.header.len() == HEADER_SIZE {
+                self.check_header()?;
+            }
+        }
+
+        self.size += chunk.len() as u64;
+        if self.size > self.max_size {
+            return Err(MediaServiceError::FileTooLarge(self.max_size));
+        }
+
+        self.hasher.update(chunk);
+        self.file.write_all(chunk).await?;
+        Ok(())
+    }
+
+    /// 将临时文件移动到内容寻址的位置
+    pub async fn persist(mut self) -> Result<StoredMedia, MediaServiceError> {
+        if self.size == 0 {
+            self.discard().await;
+            return Err(MediaServiceError::EmptyFile);
+        }
+        if self.header.len() < HEADER_SIZE && let Err(e) = self.check_header() {
+            self.discard().await;
+            return Err(e);
+        }
+        self.file.flush().await?;
+
+        let hash = hex::encode(self.hasher.finalize_reset());
+        let path = content_address(&self.media_root, &hash, &self.extension);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // 相同内容的文件已存在时直接复用
+        let newly_created = if fs::try_exists(&path).await? {
+            let _ = fs::remove_file(&self.temp.path).await;
+            false
+        } else {
+            fs::rename(&self.temp.path, &path).await?;
+            true
+        };
+        self.temp.armed = false;
+
+        Ok(StoredMedia {
+            path,
+            hash,
+            size: self.size,
+            newly_created,
+        })
+    }
+
+    fn check_header(&self) -> Result<(), MediaServiceError> {
+        match detect_audio_format(&self.header) {
+            Some(_) => Ok(()),
+            None => Err(MediaServiceError::UnsupportedFormat(self.extension.clone())),
+        }
+    }
+
+    /// 放弃本次上传并删除临时文件
+    pub async fn discard(mut self) {
+        self.temp.armed = false;
+        drop(self.file);
+        if let Err(e) = fs::remove_file(&self.temp.path).await {
+            log::warn!("Failed to remove temp upload {:?}: {:?}", self.temp.path, e);
+        }
+    }
+}
+
+/// 删除已存储的文件（仅删除本次新写入的文件）
+pub async fn remove_stored_media(stored: &StoredMedia) {
+    if !stored.newly_created {
+        return;
+    }
+    if let Err(e) = fs::remove_file(&stored.path).await {
+        log::warn!("Failed to remove stored media {:?}: {:?}", stored.path, e);
+    }
+}
+
+/// 在阻塞线程池中读取音频文件标签
+pub async fn read_tags(path: PathBuf, extension: Option<String>) -> Result<AudioTags, MediaServiceError> {
+    tokio::task::spawn_blocking(move || tags::read_audio_tags(&path, extension.as_deref()))
+        .await
+        .map_err(|e| MediaServiceError::IoError(io::Error::other(e)))?
+}
+
+/// 计算文件内容的 SHA-256 哈希
+pub async fn hash_file(path: PathBuf) -> Result<String, MediaServiceError> {
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)?;
+        let mut hasher = Sha256::new();
+        io::copy(&mut file, &mut hasher)?;
+        Ok(hex::encode(hasher.finalize()))
+    })
+    .await
+    .map_err(|e| MediaServiceError::IoError(io::Error::other(e)))?
+}
+
+/// 保存内嵌封面，按内容哈希命名: <media_root>/covers/<hash>.<ext>
+pub async fn store_cover_art(media_root: &str, data: &[u8], media_type: &str) -> Result<PathBuf, MediaServiceError> {
+    let extension = match media_type {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "jpg",
+    };
+    let hash = hex::encode(Sha256::digest(data));
+    let dir = Path::new(media_root).join("covers");
+    fs::create_dir_all(&dir).await?;
+
+    let path = dir.join(format!("{}.{}", hash, extension));
+    if !fs::try_exists(&path).await? {
+        fs::write(&path, data).await?;
+    }
+    Ok(path)
+}
+
+/// 封面/头像引用是否合法：http(s)地址，或位于<media_root>/covers内的本地路径
+pub fn is_valid_image_ref(media_root: &str, image: &str) -> bool {
+    if image.is_empty() || image.starts_with("http://") || image.starts_with("https://") {
+        return true;
+    }
+    let path = Path::new(image);
+    !path.components().any(|component| component == Component::ParentDir)
+        && path.starts_with(Path::new(media_root).join("covers"))
+}
+
+/// 解析本地封面路径，只返回规范化后仍位于<media_root>/covers内的文件
+pub async fn resolve_cover_path(media_root: &str, image: &str) -> Option<PathBuf> {
+    let covers = fs::canonicalize(Path::new(media_root).join("covers")).await.ok()?;
+    let path = fs::canonicalize(image).await.ok()?;
+    (path.starts_with(&covers) && path != covers).then_some(path)
+}
+
+/// 计算内容寻址路径: <media_root>/<hash[0..2]>/<hash[2..4]>/<hash>.<ext>
+pub fn content_address(media_root: &Path, hash: &str, extension: &str) -> PathBuf {
+    media_root
+        .join(&hash[0..2])
+        .join(&hash[2..4])
+        .join(format!("{}.{}", hash, extension))
+}
+
+/// 根据文件头识别音频格式
+pub fn detect_audio_format(header: &[u8]) -> Option<&'static str> {
+    if header.starts_with(b"fLaC") {
+        Some("flac")
+    } else if header.starts_with(b"ID3") || (header.len() > 1 && header[0] == 0xFF && header[1] & 0xE0 == 0xE0) {
+        Some("mp3")
+    } else if header.starts_with(b"OggS") {
+        Some("ogg")
+    } else if header.len() >= 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WAVE" {
+        Some("wav")
+    } else if header.len() >= 8 && &header[4..8] == b"ftyp" {
+        Some("m4a")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_refs_must_be_urls_or_stored_covers() {
+        assert!(is_valid_image_ref("/srv/media", "https://example.com/cover.jpg"));
+        assert!(is_valid_image_ref("/srv/media", "/srv/media/covers/abc.jpg"));
+        assert!(is_valid_image_ref("/srv/media", ""));
+        assert!(!is_valid_image_ref("/srv/media", "/etc/passwd"));
+        assert!(!is_valid_image_ref("/srv/media", ".env"));
+        assert!(!is_valid_image_ref("/srv/media", "/srv/media/song.flac"));
+        assert!(!is_valid_image_ref("/srv/media", "/srv/media/covers/../../../etc/passwd"));
+    }
+
+    #[tokio::test]
+    async fn cover_paths_outside_covers_dir_are_not_resolved() {
+        let media_root = std::env::temp_dir().join(format!("media-cover-{}", Uuid::new_v4()));
+        let root = media_root.to_str().unwrap();
+        let cover = store_cover_art(root, b"cover", "image/png").await.unwrap();
+        let outside = media_root.join("secret.txt");
+        std::fs::write(&outside, b"secret").unwrap();
+
+        assert!(resolve_cover_path(root, cover.to_str().unwrap()).await.is_some());
+        assert!(resolve_cover_path(root, outside.to_str().unwrap()).await.is_none());
+        let escaped = media_root.join("covers").join("..").join("secret.txt");
+        assert!(resolve_cover_path(root, escaped.to_str().unwrap()).await.is_none());
+        let _ = std::fs::remove_dir_all(&media_root);
+    }
+
+    #[tokio::test]
+    async fn dropped_upload_removes_temp_file() {
+        let media_root = std::env::temp_dir().join(format!("media-upload-{}", Uuid::new_v4()));
+        let mut upload = MediaUpload::new(media_root.to_str().unwrap(), "song.flac", 1024).await.unwrap();
+        upload.write_chunk(b"fLaC\0\0\0\x22\0\0\0\0").await.unwrap();
+        let temp_path = upload.temp_path().to_path_buf();
+        assert!(temp_path.exists());
+
+        drop(upload);
+
+        assert!(!temp_path.exists());
+        let _ = std::fs::remove_dir_all(&media_root);
+    }
+
+    #[tokio::test]
+    async fn persisted_upload_keeps_stored_file() {
+        let media_root = std::env::temp_dir().join(format!("media-upload-{}", Uuid::new_v4()));
+        let mut upload = MediaUpload::new(media_root.to_str().unwrap(), "song.flac", 1024).await.unwrap();
+        upload.write_chunk(b"fLaC\0\0\0\x22\0\0\0\0").await.unwrap();
+        let temp_path = upload.temp_path().to_path_buf();
+
+        let stored = upload.persist().await.unwrap();
+
+        assert!(!temp_path.exists());
+        assert!(stored.path.exists());
+        let _ = std::fs::remove_dir_all(&media_root);
+    }
+}
@@ -0,0 +1,40 @@
+use serde::Serialize;
+
+/// 从音频文件中提取的标签与音频流信息
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub genre: Option<String>,
+    /// 日期标签原文（如 2015 或 2015-06-01）
+    pub date: Option<String>,
+    /// 时长(秒)
+    pub duration: Option<u32>,
+    /// 比特率(kbps)
+    pub bitrate: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub bits_per_sample: Option<u32>,
+    pub channels: Option<u32>,
+    pub codec: Option<String>,
+    /// 根据编码和比特率推断的音质
+    pub quality: Option<String>,
+    /// 内嵌封面的MIME类型
+    pub cover_art_type: Option<String>,
+    #[serde(skip)]
+    pub cover_art: Option<Vec<u8>>,
+    /// 原始标签
+    pub raw_tags: Vec<RawTag>,
+}
+
+/// 原始标签键值
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTag {
+    pub key: String,
+    pub value: String,
+}
@@ -0,0 +1,148 @@
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey, StandardVisualKey, Value};
+use symphonia::core::probe::Hint;
+use super::model::{AudioTags, RawTag};
+use super::MediaServiceError;
+
+/// 读取音频文件的内嵌标签（ID3v2、FLAC/Vorbis注释、MP4 atoms）和流属性
+///
+/// 该函数为同步IO，在异步上下文中应通过 spawn_blocking 调用
+pub fn read_audio_tags(path: &Path, extension: Option<&str>) -> Result<AudioTags, MediaServiceError> {
+    let file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = extension.or_else(|| path.extension().and_then(|ext| ext.to_str())) {
+        hint.with_extension(extension);
+    }
+
+    let mut probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| MediaServiceError::UnsupportedFormat(e.to_string()))?;
+
+    let mut tags = AudioTags::default();
+
+    // 文件头部的标签（如MP3的ID3v2）在探测阶段读取，容器内的标签由FormatReader提供
+    if let Some(metadata) = probed.metadata.get()
+        && let Some(revision) = metadata.current()
+    {
+        apply_revision(&mut tags, revision);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_revision(&mut tags, revision);
+    }
+
+    let track = match probed.format.default_track() {
+        Some(track) => track.clone(),
+        None => return Ok(tags),
+    };
+    let params = &track.codec_params;
+
+    tags.sample_rate = params.sample_rate;
+    tags.bits_per_sample = params.bits_per_sample;
+    tags.channels = params.channels.map(|channels| channels.count() as u32);
+    tags.codec = symphonia::default::get_codecs()
+        .get_codec(params.codec)
+        .map(|descriptor| descriptor.short_name.to_string());
+
+    // 容器未记录总帧数时（如没有Xing头的MP3），逐包累加时长，无需解码
+    let n_frames = match params.n_frames {
+        Some(n_frames) => Some(n_frames),
+        None => {
+            let mut total = 0u64;
+            while let Ok(packet) = probed.format.next_packet() {
+                if packet.track_id() == track.id {
+                    total += packet.dur();
+                }
+            }
+            (total > 0).then_some(total)
+        }
+    };
+
+    let duration_secs = match (n_frames, params.time_base, params.sample_rate) {
+        (Some(n_frames), Some(time_base), _) => {
+            let time = time_base.calc_time(n_frames);
+            Some(time.seconds as f64 + time.frac)
+        }
+        (Some(n_frames), None, Some(sample_rate)) => Some(n_frames as f64 / sample_rate as f64),
+        _ => None,
+    };
+
+    if let Some(duration_secs) = duration_secs.filter(|secs| *secs > 0.0) {
+        tags.duration = Some(duration_secs.round() as u32);
+        tags.bitrate = Some((file_size as f64 * 8.0 / duration_secs / 1000.0).round() as u32);
+    }
+    tags.quality = derive_quality(&tags);
+
+    Ok(tags)
+}
+
+fn apply_revision(tags: &mut AudioTags, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        let value = match &tag.value {
+            Value::Binary(_) => continue,
+            value => value.to_string().trim().to_string(),
+        };
+        if value.is_empty() {
+            continue;
+        }
+
+        match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => tags.title = Some(value.clone()),
+            Some(StandardTagKey::Artist) => tags.artist = Some(value.clone()),
+            Some(StandardTagKey::Album) => tags.album = Some(value.clone()),
+            Some(StandardTagKey::AlbumArtist) => tags.album_artist = Some(value.clone()),
+            Some(StandardTagKey::TrackNumber) => tags.track_number = parse_position(&value),
+            Some(StandardTagKey::DiscNumber) => tags.disc_number = parse_position(&value),
+            Some(StandardTagKey::Genre) => tags.genre = Some(value.clone()),
+            Some(StandardTagKey::Date) | Some(StandardTagKey::ReleaseDate) => tags.date = Some(value.clone()),
+            Some(StandardTagKey::OriginalDate) if tags.date.is_none() => tags.date = Some(value.clone()),
+            _ => {}
+        }
+
+        tags.raw_tags.push(RawTag {
+            key: tag.key.clone(),
+            value,
+        });
+    }
+
+    // 优先使用正面封面，否则取第一张图片
+    let visual = revision.visuals()
+        .iter()
+        .find(|visual| visual.usage == Some(StandardVisualKey::FrontCover))
+        .or_else(|| revision.visuals().first());
+    if let Some(visual) = visual {
+        tags.cover_art_type = Some(visual.media_type.clone());
+        tags.cover_art = Some(visual.data.to_vec());
+    }
+}
+
+// 解析 "3" 或 "3/12" 形式的音轨号/碟号
+fn parse_position(value: &str) -> Option<i32> {
+    value.split('/').next()?.trim().parse::<i32>().ok().filter(|n| *n > 0)
+}
+
+/// 根据编码、采样参数和比特率推断音质
+pub fn derive_quality(tags: &AudioTags) -> Option<String> {
+    let codec = tags.codec.as_deref()?;
+    let lossless = codec == "flac" || codec == "alac" || codec.starts_with("pcm");
+
+    let quality = if lossless {
+        if tags.bits_per_sample.unwrap_or(16) > 16 || tags.sample_rate.unwrap_or(44100) > 48000 {
+            "hires"
+        } else {
+            "lossless"
+        }
+    } else {
+        match tags.bitrate? {
+            bitrate if bitrate >= 256 => "high",
+            bitrate if bitrate >= 128 => "standard",
+            _ => "low",
+        }
+    };
+    Some(quality.to_string())
+}
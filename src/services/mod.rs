@@ -3,3 +3,19 @@ pub mod albums;
 pub mod songs;
 pub mod auth;
 pub mod users;
+pub mod stream;
+pub mod media;
+pub mod library;
+pub mod transcode;
+pub mod subsonic;
+pub mod playlists;
+pub mod stars;
+pub mod ratings;
+pub mod plays;
+pub mod stats;
+pub mod charts;
+pub mod radio;
+pub mod recommendations;
+pub mod play_queue;
+pub mod trash;
+pub mod credits;
@@ -0,0 +1,126 @@
+pub mod model;
+use self::model::*;
+use crate::config::AppConfig;
+use crate::models::play_queue::{PlayQueue, SavePlayQueueData, SavePlayQueueResult};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+// 队列中歌曲数量上限
+const MAX_QUEUE_SIZE: usize = 1000;
+
+#[derive(Debug)]
+pub enum PlayQueueServiceError {
+    DatabaseError(sea_orm::DbErr),
+    TooManySongs,
+    InvalidCurrentIndex(i32),
+    InvalidPosition,
+    SongNotFound(Uuid),
+    // 队列已被其他设备更新，附带当前队列
+    VersionConflict(Box<PlayQueueViewObject>),
+}
+
+impl fmt::Display for PlayQueueServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayQueueServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            PlayQueueServiceError::TooManySongs => write!(f, "Play queue can contain at most {} songs", MAX_QUEUE_SIZE),
+            PlayQueueServiceError::InvalidCurrentIndex(index) => write!(f, "Invalid current index: {}", index),
+            PlayQueueServiceError::InvalidPosition => write!(f, "positionMs must not be negative"),
+            PlayQueueServiceError::SongNotFound(id) => write!(f, "Song not found: {}", id),
+            PlayQueueServiceError::VersionConflict(queue) => write!(f, "Play queue has been updated by another device (version {})", queue.version),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for PlayQueueServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        PlayQueueServiceError::DatabaseError(e)
+    }
+}
+
+/// 获取当前用户的播放队列，尚未保存过时返回空队列
+pub async fn get_play_queue_service(user_id: Uuid, config: &AppConfig) -> Result<PlayQueueViewObject, PlayQueueServiceError> {
+    let queue = config.play_queue_repo.find_by_user(user_id).await?;
+    Ok(queue_view(queue, config).await?)
+}
+
+/// 保存播放队列服务
+///
+/// 不带版本时后写入者胜出；带版本且队列已被其他设备更新时返回冲突和当前队列，由客户端决定是否覆盖
+pub async fn save_play_queue_service(
+    user_id: Uuid,
+    data: SavePlayQueueViewObject,
+    config: &AppConfig,
+) -> Result<PlayQueueViewObject, PlayQueueServiceError> {
+    if data.song_ids.len() > MAX_QUEUE_SIZE {
+        return Err(PlayQueueServiceError::TooManySongs);
+    }
+    let current_index = data.current_index.unwrap_or(0);
+    let in_range = current_index >= 0 && (current_index as usize) < data.song_ids.len().max(1);
+    if !in_range {
+        return Err(PlayQueueServiceError::InvalidCurrentIndex(current_index));
+    }
+    let position_ms = data.position_ms.unwrap_or(0);
+    if position_ms < 0 {
+        return Err(PlayQueueServiceError::InvalidPosition);
+    }
+
+    // 队列中可以有重复的歌曲，只校验去重后的ID
+    let unique: Vec<Uuid> = data.song_ids.iter().copied().collect::<HashSet<_>>().into_iter().collect();
+    let existing: HashSet<Uuid> = config.song_repo.find_by_ids(&unique)
+        .await?
+        .into_iter()
+        .filter(|song| !song.delete_flag)
+        .map(|song| song.id)
+        .collect();
+    if let Some(missing) = data.song_ids.iter().find(|id| !existing.contains(id)) {
+        return Err(PlayQueueServiceError::SongNotFound(*missing));
+    }
+
+    let save = SavePlayQueueData {
+        user_id,
+        song_ids: data.song_ids,
+        current_index,
+        position_ms,
+        client_name: data.client_name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty()),
+        base_version: data.version,
+    };
+    match config.play_queue_repo.save(&save).await? {
+        SavePlayQueueResult::Saved(queue) => Ok(queue_view(Some(queue), config).await?),
+        SavePlayQueueResult::Conflict(queue) => Err(PlayQueueServiceError::VersionConflict(Box::new(queue_view(queue, config).await?))),
+    }
+}
+
+async fn queue_view(queue: Option<PlayQueue>, config: &AppConfig) -> Result<PlayQueueViewObject, sea_orm::DbErr> {
+    let Some(queue) = queue else {
+        return Ok(PlayQueueViewObject {
+            song_ids: Vec::new(),
+            songs: Vec::new(),
+            current_index: 0,
+            position_ms: 0,
+            client_name: None,
+            version: 0,
+            updated_at: None,
+        });
+    };
+
+    let song_ids = queue.song_ids();
+    let songs: HashMap<Uuid, _> = config.song_repo.find_by_ids(&song_ids)
+        .await?
+        .into_iter()
+        .filter(|song| !song.delete_flag)
+        .map(|song| (song.id, song))
+        .collect();
+    Ok(PlayQueueViewObject {
+        songs: song_ids.iter().filter_map(|id| songs.get(id).cloned()).collect(),
+        song_ids,
+        current_index: queue.current_index,
+        position_ms: queue.position_ms,
+        client_name: queue.client_name,
+        version: queue.version,
+        updated_at: Some(queue.updated_at),
+    })
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+use crate::models::Song;
+
+/// 保存播放队列视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePlayQueueViewObject {
+    /// 有序的歌曲ID
+    pub song_ids: Vec<Uuid>,
+    /// 当前播放的歌曲下标，默认为0
+    pub current_index: Option<i32>,
+    /// 当前歌曲的播放进度（毫秒），默认为0
+    pub position_ms: Option<i64>,
+    /// 客户端名称，如"iPhone"、"Desktop"
+    pub client_name: Option<String>,
+    /// 本次修改所基于的队列版本，版本已变化时返回409；为空时直接覆盖
+    pub version: Option<i64>,
+}
+
+/// 播放队列视图对象
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayQueueViewObject {
+    pub song_ids: Vec<Uuid>,
+    /// 队列中的歌曲，已删除的歌曲被跳过
+    pub songs: Vec<Song>,
+    pub current_index: i32,
+    pub position_ms: i64,
+    pub client_name: Option<String>,
+    /// 队列版本，尚未保存过队列时为0
+    pub version: i64,
+    #[serde(serialize_with = "crate::utils::date_time::format_optional_datetime")]
+    pub updated_at: Option<DateTime<Local>>,
+}
@@ -0,0 +1,476 @@
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::reader::Reader;
+use std::io::Cursor;
+
+// 导出XSPF时需要转义的路径字符（保留路径分隔符）
+const PATH_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ').add(b'"').add(b'#').add(b'%').add(b'<').add(b'>')
+    .add(b'?').add(b'[').add(b']').add(b'^').add(b'`').add(b'{').add(b'|').add(b'}');
+
+/// 支持导入导出的歌单文件格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaylistFormat {
+    M3u8,
+    Pls,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "m3u8" | "m3u" => Some(PlaylistFormat::M3u8),
+            "pls" => Some(PlaylistFormat::Pls),
+            "xspf" => Some(PlaylistFormat::Xspf),
+            _ => None,
+        }
+    }
+
+    /// 根据文件内容推断格式
+    pub fn detect(content: &str) -> Option<Self> {
+        let head = content.trim_start_matches('\u{feff}').trim_start();
+        if head.starts_with("<?xml") || head.starts_with("<playlist") {
+            Some(PlaylistFormat::Xspf)
+        } else if head.to_lowercase().starts_with("[playlist]") {
+            Some(PlaylistFormat::Pls)
+        } else if head.starts_with("#EXTM3U") || !head.is_empty() {
+            Some(PlaylistFormat::M3u8)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            PlaylistFormat::M3u8 => "audio/x-mpegurl; charset=utf-8",
+            PlaylistFormat::Pls => "audio/x-scpls; charset=utf-8",
+            PlaylistFormat::Xspf => "application/xspf+xml; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            PlaylistFormat::M3u8 => "m3u8",
+            PlaylistFormat::Pls => "pls",
+            PlaylistFormat::Xspf => "xspf",
+        }
+    }
+}
+
+/// 导出的歌单条目
+#[derive(Debug)]
+pub struct ExportTrack {
+    pub location: String,
+    pub title: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub duration: u32,
+    pub track_number: Option<i32>,
+}
+
+/// 从歌单文件解析出的条目
+#[derive(Debug, Default, Clone)]
+pub struct ImportedTrack {
+    pub location: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub duration: Option<u32>,
+}
+
+/// 解析结果，XSPF和M3U8可能带有歌单名称
+#[derive(Debug, Default)]
+pub struct ParsedPlaylist {
+    pub name: Option<String>,
+    pub tracks: Vec<ImportedTrack>,
+}
+
+pub fn export(format: PlaylistFormat, name: &str, tracks: &[ExportTrack]) -> String {
+    match format {
+        PlaylistFormat::M3u8 => export_m3u8(name, tracks),
+        PlaylistFormat::Pls => export_pls(tracks),
+        PlaylistFormat::Xspf => export_xspf(name, tracks),
+    }
+}
+
+pub fn parse(format: PlaylistFormat, content: &str) -> Result<ParsedPlaylist, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    match format {
+        PlaylistFormat::M3u8 => Ok(parse_m3u8(content)),
+        PlaylistFormat::Pls => Ok(parse_pls(content)),
+        PlaylistFormat::Xspf => parse_xspf(content),
+    }
+}
+
+fn display_title(track: &ExportTrack) -> String {
+    match &track.artist {
+        Some(artist) => format!("{} - {}", artist, track.title),
+        None => track.title.clone(),
+    }
+}
+
+fn export_m3u8(name: &str, tracks: &[ExportTrack]) -> String {
+    let mut output = String::from("#EXTM3U\n");
+    output.push_str(&format!("#PLAYLIST:{}\n", single_line(name)));
+    for track in tracks {
+        output.push_str(&format!("#EXTINF:{},{}\n", track.duration, single_line(&display_title(track))));
+        output.push_str(&track.location);
+        output.push('\n');
+    }
+    output
+}
+
+fn export_pls(tracks: &[ExportTrack]) -> String {
+    let mut output = String::from("[playlist]\n");
+    for (index, track) in tracks.iter().enumerate() {
+        let number = index + 1;
+        output.push_str(&format!("File{}={}\n", number, track.location));
+        output.push_str(&format!("Title{}={}\n", number, single_line(&display_title(track))));
+        output.push_str(&format!("Length{}={}\n", number, track.duration));
+    }
+    output.push_str(&format!("NumberOfEntries={}\nVersion=2\n", tracks.len()));
+    output
+}
+
+fn export_xspf(name: &str, tracks: &[ExportTrack]) -> String {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    // 写入内存不会失败
+    let _ = writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)));
+    let _ = writer.create_element("playlist")
+        .with_attribute(("version", "1"))
+        .with_attribute(("xmlns", "http://xspf.org/ns/0/"))
+        .write_inner_content(|writer| {
+            writer.create_element("title").write_text_content(BytesText::new(name))?;
+            writer.create_element("trackList").write_inner_content(|writer| {
+                for track in tracks {
+                    writer.create_element("track").write_inner_content(|writer| {
+                        let location = utf8_percent_encode(&track.location, PATH_ENCODE_SET).to_string();
+                        writer.create_element("location").write_text_content(BytesText::new(&location))?;
+                        writer.create_element("title").write_text_content(BytesText::new(&track.title))?;
+                        if let Some(artist) = &track.artist {
+                            writer.create_element("creator").write_text_content(BytesText::new(artist))?;
+                        }
+                        if let Some(album) = &track.album {
+                            writer.create_element("album").write_text_content(BytesText::new(album))?;
+                        }
+                        if let Some(track_number) = track.track_number {
+                            writer.create_element("trackNum").write_text_content(BytesText::new(&track_number.to_string()))?;
+                        }
+                        // XSPF的时长单位为毫秒
+                        let duration = (track.duration as u64 * 1000).to_string();
+                        writer.create_element("duration").write_text_content(BytesText::new(&duration))?;
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            })?;
+            Ok(())
+        });
+    String::from_utf8_lossy(&writer.into_inner().into_inner()).into_owned()
+}
+
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+// 解析"歌手 - 标题"形式的显示名称
+fn split_display_title(text: &str) -> (Option<String>, Option<String>) {
+    let text = text.trim();
+    if text.is_empty() {
+        return (None, None);
+    }
+    match text.split_once(" - ") {
+        Some((artist, title)) if !artist.trim().is_empty() && !title.trim().is_empty() => {
+            (Some(artist.trim().to_string()), Some(title.trim().to_string()))
+        }
+        _ => (None, Some(text.to_string())),
+    }
+}
+
+fn parse_m3u8(content: &str) -> ParsedPlaylist {
+    let mut playlist = ParsedPlaylist::default();
+    let mut pending = ImportedTrack::default();
+
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:时长,歌手 - 标题，时长为-1表示未知
+            let (duration, display) = info.split_once(',').unwrap_or((info, ""));
+            let (artist, title) = split_display_title(display);
+            pending.duration = duration.trim().parse::<f64>().ok()
+                .filter(|duration| *duration > 0.0)
+                .map(|duration| duration.round() as u32);
+            pending.artist = artist;
+            pending.title = title;
+        } else if let Some(name) = line.strip_prefix("#PLAYLIST:") {
+            playlist.name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+        } else if !line.starts_with('#') {
+            pending.location = Some(line.to_string());
+            playlist.tracks.push(std::mem::take(&mut pending));
+        }
+    }
+    playlist
+}
+
+fn parse_pls(content: &str) -> ParsedPlaylist {
+    let mut tracks: Vec<(usize, ImportedTrack)> = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+        let (field, number) = match ["file", "title", "length"].iter().find(|field| key.starts_with(**field)) {
+            Some(field) => (*field, key[field.len()..].parse::<usize>().ok()),
+            None => continue,
+        };
+        let Some(number) = number else {
+            continue;
+        };
+
+        let index = match tracks.iter().position(|(n, _)| *n == number) {
+            Some(index) => index,
+            None => {
+                tracks.push((number, ImportedTrack::default()));
+                tracks.len() - 1
+            }
+        };
+        let track = &mut tracks[index].1;
+        match field {
+            "file" => track.location = Some(value.to_string()).filter(|value| !value.is_empty()),
+            "title" => {
+                let (artist, title) = split_display_title(value);
+                track.artist = artist;
+                track.title = title;
+            }
+            _ => track.duration = value.parse::<i64>().ok().filter(|duration| *duration > 0).map(|duration| duration as u32),
+        }
+    }
+
+    tracks.sort_by_key(|(number, _)| *number);
+    ParsedPlaylist {
+        name: None,
+        tracks: tracks.into_iter()
+            .map(|(_, track)| track)
+            .filter(|track| track.location.is_some() || track.title.is_some())
+            .collect(),
+    }
+}
+
+fn parse_xspf(content: &str) -> Result<ParsedPlaylist, String> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut playlist = ParsedPlaylist::default();
+    let mut path: Vec<String> = Vec::new();
+    let mut track: Option<ImportedTrack> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| format!("Invalid XSPF at position {}: {}", reader.buffer_position(), e))?;
+        match event {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                if name == "track" {
+                    track = Some(ImportedTrack::default());
+                }
+                path.push(name);
+            }
+            Event::End(_) => {
+                if path.pop().as_deref() == Some("track")
+                    && let Some(track) = track.take()
+                    && (track.location.is_some() || track.title.is_some())
+                {
+                    playlist.tracks.push(track);
+                }
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| format!("Invalid XSPF text: {}", e))?;
+                assign_xspf_text(&mut playlist, &mut track, &path, text.trim());
+            }
+            Event::CData(data) => {
+                let text = data.decode().map_err(|e| format!("Invalid XSPF text: {}", e))?;
+                assign_xspf_text(&mut playlist, &mut track, &path, text.trim());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !path.is_empty() {
+        return Err("Invalid XSPF: unexpected end of document".to_string());
+    }
+    Ok(playlist)
+}
+
+fn assign_xspf_text(playlist: &mut ParsedPlaylist, track: &mut Option<ImportedTrack>, path: &[String], text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let Some(element) = path.last() else {
+        return;
+    };
+    match track {
+        Some(track) => match element.as_str() {
+            // 只取第一个location
+            "location" if track.location.is_none() => track.location = Some(text.to_string()),
+            "title" => track.title = Some(text.to_string()),
+            "creator" => track.artist = Some(text.to_string()),
+            "duration" => track.duration = text.parse::<u64>().ok().map(|ms| ((ms + 500) / 1000) as u32),
+            _ => {}
+        },
+        None if path.len() == 2 && element == "title" => playlist.name = Some(text.to_string()),
+        None => {}
+    }
+}
+
+/// 将歌单中的位置规范化为路径：去掉file://前缀、解码URI转义并统一分隔符
+pub fn normalize_location(location: &str) -> String {
+    let location = location.trim();
+    let location = match location.strip_prefix("file://") {
+        Some(path) => {
+            let path = percent_decode_str(path).decode_utf8_lossy().into_owned();
+            // file:///C:/Music → C:/Music
+            match path.strip_prefix('/') {
+                Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest.to_string(),
+                _ => path,
+            }
+        }
+        None if location.contains('%') => percent_decode_str(location).decode_utf8_lossy().into_owned(),
+        None => location.to_string(),
+    };
+    location.replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export_tracks() -> Vec<ExportTrack> {
+        vec![
+            ExportTrack {
+                location: "Miles Davis/Kind of Blue/01 So What.flac".to_string(),
+                title: "So What".to_string(),
+                artist: Some("Miles Davis".to_string()),
+                album: Some("Kind of Blue".to_string()),
+                duration: 562,
+                track_number: Some(1),
+            },
+            ExportTrack {
+                location: "misc/untitled #2.mp3".to_string(),
+                title: "Untitled".to_string(),
+                artist: None,
+                album: None,
+                duration: 95,
+                track_number: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn detects_format_from_content() {
+        assert_eq!(PlaylistFormat::detect("\u{feff}<?xml version=\"1.0\"?><playlist/>"), Some(PlaylistFormat::Xspf));
+        assert_eq!(PlaylistFormat::detect("  [Playlist]\nFile1=a.mp3"), Some(PlaylistFormat::Pls));
+        assert_eq!(PlaylistFormat::detect("#EXTM3U\n"), Some(PlaylistFormat::M3u8));
+        assert_eq!(PlaylistFormat::detect("a.mp3\nb.mp3"), Some(PlaylistFormat::M3u8));
+        assert_eq!(PlaylistFormat::detect(" \n"), None);
+    }
+
+    #[test]
+    fn parses_m3u8() {
+        let content = "\u{feff}#EXTM3U\r\n#PLAYLIST:Road trip\r\n#EXTINF:-1,Nirvana - Lithium\r\nmusic/lithium.mp3\r\n\r\n# comment\r\n#EXTINF:241.6,Untitled\r\nC:\\Music\\untitled.flac\r\nbare.ogg\r\n";
+        let playlist = parse(PlaylistFormat::M3u8, content).unwrap();
+
+        assert_eq!(playlist.name.as_deref(), Some("Road trip"));
+        assert_eq!(playlist.tracks.len(), 3);
+        let first = &playlist.tracks[0];
+        assert_eq!(first.location.as_deref(), Some("music/lithium.mp3"));
+        assert_eq!(first.artist.as_deref(), Some("Nirvana"));
+        assert_eq!(first.title.as_deref(), Some("Lithium"));
+        assert_eq!(first.duration, None);
+        let second = &playlist.tracks[1];
+        assert_eq!(second.artist, None);
+        assert_eq!(second.title.as_deref(), Some("Untitled"));
+        assert_eq!(second.duration, Some(242));
+        // 没有#EXTINF的条目不继承上一条的信息
+        let third = &playlist.tracks[2];
+        assert_eq!(third.location.as_deref(), Some("bare.ogg"));
+        assert_eq!(third.title, None);
+    }
+
+    #[test]
+    fn parses_pls_by_entry_number() {
+        let content = "[playlist]\nTitle2=Second\nfile2=two.mp3\nFile1=one.mp3\nTitle1=Artist - First\nLength1=-1\nLength2=180\nFileX=ignored.mp3\nTitle3=\nNumberOfEntries=3\nVersion=2\n";
+        let playlist = parse(PlaylistFormat::Pls, content).unwrap();
+
+        assert_eq!(playlist.name, None);
+        assert_eq!(playlist.tracks.len(), 2);
+        assert_eq!(playlist.tracks[0].location.as_deref(), Some("one.mp3"));
+        assert_eq!(playlist.tracks[0].artist.as_deref(), Some("Artist"));
+        assert_eq!(playlist.tracks[0].title.as_deref(), Some("First"));
+        assert_eq!(playlist.tracks[0].duration, None);
+        assert_eq!(playlist.tracks[1].location.as_deref(), Some("two.mp3"));
+        assert_eq!(playlist.tracks[1].title.as_deref(), Some("Second"));
+        assert_eq!(playlist.tracks[1].duration, Some(180));
+    }
+
+    #[test]
+    fn parses_xspf() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<playlist version="1" xmlns="http://xspf.org/ns/0/">
+  <title>Evening &amp; night</title>
+  <trackList>
+    <track>
+      <location>file:///music/a%20b.flac</location>
+      <location>http://mirror/a.flac</location>
+      <title><![CDATA[Night & Day]]></title>
+      <creator>Ella Fitzgerald</creator>
+      <duration>200499</duration>
+    </track>
+    <track><annotation>nothing useful</annotation></track>
+  </trackList>
+</playlist>"#;
+        let playlist = parse(PlaylistFormat::Xspf, content).unwrap();
+
+        assert_eq!(playlist.name.as_deref(), Some("Evening & night"));
+        assert_eq!(playlist.tracks.len(), 1);
+        let track = &playlist.tracks[0];
+        assert_eq!(track.location.as_deref(), Some("file:///music/a%20b.flac"));
+        assert_eq!(track.title.as_deref(), Some("Night & Day"));
+        assert_eq!(track.artist.as_deref(), Some("Ella Fitzgerald"));
+        assert_eq!(track.duration, Some(200));
+    }
+
+    #[test]
+    fn rejects_malformed_xspf() {
+        assert!(parse(PlaylistFormat::Xspf, "<playlist><trackList><track>").is_err());
+        assert!(parse(PlaylistFormat::Xspf, "<playlist></trackList>").is_err());
+    }
+
+    #[test]
+    fn exported_playlists_parse_back() {
+        let tracks = export_tracks();
+        for format in [PlaylistFormat::M3u8, PlaylistFormat::Pls, PlaylistFormat::Xspf] {
+            let playlist = parse(format, &export(format, "Jazz\nnight", &tracks)).unwrap();
+
+            assert_eq!(playlist.tracks.len(), 2, "{:?}", format);
+            for (parsed, exported) in playlist.tracks.iter().zip(&tracks) {
+                let location = normalize_location(parsed.location.as_deref().unwrap());
+                assert_eq!(location, exported.location, "{:?}", format);
+                assert_eq!(parsed.title.as_deref(), Some(exported.title.as_str()), "{:?}", format);
+                assert_eq!(parsed.artist, exported.artist, "{:?}", format);
+                assert_eq!(parsed.duration, Some(exported.duration), "{:?}", format);
+            }
+            if format != PlaylistFormat::Pls {
+                assert!(playlist.name.is_some(), "{:?}", format);
+            }
+        }
+    }
+
+    #[test]
+    fn normalizes_locations() {
+        assert_eq!(normalize_location(" file:///C:/Music/a%20b.mp3 "), "C:/Music/a b.mp3");
+        assert_eq!(normalize_location("file:///srv/music/%E6%AD%8C.flac"), "/srv/music/歌.flac");
+        assert_eq!(normalize_location("Music\\Album\\01.flac"), "Music/Album/01.flac");
+        assert_eq!(normalize_location("misc/untitled%20%232.mp3"), "misc/untitled #2.mp3");
+    }
+}
@@ -0,0 +1,328 @@
+pub mod formats;
+pub mod model;
+pub mod transfer;
+use self::model::*;
+use crate::models::{Playlist, playlist::PlaylistRepository};
+use crate::models::smart_rule::SmartRules;
+use crate::models::song::SongRepository;
+use serde_json::Value;
+use crate::models::{CreatePlaylistData, PlaylistQueryData, UpdatePlaylistData};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub enum PlaylistServiceError {
+    DatabaseError(sea_orm::DbErr),
+    PlaylistNotFound,
+    PermissionDenied,
+    SongNotFound(Uuid),
+    EntryNotFound,
+    InvalidName,
+    InvalidOrder,
+    InvalidRules(String),
+    SmartPlaylistReadOnly,
+    NotSmartPlaylist,
+    UnsupportedFormat(String),
+    InvalidImport(String),
+}
+
+impl fmt::Display for PlaylistServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlaylistServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            PlaylistServiceError::PlaylistNotFound => write!(f, "Playlist not found"),
+            PlaylistServiceError::PermissionDenied => write!(f, "Only the owner can modify this playlist"),
+            PlaylistServiceError::SongNotFound(id) => write!(f, "Song not found: {}", id),
+            PlaylistServiceError::EntryNotFound => write!(f, "Playlist entry not found"),
+            PlaylistServiceError::InvalidName => write!(f, "Playlist name must not be empty"),
+            PlaylistServiceError::InvalidOrder => write!(f, "Entry ids must contain every entry of the playlist exactly once"),
+            PlaylistServiceError::InvalidRules(message) => write!(f, "Invalid smart playlist rules: {}", message),
+            PlaylistServiceError::SmartPlaylistReadOnly => write!(f, "Entries of a smart playlist are generated from its rules and cannot be edited"),
+            PlaylistServiceError::NotSmartPlaylist => write!(f, "Rules can only be set on smart playlists"),
+            PlaylistServiceError::UnsupportedFormat(format) => write!(f, "Unsupported playlist format: {}", format),
+            PlaylistServiceError::InvalidImport(message) => write!(f, "Invalid playlist file: {}", message),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for PlaylistServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        PlaylistServiceError::DatabaseError(e)
+    }
+}
+
+/// 获取歌单列表服务（自己的歌单和其他用户的公开歌单）
+pub async fn get_playlists_service(
+    query: PlaylistQueryViewObject,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<Vec<PlaylistViewObject>, PlaylistServiceError> {
+    let params = PlaylistQueryData {
+        viewer_id: user_id,
+        owner_id: query.owner_id,
+        name: query.name,
+        public: query.public,
+        page: query.page,
+        page_size: query.page_size,
+    };
+    let playlists = playlist_repo.find_all(&params).await?;
+    to_view_objects(playlists, &playlist_repo).await
+}
+
+/// 获取歌单详情服务，私有歌单只有所有者可见，智能歌单按规则实时求值
+pub async fn get_playlist_service(
+    playlist_id: Uuid,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<PlaylistDetailViewObject, PlaylistServiceError> {
+    let playlist = find_visible_playlist(playlist_id, user_id, &playlist_repo).await?;
+    if playlist.rules.is_some() {
+        return smart_playlist_detail(playlist, &song_repo).await;
+    }
+    playlist_detail(playlist, &playlist_repo).await
+}
+
+/// 创建歌单服务
+pub async fn create_playlist_service(
+    data: CreatePlaylistViewObject,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<PlaylistViewObject, PlaylistServiceError> {
+    let name = data.name.trim().to_string();
+    if name.is_empty() {
+        return Err(PlaylistServiceError::InvalidName);
+    }
+    let rules = data.rules.map(parse_rules).transpose()?;
+
+    let playlist = playlist_repo.create(&CreatePlaylistData {
+        owner_id: user_id,
+        name,
+        description: data.description,
+        public: data.public.unwrap_or(false),
+        rules,
+        created_by: user_id.to_string(),
+    }).await?;
+    let summary = playlist.rules.is_none().then_some((0, 0));
+    Ok(to_view_object(playlist, summary))
+}
+
+/// 更新歌单服务（重命名、修改描述和公开状态）
+pub async fn update_playlist_service(
+    playlist_id: Uuid,
+    data: UpdatePlaylistViewObject,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<PlaylistViewObject, PlaylistServiceError> {
+    let playlist = find_owned_playlist(playlist_id, user_id, &playlist_repo).await?;
+    let name = data.name.map(|name| name.trim().to_string());
+    if name.as_deref().is_some_and(str::is_empty) {
+        return Err(PlaylistServiceError::InvalidName);
+    }
+    if data.rules.is_some() && playlist.rules.is_none() {
+        return Err(PlaylistServiceError::NotSmartPlaylist);
+    }
+    let rules = data.rules.map(parse_rules).transpose()?;
+
+    let playlist = playlist_repo.update(playlist_id, &UpdatePlaylistData {
+        name,
+        description: data.description,
+        public: data.public,
+        rules,
+        updated_by: user_id.to_string(),
+    }).await?;
+    Ok(to_view_objects(vec![playlist], &playlist_repo).await?.remove(0))
+}
+
+/// 删除歌单服务
+pub async fn delete_playlist_service(
+    playlist_id: Uuid,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<(), PlaylistServiceError> {
+    find_owned_playlist(playlist_id, user_id, &playlist_repo).await?;
+    playlist_repo.delete(playlist_id, &user_id.to_string()).await?;
+    Ok(())
+}
+
+/// 向歌单添加歌曲服务
+pub async fn add_playlist_entries_service(
+    playlist_id: Uuid,
+    data: AddPlaylistEntriesViewObject,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<PlaylistDetailViewObject, PlaylistServiceError> {
+    let playlist = find_editable_playlist(playlist_id, user_id, &playlist_repo).await?;
+
+    let songs = song_repo.find_by_ids(&data.song_ids).await?;
+    if let Some(missing) = data.song_ids.iter().find(|id| !songs.iter().any(|song| song.id == **id && !song.delete_flag)) {
+        return Err(PlaylistServiceError::SongNotFound(*missing));
+    }
+
+    playlist_repo.add_entries(playlist_id, &data.song_ids, data.position, &user_id.to_string()).await?;
+    playlist_detail(playlist, &playlist_repo).await
+}
+
+/// 从歌单移除条目服务
+pub async fn remove_playlist_entry_service(
+    playlist_id: Uuid,
+    entry_id: Uuid,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<PlaylistDetailViewObject, PlaylistServiceError> {
+    let playlist = find_editable_playlist(playlist_id, user_id, &playlist_repo).await?;
+    if !playlist_repo.remove_entry(playlist_id, entry_id, &user_id.to_string()).await? {
+        return Err(PlaylistServiceError::EntryNotFound);
+    }
+    playlist_detail(playlist, &playlist_repo).await
+}
+
+/// 调整歌单条目顺序服务（整体替换顺序，在事务中完成）
+pub async fn reorder_playlist_service(
+    playlist_id: Uuid,
+    data: ReorderPlaylistViewObject,
+    user_id: Uuid,
+    playlist_repo: Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<PlaylistDetailViewObject, PlaylistServiceError> {
+    let playlist = find_editable_playlist(playlist_id, user_id, &playlist_repo).await?;
+    if !playlist_repo.reorder_entries(playlist_id, &data.entry_ids, &user_id.to_string()).await? {
+        return Err(PlaylistServiceError::InvalidOrder);
+    }
+    playlist_detail(playlist, &playlist_repo).await
+}
+
+pub(crate) async fn find_visible_playlist(
+    playlist_id: Uuid,
+    user_id: Uuid,
+    playlist_repo: &Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<Playlist, PlaylistServiceError> {
+    let playlist = playlist_repo.find_by_id(playlist_id)
+        .await?
+        .ok_or(PlaylistServiceError::PlaylistNotFound)?;
+    // 私有歌单对其他用户表现为不存在
+    if !playlist.public && playlist.owner_id != user_id {
+        return Err(PlaylistServiceError::PlaylistNotFound);
+    }
+    Ok(playlist)
+}
+
+async fn find_owned_playlist(
+    playlist_id: Uuid,
+    user_id: Uuid,
+    playlist_repo: &Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<Playlist, PlaylistServiceError> {
+    let playlist = find_visible_playlist(playlist_id, user_id, playlist_repo).await?;
+    if playlist.owner_id != user_id {
+        return Err(PlaylistServiceError::PermissionDenied);
+    }
+    Ok(playlist)
+}
+
+// 智能歌单的条目由规则生成，不能手动修改
+async fn find_editable_playlist(
+    playlist_id: Uuid,
+    user_id: Uuid,
+    playlist_repo: &Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<Playlist, PlaylistServiceError> {
+    let playlist = find_owned_playlist(playlist_id, user_id, playlist_repo).await?;
+    if playlist.rules.is_some() {
+        return Err(PlaylistServiceError::SmartPlaylistReadOnly);
+    }
+    Ok(playlist)
+}
+
+// 解析并校验智能歌单规则，返回规范化后的JSON用于保存
+fn parse_rules(rules: Value) -> Result<Value, PlaylistServiceError> {
+    let rules: SmartRules = serde_json::from_value(rules)
+        .map_err(|e| PlaylistServiceError::InvalidRules(e.to_string()))?;
+    rules.validate().map_err(|e| PlaylistServiceError::InvalidRules(e.to_string()))?;
+    serde_json::to_value(&rules).map_err(|e| PlaylistServiceError::InvalidRules(e.to_string()))
+}
+
+async fn smart_playlist_detail(
+    playlist: Playlist,
+    song_repo: &Arc<dyn SongRepository + Send + Sync>,
+) -> Result<PlaylistDetailViewObject, PlaylistServiceError> {
+    let rules: SmartRules = playlist.rules.clone()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| PlaylistServiceError::InvalidRules(e.to_string()))?
+        .ok_or(PlaylistServiceError::NotSmartPlaylist)?;
+    let songs = song_repo.find_by_rules(&rules).await?;
+    let duration = songs.iter().map(|song| song.duration as i64).sum();
+
+    Ok(PlaylistDetailViewObject {
+        playlist: to_view_object(playlist, Some((songs.len() as i64, duration))),
+        entries: songs.into_iter().enumerate().map(|(position, song)| PlaylistEntryViewObject {
+            id: song.id,
+            position: position as i32,
+            song_id: song.id,
+            title: song.title,
+            artist_id: song.artist_id,
+            album_id: song.album_id,
+            duration: song.duration,
+            added_at: song.created_at,
+        }).collect(),
+    })
+}
+
+async fn playlist_detail(
+    playlist: Playlist,
+    playlist_repo: &Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<PlaylistDetailViewObject, PlaylistServiceError> {
+    let entries = playlist_repo.find_entries(playlist.id).await?;
+    let duration = entries.iter().map(|(_, song)| song.duration as i64).sum();
+    let playlist = playlist_repo.find_by_id(playlist.id).await?.unwrap_or(playlist);
+
+    Ok(PlaylistDetailViewObject {
+        playlist: to_view_object(playlist, Some((entries.len() as i64, duration))),
+        entries: entries.into_iter().map(|(entry, song)| PlaylistEntryViewObject {
+            id: entry.id,
+            position: entry.position,
+            song_id: song.id,
+            title: song.title,
+            artist_id: song.artist_id,
+            album_id: song.album_id,
+            duration: song.duration,
+            added_at: entry.created_at,
+        }).collect(),
+    })
+}
+
+async fn to_view_objects(
+    playlists: Vec<Playlist>,
+    playlist_repo: &Arc<dyn PlaylistRepository + Send + Sync>,
+) -> Result<Vec<PlaylistViewObject>, PlaylistServiceError> {
+    let ids: Vec<Uuid> = playlists.iter().map(|playlist| playlist.id).collect();
+    let summaries: HashMap<Uuid, (i64, i64)> = playlist_repo.summaries(&ids)
+        .await?
+        .into_iter()
+        .map(|(id, count, duration)| (id, (count, duration)))
+        .collect();
+
+    Ok(playlists.into_iter().map(|playlist| {
+        // 智能歌单的内容只在获取详情时求值
+        let summary = playlist.rules.is_none()
+            .then(|| summaries.get(&playlist.id).copied().unwrap_or_default());
+        to_view_object(playlist, summary)
+    }).collect())
+}
+
+pub(crate) fn to_view_object(playlist: Playlist, summary: Option<(i64, i64)>) -> PlaylistViewObject {
+    PlaylistViewObject {
+        id: playlist.id,
+        name: playlist.name,
+        description: playlist.description,
+        public: playlist.public,
+        owner_id: playlist.owner_id,
+        smart: playlist.rules.is_some(),
+        rules: playlist.rules,
+        song_count: summary.map(|(song_count, _)| song_count),
+        duration: summary.map(|(_, duration)| duration),
+        created_at: playlist.created_at,
+        updated_at: playlist.updated_at,
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use chrono::{DateTime, Local};
+
+/// 歌单查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistQueryViewObject {
+    /// 歌单所有者ID
+    pub owner_id: Option<Uuid>,
+    /// 歌单名称
+    pub name: Option<String>,
+    /// 是否公开
+    pub public: Option<bool>,
+    /// 页码
+    pub page: Option<u64>,
+    /// 每页数量
+    pub page_size: Option<u64>,
+}
+
+/// 创建歌单视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePlaylistViewObject {
+    /// 歌单名称
+    pub name: String,
+    /// 歌单描述
+    pub description: Option<String>,
+    /// 是否公开，默认私有
+    pub public: Option<bool>,
+    /// 智能歌单规则，设置后歌单内容在每次获取时按规则求值
+    pub rules: Option<Value>,
+}
+
+/// 更新歌单视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePlaylistViewObject {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    /// 智能歌单规则，仅智能歌单可修改
+    pub rules: Option<Value>,
+}
+
+/// 添加歌单条目视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddPlaylistEntriesViewObject {
+    /// 要添加的歌曲ID，按顺序插入
+    pub song_ids: Vec<Uuid>,
+    /// 插入位置（从0开始），为空时追加到末尾
+    pub position: Option<i32>,
+}
+
+/// 歌单条目排序视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderPlaylistViewObject {
+    /// 歌单全部条目ID的新顺序
+    pub entry_ids: Vec<Uuid>,
+}
+
+/// 歌单视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistViewObject {
+    /// 歌单ID
+    pub id: Uuid,
+    /// 歌单名称
+    pub name: String,
+    /// 歌单描述
+    pub description: Option<String>,
+    /// 是否公开
+    pub public: bool,
+    /// 所有者ID
+    pub owner_id: Uuid,
+    /// 是否为智能歌单
+    pub smart: bool,
+    /// 智能歌单规则
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Value>,
+    /// 歌曲数量（智能歌单只在获取详情时求值，列表中为空）
+    pub song_count: Option<i64>,
+    /// 总时长(秒)
+    pub duration: Option<i64>,
+    #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
+    pub created_at: DateTime<Local>,
+    #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
+    pub updated_at: DateTime<Local>,
+}
+
+/// 歌单条目视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistEntryViewObject {
+    /// 条目ID（同一首歌可以多次出现在歌单中），智能歌单中为歌曲ID
+    pub id: Uuid,
+    /// 位置
+    pub position: i32,
+    pub song_id: Uuid,
+    pub title: String,
+    pub artist_id: Uuid,
+    pub album_id: Uuid,
+    pub duration: u32,
+    /// 加入歌单的时间，智能歌单中为歌曲入库时间
+    #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
+    pub added_at: DateTime<Local>,
+}
+
+/// 歌单详情视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistDetailViewObject {
+    #[serde(flatten)]
+    pub playlist: PlaylistViewObject,
+    pub entries: Vec<PlaylistEntryViewObject>,
+}
+
+/// 歌单导出查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistExportQueryViewObject {
+    /// 导出格式：m3u8（默认）、pls、xspf
+    pub format: Option<String>,
+}
+
+/// 歌单导入查询视图对象，请求体为歌单文件内容
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistImportQueryViewObject {
+    /// 歌单名称，为空时使用文件中的名称
+    pub name: Option<String>,
+    /// 文件格式：m3u8、pls、xspf，为空时根据内容推断
+    pub format: Option<String>,
+    /// 是否公开，默认私有
+    pub public: Option<bool>,
+}
+
+/// 导入文件中的原始条目
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEntryViewObject {
+    /// 条目在文件中的序号（从0开始）
+    pub index: usize,
+    pub location: Option<String>,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub duration: Option<u32>,
+}
+
+/// 已匹配的导入条目
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportMatchViewObject {
+    #[serde(flatten)]
+    pub entry: ImportEntryViewObject,
+    pub song_id: Uuid,
+    /// 匹配方式：path（文件路径）或metadata（标题/歌手/时长）
+    pub matched_by: String,
+}
+
+/// 导入匹配的候选歌曲
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCandidateViewObject {
+    pub song_id: Uuid,
+    pub title: String,
+    pub artist_id: Uuid,
+    pub album_id: Uuid,
+    pub duration: u32,
+    /// 相对于媒体根目录的文件路径，与导出的条目位置一致
+    pub location: String,
+}
+
+/// 存在多个候选、无法确定的导入条目
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportAmbiguousViewObject {
+    #[serde(flatten)]
+    pub entry: ImportEntryViewObject,
+    pub candidates: Vec<ImportCandidateViewObject>,
+}
+
+/// 歌单导入结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistImportViewObject {
+    /// 新建的歌单，只包含已匹配的条目
+    pub playlist: PlaylistViewObject,
+    pub matched: Vec<ImportMatchViewObject>,
+    pub ambiguous: Vec<ImportAmbiguousViewObject>,
+    pub unresolved: Vec<ImportEntryViewObject>,
+}
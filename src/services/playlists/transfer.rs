@@ -0,0 +1,324 @@
+use super::formats::{self, ExportTrack, ImportedTrack, PlaylistFormat};
+use super::model::*;
+use super::{PlaylistServiceError, find_visible_playlist, to_view_object};
+use crate::config::AppConfig;
+use crate::models::artist::ArtistRepository;
+use crate::models::smart_rule::SmartRules;
+use crate::models::song::SongRepository;
+use crate::models::{CreatePlaylistData, Song};
+use crate::services::library::normalize_name;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// 单次导入的最大条目数
+const MAX_IMPORT_ENTRIES: usize = 5000;
+// 按标题搜索候选歌曲的数量上限
+const METADATA_SEARCH_LIMIT: u64 = 200;
+// 时长匹配允许的误差(秒)
+const DURATION_TOLERANCE: u32 = 3;
+
+/// 导出的歌单文件
+pub struct PlaylistExport {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+// 导入条目的匹配结果
+enum Resolution {
+    Matched(Box<Song>, &'static str),
+    Ambiguous(Vec<Song>),
+    Unresolved,
+}
+
+/// 导出歌单服务，文件路径相对于媒体库根目录
+pub async fn export_playlist_service(
+    playlist_id: Uuid,
+    query: PlaylistExportQueryViewObject,
+    user_id: Uuid,
+    config: &AppConfig,
+) -> Result<PlaylistExport, PlaylistServiceError> {
+    let (playlist_repo, song_repo) = (&config.playlist_repo, &config.song_repo);
+    let format_name = query.format.unwrap_or_else(|| "m3u8".to_string());
+    let format = PlaylistFormat::parse(&format_name)
+        .ok_or(PlaylistServiceError::UnsupportedFormat(format_name))?;
+    let playlist = find_visible_playlist(playlist_id, user_id, playlist_repo).await?;
+
+    // 智能歌单导出当前求值结果
+    let songs: Vec<Song> = match &playlist.rules {
+        Some(rules) => {
+            let rules: SmartRules = serde_json::from_value(rules.clone())
+                .map_err(|e| PlaylistServiceError::InvalidRules(e.to_string()))?;
+            song_repo.find_by_rules(&rules).await?
+        }
+        None => playlist_repo.find_entries(playlist.id)
+            .await?
+            .into_iter()
+            .map(|(_, song)| song)
+            .collect(),
+    };
+
+    let artist_ids: Vec<Uuid> = songs.iter().map(|song| song.artist_id).collect();
+    let album_ids: Vec<Uuid> = songs.iter().map(|song| song.album_id).collect();
+    let artists: HashMap<Uuid, String> = config.artist_repo.find_by_ids(&artist_ids)
+        .await?
+        .into_iter()
+        .map(|artist| (artist.id, artist.name))
+        .collect();
+    let albums: HashMap<Uuid, String> = config.album_repo.find_by_ids(&album_ids)
+        .await?
+        .into_iter()
+        .map(|album| (album.id, album.name))
+        .collect();
+
+    let tracks: Vec<ExportTrack> = songs.into_iter().map(|song| ExportTrack {
+        location: relative_location(&song.file_path, &config.media_root),
+        artist: artists.get(&song.artist_id).cloned(),
+        album: albums.get(&song.album_id).cloned(),
+        title: song.title,
+        duration: song.duration,
+        track_number: song.track_number,
+    }).collect();
+
+    Ok(PlaylistExport {
+        file_name: format!("{}.{}", playlist.name, format.extension()),
+        content_type: format.content_type(),
+        body: formats::export(format, &playlist.name, &tracks),
+    })
+}
+
+/// 导入歌单服务：先按文件路径匹配，再按标题/歌手/时长模糊匹配，只添加能唯一确定的歌曲
+pub async fn import_playlist_service(
+    query: PlaylistImportQueryViewObject,
+    content: &str,
+    user_id: Uuid,
+    config: &AppConfig,
+) -> Result<PlaylistImportViewObject, PlaylistServiceError> {
+    let playlist_repo = &config.playlist_repo;
+    let format = match &query.format {
+        Some(format) => PlaylistFormat::parse(format)
+            .ok_or_else(|| PlaylistServiceError::UnsupportedFormat(format.clone()))?,
+        None => PlaylistFormat::detect(content)
+            .ok_or_else(|| PlaylistServiceError::InvalidImport("empty file".to_string()))?,
+    };
+    let parsed = formats::parse(format, content).map_err(PlaylistServiceError::InvalidImport)?;
+    if parsed.tracks.is_empty() {
+        return Err(PlaylistServiceError::InvalidImport("no entries found".to_string()));
+    }
+    if parsed.tracks.len() > MAX_IMPORT_ENTRIES {
+        return Err(PlaylistServiceError::InvalidImport(format!("at most {} entries can be imported at once", MAX_IMPORT_ENTRIES)));
+    }
+
+    let name = query.name
+        .or(parsed.name)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Imported playlist".to_string());
+
+    let mut artist_names: HashMap<Uuid, String> = HashMap::new();
+    let mut matched = Vec::new();
+    let mut ambiguous = Vec::new();
+    let mut unresolved = Vec::new();
+    for (index, track) in parsed.tracks.into_iter().enumerate() {
+        let resolution = resolve_track(&track, &config.media_root, &config.song_repo, &config.artist_repo, &mut artist_names).await?;
+        let entry = ImportEntryViewObject {
+            index,
+            location: track.location,
+            title: track.title,
+            artist: track.artist,
+            duration: track.duration,
+        };
+        match resolution {
+            Resolution::Matched(song, matched_by) => matched.push(ImportMatchViewObject {
+                entry,
+                song_id: song.id,
+                matched_by: matched_by.to_string(),
+            }),
+            Resolution::Ambiguous(candidates) => ambiguous.push(ImportAmbiguousViewObject {
+                entry,
+                candidates: candidates.into_iter().map(|song| ImportCandidateViewObject {
+                    song_id: song.id,
+                    title: song.title,
+                    artist_id: song.artist_id,
+                    album_id: song.album_id,
+                    duration: song.duration,
+                    location: relative_location(&song.file_path, &config.media_root),
+                }).collect(),
+            }),
+            Resolution::Unresolved => unresolved.push(entry),
+        }
+    }
+
+    let song_ids: Vec<Uuid> = matched.iter().map(|entry| entry.song_id).collect();
+    let playlist = playlist_repo.create_with_entries(&CreatePlaylistData {
+        owner_id: user_id,
+        name,
+        description: None,
+        public: query.public.unwrap_or(false),
+        rules: None,
+        created_by: user_id.to_string(),
+    }, &song_ids).await?;
+    let duration = playlist_repo.summaries(&[playlist.id])
+        .await?
+        .first()
+        .map(|(_, _, duration)| *duration)
+        .unwrap_or_default();
+
+    Ok(PlaylistImportViewObject {
+        playlist: to_view_object(playlist, Some((song_ids.len() as i64, duration))),
+        matched,
+        ambiguous,
+        unresolved,
+    })
+}
+
+async fn resolve_track(
+    track: &ImportedTrack,
+    media_root: &str,
+    song_repo: &Arc<dyn SongRepository + Send + Sync>,
+    artist_repo: &Arc<dyn ArtistRepository + Send + Sync>,
+    artist_names: &mut HashMap<Uuid, String>,
+) -> Result<Resolution, PlaylistServiceError> {
+    let location = track.location.as_deref()
+        .map(formats::normalize_location)
+        .filter(|location| !location.contains("://"));
+
+    // 1. 按文件路径匹配
+    let mut path_candidates = Vec::new();
+    if let Some(location) = &location {
+        match resolve_by_path(location, media_root, song_repo).await? {
+            Ok(song) => return Ok(Resolution::Matched(Box::new(song), "path")),
+            Err(candidates) => path_candidates = candidates,
+        }
+    }
+
+    // 2. 按标题/歌手/时长匹配，没有标题时使用文件名
+    let title = track.title.clone().or_else(|| {
+        location.as_deref()
+            .and_then(|location| Path::new(location).file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+    });
+    let Some(title) = title.map(|title| normalize_name(&title)).filter(|title| !title.is_empty()) else {
+        return Ok(unresolved_or_ambiguous(path_candidates));
+    };
+
+    let candidates = if path_candidates.is_empty() {
+        let keyword = title.split(' ').max_by_key(|word| word.chars().count()).unwrap_or(&title);
+        song_repo.search(keyword, 0, METADATA_SEARCH_LIMIT).await?
+    } else {
+        path_candidates.clone()
+    };
+    let candidates: Vec<Song> = candidates.into_iter()
+        .filter(|song| normalize_name(&song.title) == title)
+        .collect();
+    if candidates.is_empty() {
+        return Ok(unresolved_or_ambiguous(path_candidates));
+    }
+
+    // 逐步用歌手和时长缩小范围，过滤后为空时保留上一步的候选
+    let mut candidates = candidates;
+    if let Some(artist) = track.artist.as_deref().map(normalize_name).filter(|artist| !artist.is_empty()) {
+        load_artist_names(&candidates, artist_repo, artist_names).await?;
+        let by_artist: Vec<Song> = candidates.iter()
+            .filter(|song| artist_names.get(&song.artist_id).is_some_and(|name| *name == artist))
+            .cloned()
+            .collect();
+        if by_artist.is_empty() {
+            return Ok(Resolution::Ambiguous(candidates));
+        }
+        candidates = by_artist;
+    }
+    if let Some(duration) = track.duration {
+        let by_duration: Vec<Song> = candidates.iter()
+            .filter(|song| song.duration.abs_diff(duration) <= DURATION_TOLERANCE)
+            .cloned()
+            .collect();
+        if by_duration.is_empty() {
+            return Ok(Resolution::Ambiguous(candidates));
+        }
+        candidates = by_duration;
+    }
+
+    if candidates.len() == 1 {
+        Ok(Resolution::Matched(Box::new(candidates.remove(0)), "metadata"))
+    } else {
+        Ok(Resolution::Ambiguous(candidates))
+    }
+}
+
+// 按路径匹配：先精确匹配，再按文件名匹配并比较末尾路径片段；返回Err时带上无法区分的候选
+async fn resolve_by_path(
+    location: &str,
+    media_root: &str,
+    song_repo: &Arc<dyn SongRepository + Send + Sync>,
+) -> Result<Result<Song, Vec<Song>>, PlaylistServiceError> {
+    let absolute = Path::new(media_root).join(location).to_string_lossy().into_owned();
+    for path in [location, absolute.as_str()] {
+        if let Some(song) = song_repo.find_by_file_path(path).await?.filter(|song| !song.delete_flag) {
+            return Ok(Ok(song));
+        }
+    }
+
+    let location_parts: Vec<&str> = location.split('/').filter(|part| !part.is_empty() && *part != ".").collect();
+    let Some(file_name) = location_parts.last() else {
+        return Ok(Err(Vec::new()));
+    };
+
+    let mut scored: Vec<(usize, Song)> = song_repo.find_by_file_name(file_name)
+        .await?
+        .into_iter()
+        .filter_map(|song| {
+            let path = song.file_path.replace('\\', "/");
+            let score = path.split('/')
+                .rev()
+                .zip(location_parts.iter().rev())
+                .take_while(|(a, b)| a == *b)
+                .count();
+            (score > 0).then_some((score, song))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    match scored.as_slice() {
+        [] => Ok(Err(Vec::new())),
+        [(_, song)] => Ok(Ok(song.clone())),
+        [(best, song), (second, _), ..] if best > second => Ok(Ok(song.clone())),
+        _ => Ok(Err(scored.into_iter().map(|(_, song)| song).collect())),
+    }
+}
+
+async fn load_artist_names(
+    songs: &[Song],
+    artist_repo: &Arc<dyn ArtistRepository + Send + Sync>,
+    artist_names: &mut HashMap<Uuid, String>,
+) -> Result<(), PlaylistServiceError> {
+    let missing: Vec<Uuid> = songs.iter()
+        .map(|song| song.artist_id)
+        .filter(|id| !artist_names.contains_key(id))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    for artist in artist_repo.find_by_ids(&missing).await? {
+        artist_names.insert(artist.id, normalize_name(&artist.name));
+    }
+    Ok(())
+}
+
+fn unresolved_or_ambiguous(candidates: Vec<Song>) -> Resolution {
+    if candidates.is_empty() {
+        Resolution::Unresolved
+    } else {
+        Resolution::Ambiguous(candidates)
+    }
+}
+
+fn relative_location(file_path: &str, media_root: &str) -> String {
+    Path::new(file_path)
+        .strip_prefix(media_root)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| file_path.to_string())
+        .replace('\\', "/")
+}
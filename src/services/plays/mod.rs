@@ -0,0 +1,129 @@
+pub mod model;
+use self::model::*;
+use chrono::{Duration, Local};
+use crate::config::AppConfig;
+use crate::models::play_event::{CreatePlayEventData, PlayEventRepository, PlayEventType};
+use crate::services::stats::user_timezone;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// 允许客户端时钟超前的时间
+const MAX_CLOCK_SKEW_MINUTES: i64 = 5;
+
+#[derive(Debug)]
+pub enum PlayServiceError {
+    DatabaseError(sea_orm::DbErr),
+    SongNotFound,
+    InvalidPlayedAt,
+}
+
+impl fmt::Display for PlayServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            PlayServiceError::SongNotFound => write!(f, "Song not found"),
+            PlayServiceError::InvalidPlayedAt => write!(f, "playedAt must not be in the future"),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for PlayServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        PlayServiceError::DatabaseError(e)
+    }
+}
+
+/// 播放上报服务，开始播放和播放完成时调用
+/// 播放完成的事件计入播放次数，同一事件重复上报只记录一次
+pub async fn scrobble_service(
+    user_id: Uuid,
+    song_id: Uuid,
+    data: ScrobbleViewObject,
+    config: &AppConfig,
+) -> Result<ScrobbleResultViewObject, PlayServiceError> {
+    let now = Local::now();
+    let played_at = data.played_at.unwrap_or(now);
+    if played_at > now + Duration::minutes(MAX_CLOCK_SKEW_MINUTES) {
+        return Err(PlayServiceError::InvalidPlayedAt);
+    }
+
+    let song = config.song_repo.find_by_id(song_id)
+        .await?
+        .filter(|song| !song.delete_flag)
+        .ok_or(PlayServiceError::SongNotFound)?;
+
+    // 进度不超过歌曲时长，播放完成时没有进度则按整首计算收听时长
+    let position_seconds = data.position.map(|position| position.min(song.duration) as i32);
+    let event = CreatePlayEventData {
+        user_id,
+        song_id: song.id,
+        event_type: data.event,
+        position_seconds,
+        played_at,
+        timezone: user_timezone(user_id, config).await?,
+        listened_seconds: position_seconds.filter(|position| *position > 0).unwrap_or(song.duration as i32) as i64,
+    };
+    let recorded = config.play_event_repo.record(&event).await?;
+
+    let stats = play_stats(&config.play_event_repo, &[song.id])
+        .await?
+        .remove(&song.id)
+        .unwrap_or_default();
+    Ok(ScrobbleResultViewObject { recorded, stats })
+}
+
+/// 记录一次音频流开始，供没有上报播放的客户端计入排行榜
+/// 流事件不计入播放次数和个人听歌统计
+pub async fn record_stream_service(user_id: Uuid, song_id: Uuid, config: &AppConfig) -> Result<(), PlayServiceError> {
+    let event = CreatePlayEventData {
+        user_id,
+        song_id,
+        event_type: PlayEventType::Stream,
+        position_seconds: None,
+        played_at: Local::now(),
+        timezone: config.default_timezone.clone(),
+        listened_seconds: 0,
+    };
+    config.play_event_repo.record(&event).await?;
+    Ok(())
+}
+
+/// 获取当前用户的播放历史服务，按播放时间倒序
+pub async fn get_play_history_service(
+    user_id: Uuid,
+    query: PlayHistoryQueryViewObject,
+    config: &AppConfig,
+) -> Result<Vec<PlayHistoryItemViewObject>, PlayServiceError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 500);
+    let offset = (page - 1) * page_size;
+
+    let history = config.play_event_repo.find_history(user_id, offset, page_size)
+        .await?
+        .into_iter()
+        .map(|(event, song)| PlayHistoryItemViewObject {
+            song,
+            played_at: event.played_at,
+            position: event.position_seconds,
+        })
+        .collect();
+    Ok(history)
+}
+
+/// 批量查询一组歌曲的播放统计，供列表和详情接口使用
+pub async fn play_stats(
+    play_event_repo: &Arc<dyn PlayEventRepository + Send + Sync>,
+    song_ids: &[Uuid],
+) -> Result<HashMap<Uuid, PlayStatsViewObject>, sea_orm::DbErr> {
+    let mut stats: HashMap<Uuid, _> = play_event_repo.find_song_stats(song_ids)
+        .await?
+        .into_iter()
+        .map(|stat| (stat.song_id, stat))
+        .collect();
+
+    Ok(song_ids.iter()
+        .map(|id| (*id, stats.remove(id).into()))
+        .collect())
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local};
+use crate::models::Song;
+use crate::models::play_event::PlayEventType;
+use crate::models::song_play_stat::SongPlayStat;
+
+/// 播放上报视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrobbleViewObject {
+    /// 事件类型：nowPlaying（开始播放）或completed（播放完成）
+    pub event: PlayEventType,
+    /// 播放进度（秒）
+    pub position: Option<u32>,
+    /// 播放时间，离线客户端补报时传入，默认为当前时间
+    pub played_at: Option<DateTime<Local>>,
+}
+
+/// 歌曲的播放统计
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayStatsViewObject {
+    /// 播放次数
+    pub play_count: i64,
+    /// 最近播放时间
+    #[serde(serialize_with = "crate::utils::date_time::format_optional_datetime")]
+    pub last_played_at: Option<DateTime<Local>>,
+}
+
+impl From<Option<SongPlayStat>> for PlayStatsViewObject {
+    fn from(stat: Option<SongPlayStat>) -> Self {
+        PlayStatsViewObject {
+            play_count: stat.as_ref().map(|stat| stat.play_count).unwrap_or(0),
+            last_played_at: stat.map(|stat| stat.last_played_at),
+        }
+    }
+}
+
+/// 播放上报结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrobbleResultViewObject {
+    /// 是否新记录了事件，重复上报时为false
+    pub recorded: bool,
+    /// 上报后歌曲的播放统计
+    #[serde(flatten)]
+    pub stats: PlayStatsViewObject,
+}
+
+/// 播放历史查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayHistoryQueryViewObject {
+    /// 页码
+    pub page: Option<u64>,
+    /// 每页数量
+    pub page_size: Option<u64>,
+}
+
+/// 播放历史中的一条记录
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayHistoryItemViewObject {
+    #[serde(flatten)]
+    pub song: Song,
+    /// 播放时间
+    #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
+    pub played_at: DateTime<Local>,
+    /// 上报时的播放进度（秒）
+    pub position: Option<i32>,
+}
@@ -0,0 +1,242 @@
+pub mod model;
+use self::model::*;
+use chrono::{Duration, Local};
+use crate::config::AppConfig;
+use crate::models::similarity::{SeedType, SongFeatures};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+// 默认及最大每页数量
+const DEFAULT_RADIO_LIMIT: u64 = 20;
+const MAX_RADIO_LIMIT: u64 = 100;
+// 候选歌曲和一起收听歌曲的数量上限
+const MAX_CANDIDATES: u64 = 2000;
+const MAX_CO_LISTENED: u64 = 500;
+// 相关歌手数量上限
+const MAX_RELATED_ARTISTS: usize = 20;
+// 最近播放过的歌曲不进入队列
+const RECENT_HOURS: i64 = 24;
+// 发行年份相差超过该年数不加分
+const ERA_SPAN_YEARS: f64 = 10.0;
+
+// 各项相似度的权重
+const GENRE_WEIGHT: f64 = 3.0;
+const SAME_ARTIST_WEIGHT: f64 = 3.0;
+const RELATED_ARTIST_WEIGHT: f64 = 2.0;
+const ERA_WEIGHT: f64 = 1.5;
+const CO_LISTENED_WEIGHT: f64 = 4.0;
+// 由种子决定的随机扰动，使不同种子得到不同的队列
+const JITTER_WEIGHT: f64 = 1.5;
+
+#[derive(Debug)]
+pub enum RadioServiceError {
+    DatabaseError(sea_orm::DbErr),
+    InvalidItemType(String),
+    ItemNotFound(SeedType),
+}
+
+impl fmt::Display for RadioServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RadioServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            RadioServiceError::InvalidItemType(kind) => write!(f, "Invalid item type: {}", kind),
+            RadioServiceError::ItemNotFound(seed_type) => match seed_type {
+                SeedType::Song => write!(f, "Song not found"),
+                SeedType::Album => write!(f, "Album not found"),
+                SeedType::Artist => write!(f, "Artist not found"),
+            },
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for RadioServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        RadioServiceError::DatabaseError(e)
+    }
+}
+
+// 种子歌曲的特征汇总
+struct SeedProfile {
+    // 流派及其在种子歌曲中的占比（相对最多的流派）
+    genres: HashMap<String, f64>,
+    artists: HashSet<Uuid>,
+    // 相关歌手及其关联强度（0-1）
+    related_artists: HashMap<Uuid, f64>,
+    // 种子歌曲发行年份的中位数
+    era: Option<i32>,
+    // 一起收听的会话数
+    co_listened: HashMap<Uuid, i64>,
+}
+
+/// 电台服务：以歌曲、专辑或歌手为种子生成相似歌曲队列
+///
+/// 按流派、歌手、发行年代和听歌会话中的共同出现计算相似度，
+/// 排除种子歌曲本身、已删除的歌曲和当前用户最近播放过的歌曲。
+/// 相同种子参数得到相同的排序，offset指向排序中的位置，可连续翻页
+pub async fn get_radio_service(
+    user_id: Uuid,
+    kind: &str,
+    seed_id: Uuid,
+    query: RadioQueryViewObject,
+    config: &AppConfig,
+) -> Result<RadioViewObject, RadioServiceError> {
+    let seed_type = SeedType::parse(kind).ok_or_else(|| RadioServiceError::InvalidItemType(kind.to_string()))?;
+    if !seed_exists(seed_type, seed_id, config).await? {
+        return Err(RadioServiceError::ItemNotFound(seed_type));
+    }
+    let seed = query.seed.unwrap_or_else(|| rand::random::<u32>() as u64);
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(DEFAULT_RADIO_LIMIT).clamp(1, MAX_RADIO_LIMIT);
+
+    let repo = &config.similarity_repo;
+    let seeds = repo.find_seed_features(seed_type, seed_id).await?;
+    let profile = seed_profile(&seeds, config).await?;
+
+    let genres: Vec<String> = profile.genres.keys().cloned().collect();
+    let artists: Vec<Uuid> = profile.artists.iter().chain(profile.related_artists.keys()).copied().collect();
+    let co_listened: Vec<Uuid> = profile.co_listened.keys().copied().collect();
+    let candidates = repo.find_candidates(&genres, &artists, &co_listened, MAX_CANDIDATES).await?;
+
+    let mut ranking: Vec<(SongFeatures, f64, Vec<SimilarityReason>)> = candidates.into_iter()
+        .filter(|candidate| seed_type != SeedType::Song || candidate.id != seed_id)
+        .map(|candidate| {
+            let (score, reasons) = score(&candidate, &profile, seed);
+            (candidate, score, reasons)
+        })
+        .collect();
+    ranking.sort_by(|(a, a_score, _), (b, b_score, _)| {
+        b_score.partial_cmp(a_score).unwrap_or(Ordering::Equal).then(a.id.cmp(&b.id))
+    });
+
+    // 跳过最近播放的歌曲，直到凑满一页
+    let recent: HashSet<Uuid> = repo.find_recently_played(user_id, Local::now() - Duration::hours(RECENT_HOURS))
+        .await?
+        .into_iter()
+        .collect();
+    let mut position = offset as usize;
+    let mut page = Vec::new();
+    while position < ranking.len() && page.len() < limit as usize {
+        let (candidate, score, reasons) = &ranking[position];
+        if !recent.contains(&candidate.id) {
+            page.push((candidate.id, *score, reasons.clone()));
+        }
+        position += 1;
+    }
+    let next_offset = (position < ranking.len()).then_some(position as u64);
+
+    let ids: Vec<Uuid> = page.iter().map(|(id, _, _)| *id).collect();
+    let mut songs: HashMap<Uuid, _> = config.song_repo.find_by_ids(&ids)
+        .await?
+        .into_iter()
+        .map(|song| (song.id, song))
+        .collect();
+    let songs = page.into_iter()
+        .filter_map(|(id, score, reasons)| songs.remove(&id).map(|song| RadioSongViewObject { song, score, reasons }))
+        .collect();
+
+    Ok(RadioViewObject {
+        seed,
+        offset,
+        next_offset,
+        songs,
+    })
+}
+
+async fn seed_exists(seed_type: SeedType, seed_id: Uuid, config: &AppConfig) -> Result<bool, sea_orm::DbErr> {
+    Ok(match seed_type {
+        SeedType::Song => config.song_repo.find_by_id(seed_id).await?.is_some_and(|song| !song.delete_flag),
+        SeedType::Album => config.album_repo.find_by_id(seed_id).await?.is_some_and(|album| !album.delete_flag),
+        SeedType::Artist => config.artist_repo.find_by_id(seed_id).await?.is_some_and(|artist| !artist.delete_flag),
+    })
+}
+
+async fn seed_profile(seeds: &[SongFeatures], config: &AppConfig) -> Result<SeedProfile, sea_orm::DbErr> {
+    let mut genre_counts: HashMap<String, usize> = HashMap::new();
+    for genre in seeds.iter().filter_map(|seed| seed.genre.clone()) {
+        *genre_counts.entry(genre).or_default() += 1;
+    }
+    let max_genre_count = genre_counts.values().copied().max().unwrap_or(1) as f64;
+    let genres = genre_counts.into_iter()
+        .map(|(genre, count)| (genre, count as f64 / max_genre_count))
+        .collect();
+    let artists: HashSet<Uuid> = seeds.iter().map(|seed| seed.artist_id).collect();
+
+    let mut years: Vec<i32> = seeds.iter().map(|seed| seed.year).collect();
+    years.sort_unstable();
+    let era = years.get(years.len() / 2).copied();
+
+    let seed_ids: Vec<Uuid> = seeds.iter().map(|seed| seed.id).collect();
+    let co_listened: HashMap<Uuid, i64> = config.similarity_repo
+        .find_co_listened(&seed_ids, MAX_CO_LISTENED)
+        .await?
+        .into_iter()
+        .collect();
+
+    // 常与种子一起收听的其他歌手视为相关歌手
+    let co_ids: Vec<Uuid> = co_listened.keys().copied().collect();
+    let mut artist_sessions: HashMap<Uuid, i64> = HashMap::new();
+    for song in config.song_repo.find_by_ids(&co_ids).await? {
+        if !artists.contains(&song.artist_id) {
+            *artist_sessions.entry(song.artist_id).or_default() += co_listened.get(&song.id).copied().unwrap_or_default();
+        }
+    }
+    let mut related: Vec<(Uuid, i64)> = artist_sessions.into_iter().collect();
+    related.sort_by(|(a, a_sessions), (b, b_sessions)| b_sessions.cmp(a_sessions).then(a.cmp(b)));
+    related.truncate(MAX_RELATED_ARTISTS);
+    let max_sessions = related.first().map(|(_, sessions)| *sessions).unwrap_or(1).max(1) as f64;
+    let related_artists = related.into_iter()
+        .map(|(artist_id, sessions)| (artist_id, sessions as f64 / max_sessions))
+        .collect();
+
+    Ok(SeedProfile {
+        genres,
+        artists,
+        related_artists,
+        era,
+        co_listened,
+    })
+}
+
+fn score(candidate: &SongFeatures, profile: &SeedProfile, seed: u64) -> (f64, Vec<SimilarityReason>) {
+    let mut score = 0.0;
+    let mut reasons = Vec::new();
+
+    if let Some(share) = candidate.genre.as_ref().and_then(|genre| profile.genres.get(genre)) {
+        score += GENRE_WEIGHT * share;
+        reasons.push(SimilarityReason::Genre);
+    }
+
+    if profile.artists.contains(&candidate.artist_id) {
+        score += SAME_ARTIST_WEIGHT;
+        reasons.push(SimilarityReason::SameArtist);
+    } else if let Some(strength) = profile.related_artists.get(&candidate.artist_id) {
+        score += RELATED_ARTIST_WEIGHT * strength;
+        reasons.push(SimilarityReason::RelatedArtist);
+    }
+
+    if let Some(era) = profile.era {
+        let closeness = 1.0 - (candidate.year - era).abs() as f64 / ERA_SPAN_YEARS;
+        if closeness > 0.0 {
+            score += ERA_WEIGHT * closeness;
+        }
+        if closeness >= 0.5 {
+            reasons.push(SimilarityReason::Era);
+        }
+    }
+
+    let max_sessions = profile.co_listened.values().copied().max().unwrap_or(1).max(1) as f64;
+    if let Some(sessions) = profile.co_listened.get(&candidate.id) {
+        score += CO_LISTENED_WEIGHT * (*sessions as f64 / max_sessions);
+        reasons.push(SimilarityReason::ListenedTogether);
+    }
+
+    // 每首歌的扰动只取决于种子和歌曲ID，与候选顺序无关
+    let id = candidate.id.as_u128();
+    let mut rng = StdRng::seed_from_u64(seed ^ (id as u64) ^ ((id >> 64) as u64));
+    score += JITTER_WEIGHT * rng.r#gen::<f64>();
+
+    (score, reasons)
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use crate::models::Song;
+
+/// 电台查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RadioQueryViewObject {
+    /// 随机种子，相同种子得到相同的队列；不传时随机生成并在结果中返回
+    pub seed: Option<u64>,
+    /// 从队列的第几首开始，使用上一页返回的nextOffset
+    pub offset: Option<u64>,
+    /// 每页数量
+    pub limit: Option<u64>,
+}
+
+/// 相似原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimilarityReason {
+    /// 流派相同
+    Genre,
+    /// 同一歌手
+    SameArtist,
+    /// 相关歌手（常在同一听歌会话中出现）
+    RelatedArtist,
+    /// 发行年代相近
+    Era,
+    /// 常与种子歌曲一起收听
+    ListenedTogether,
+}
+
+/// 电台歌曲视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RadioSongViewObject {
+    #[serde(flatten)]
+    pub song: Song,
+    /// 相似度分数
+    pub score: f64,
+    pub reasons: Vec<SimilarityReason>,
+}
+
+/// 电台队列视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RadioViewObject {
+    pub seed: u64,
+    pub offset: u64,
+    /// 下一页的offset，队列结束时为空
+    pub next_offset: Option<u64>,
+    pub songs: Vec<RadioSongViewObject>,
+}
@@ -0,0 +1,110 @@
+pub mod model;
+use self::model::*;
+use crate::config::AppConfig;
+use crate::models::rating::{MAX_RATING, MIN_RATING, RatingItemType, RatingRepository};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub enum RatingServiceError {
+    DatabaseError(sea_orm::DbErr),
+    InvalidItemType(String),
+    InvalidRating(i16),
+    ItemNotFound(RatingItemType),
+}
+
+impl fmt::Display for RatingServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RatingServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            RatingServiceError::InvalidItemType(kind) => write!(f, "Invalid item type: {}", kind),
+            RatingServiceError::InvalidRating(rating) => write!(f, "Rating must be between {} and {}, got {}", MIN_RATING, MAX_RATING, rating),
+            RatingServiceError::ItemNotFound(item_type) => match item_type {
+                RatingItemType::Song => write!(f, "Song not found"),
+                RatingItemType::Album => write!(f, "Album not found"),
+            },
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for RatingServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        RatingServiceError::DatabaseError(e)
+    }
+}
+
+/// 评分服务，重复评分覆盖之前的评分，已删除的对象不能评分
+pub async fn rate_item_service(
+    user_id: Uuid,
+    kind: &str,
+    item_id: Uuid,
+    rating: i16,
+    config: &AppConfig,
+) -> Result<RatingViewObject, RatingServiceError> {
+    let item_type = parse_item_type(kind)?;
+    if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+        return Err(RatingServiceError::InvalidRating(rating));
+    }
+    if !item_exists(item_type, item_id, config).await? {
+        return Err(RatingServiceError::ItemNotFound(item_type));
+    }
+
+    let summary = config.rating_repo.rate(user_id, item_type, item_id, Some(rating)).await?;
+    Ok(RatingViewObject {
+        user_rating: Some(rating),
+        ..RatingViewObject::new(None, Some(&summary))
+    })
+}
+
+/// 清除评分服务，未评分时也视为成功
+pub async fn clear_rating_service(
+    user_id: Uuid,
+    kind: &str,
+    item_id: Uuid,
+    config: &AppConfig,
+) -> Result<RatingViewObject, RatingServiceError> {
+    let item_type = parse_item_type(kind)?;
+    if !item_exists(item_type, item_id, config).await? {
+        return Err(RatingServiceError::ItemNotFound(item_type));
+    }
+
+    let summary = config.rating_repo.rate(user_id, item_type, item_id, None).await?;
+    Ok(RatingViewObject::new(None, Some(&summary)))
+}
+
+/// 批量查询一组对象的评分信息，供列表和详情接口使用
+pub async fn rating_views(
+    rating_repo: &Arc<dyn RatingRepository + Send + Sync>,
+    user_id: Uuid,
+    item_type: RatingItemType,
+    item_ids: &[Uuid],
+) -> Result<HashMap<Uuid, RatingViewObject>, sea_orm::DbErr> {
+    let summaries: HashMap<Uuid, _> = rating_repo.find_summaries(item_type, item_ids)
+        .await?
+        .into_iter()
+        .map(|summary| (summary.item_id, summary))
+        .collect();
+    let ratings: HashMap<Uuid, _> = rating_repo.find_by_items(user_id, item_type, item_ids)
+        .await?
+        .into_iter()
+        .map(|rating| (rating.item_id, rating))
+        .collect();
+
+    Ok(item_ids.iter()
+        .map(|id| (*id, RatingViewObject::new(ratings.get(id), summaries.get(id))))
+        .collect())
+}
+
+fn parse_item_type(kind: &str) -> Result<RatingItemType, RatingServiceError> {
+    RatingItemType::parse(kind).ok_or_else(|| RatingServiceError::InvalidItemType(kind.to_string()))
+}
+
+async fn item_exists(item_type: RatingItemType, item_id: Uuid, config: &AppConfig) -> Result<bool, RatingServiceError> {
+    let exists = match item_type {
+        RatingItemType::Song => config.song_repo.find_by_id(item_id).await?.is_some_and(|song| !song.delete_flag),
+        RatingItemType::Album => config.album_repo.find_by_id(item_id).await?.is_some_and(|album| !album.delete_flag),
+    };
+    Ok(exists)
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use crate::models::rating::Rating;
+use crate::models::rating_summary::RatingSummary;
+
+/// 评分请求视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateItemViewObject {
+    /// 1-5星
+    pub rating: i16,
+}
+
+/// 对象的评分信息：当前用户的评分及所有用户的平均分
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingViewObject {
+    /// 当前用户的评分，未评分时为空
+    pub user_rating: Option<i16>,
+    /// 平均评分，没有评分时为空
+    pub average_rating: Option<f64>,
+    /// 评分人数
+    pub rating_count: i64,
+}
+
+impl RatingViewObject {
+    pub fn new(rating: Option<&Rating>, summary: Option<&RatingSummary>) -> Self {
+        RatingViewObject {
+            user_rating: rating.map(|rating| rating.rating),
+            average_rating: summary.and_then(|summary| summary.average_rating),
+            rating_count: summary.map(|summary| summary.rating_count).unwrap_or(0),
+        }
+    }
+}
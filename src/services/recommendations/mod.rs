@@ -0,0 +1,192 @@
+pub mod model;
+use self::model::*;
+use chrono::Local;
+use crate::config::AppConfig;
+use crate::models::chart_entry::{CHART_SIZE, ChartItemType, ChartOrder, ChartPeriod};
+use crate::models::item_neighbor::RecommendationItemType;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::Duration;
+use uuid::Uuid;
+
+// 默认及最大返回数量
+const DEFAULT_RECOMMENDATION_LIMIT: u64 = 20;
+const MAX_RECOMMENDATION_LIMIT: u64 = 100;
+// 读取的用户偏好数量上限（已收听的对象不再推荐）
+const MAX_AFFINITIES: u64 = 1000;
+// 用于查找相似对象的偏好最高的对象数量
+const MAX_SOURCES: usize = 50;
+
+#[derive(Debug)]
+pub enum RecommendationServiceError {
+    DatabaseError(sea_orm::DbErr),
+    InvalidItemType(String),
+}
+
+impl fmt::Display for RecommendationServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecommendationServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            RecommendationServiceError::InvalidItemType(kind) => write!(f, "Invalid item type: {}", kind),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for RecommendationServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        RecommendationServiceError::DatabaseError(e)
+    }
+}
+
+// 个人推荐候选：分数及贡献最大的来源对象
+struct Candidate {
+    id: Uuid,
+    score: f64,
+    source_id: Option<Uuid>,
+    source_contribution: f64,
+}
+
+/// 个人推荐服务
+///
+/// 从用户偏好最高的专辑或歌手出发，按相似对象模型累加分数，排除已收听、收藏或评分过的对象；
+/// 没有收听记录或推荐数量不足时用本周排行榜补充
+pub async fn get_recommendations_service(
+    user_id: Uuid,
+    kind: &str,
+    query: RecommendationQueryViewObject,
+    config: &AppConfig,
+) -> Result<Vec<RecommendationViewObject>, RecommendationServiceError> {
+    let item_type = RecommendationItemType::parse(kind)
+        .ok_or_else(|| RecommendationServiceError::InvalidItemType(kind.to_string()))?;
+    let limit = query.limit.unwrap_or(DEFAULT_RECOMMENDATION_LIMIT).clamp(1, MAX_RECOMMENDATION_LIMIT) as usize;
+
+    let affinities = config.recommendation_repo.find_affinities(user_id, item_type, MAX_AFFINITIES).await?;
+    let known: HashSet<Uuid> = affinities.iter().map(|(id, _)| *id).collect();
+    let top: Vec<Uuid> = affinities.iter().take(MAX_SOURCES).map(|(id, _)| *id).collect();
+    // 已删除的来源对象不参与打分，避免推荐理由指向不存在的对象
+    let source_items = load_items(item_type, &top, config).await?;
+    let sources: HashMap<Uuid, f64> = affinities.into_iter()
+        .take(MAX_SOURCES)
+        .filter(|(id, _)| source_items.contains_key(id))
+        .collect();
+    let source_ids: Vec<Uuid> = sources.keys().copied().collect();
+
+    let mut candidates: HashMap<Uuid, Candidate> = HashMap::new();
+    for neighbor in config.recommendation_repo.find_neighbors(item_type, &source_ids).await? {
+        if known.contains(&neighbor.neighbor_id) {
+            continue;
+        }
+        let contribution = sources.get(&neighbor.item_id).copied().unwrap_or_default() * neighbor.score;
+        let candidate = candidates.entry(neighbor.neighbor_id).or_insert(Candidate {
+            id: neighbor.neighbor_id,
+            score: 0.0,
+            source_id: None,
+            source_contribution: 0.0,
+        });
+        candidate.score += contribution;
+        if contribution > candidate.source_contribution {
+            candidate.source_id = Some(neighbor.item_id);
+            candidate.source_contribution = contribution;
+        }
+    }
+    let mut candidates: Vec<Candidate> = candidates.into_values().collect();
+    candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal).then(a.id.cmp(&b.id)));
+
+    // 先去掉已删除的候选再截取，保证返回数量
+    let candidate_ids: Vec<Uuid> = candidates.iter().map(|candidate| candidate.id).collect();
+    let mut items = load_items(item_type, &candidate_ids, config).await?;
+    candidates.retain(|candidate| items.contains_key(&candidate.id));
+    candidates.truncate(limit);
+
+    // 冷启动或推荐不足时使用本周排行榜补充
+    if candidates.len() < limit {
+        let chart_type = match item_type {
+            RecommendationItemType::Album => ChartItemType::Album,
+            RecommendationItemType::Artist => ChartItemType::Artist,
+        };
+        let included: HashSet<Uuid> = candidates.iter().map(|candidate| candidate.id).collect();
+        let entries = config.chart_repo
+            .find_entries(chart_type, ChartPeriod::Week, "", ChartOrder::Top, CHART_SIZE as u64)
+            .await?;
+        let chart_ids: Vec<Uuid> = entries.into_iter()
+            .map(|entry| entry.item_id)
+            .filter(|id| !known.contains(id) && !included.contains(id))
+            .collect();
+        let chart_items = load_items(item_type, &chart_ids, config).await?;
+        let fallback: Vec<Candidate> = chart_ids.into_iter()
+            .filter(|id| chart_items.contains_key(id))
+            .take(limit - candidates.len())
+            .map(|id| Candidate { id, score: 0.0, source_id: None, source_contribution: 0.0 })
+            .collect();
+        items.extend(chart_items);
+        candidates.extend(fallback);
+    }
+
+    Ok(candidates.into_iter()
+        .filter_map(|candidate| {
+            let because = candidate.source_id
+                .and_then(|source_id| source_items.get(&source_id).map(|(name, _)| BecauseViewObject { id: source_id, name: name.clone() }));
+            let (_, item) = items.remove(&candidate.id)?;
+            Some(match because {
+                Some(because) => RecommendationViewObject {
+                    item,
+                    source: RecommendationSource::Personal,
+                    score: Some(candidate.score),
+                    reason: format!("Because you listened to {}", because.name),
+                    because: Some(because),
+                },
+                None => RecommendationViewObject {
+                    item,
+                    source: RecommendationSource::Charts,
+                    score: None,
+                    because: None,
+                    reason: "Popular this week".to_string(),
+                },
+            })
+        })
+        .collect())
+}
+
+/// 重建推荐使用的相似对象模型
+pub async fn rebuild_recommendations_service(config: &AppConfig) -> Result<(), RecommendationServiceError> {
+    config.recommendation_repo.rebuild(Local::now()).await?;
+    Ok(())
+}
+
+/// 启动推荐模型定时重建后台任务，启动时立即重建一次
+pub fn start_recommendation_scheduler(config: &AppConfig) {
+    let config = config.clone();
+    let period = Duration::from_secs(config.recommendation_refresh_minutes.max(1) * 60);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match rebuild_recommendations_service(&config).await {
+                Ok(()) => log::info!("Recommendation model rebuilt"),
+                Err(e) => log::error!("Failed to rebuild recommendation model: {}", e),
+            }
+        }
+    });
+}
+
+// 加载未删除对象的名称和视图对象，已删除的对象不在结果中
+async fn load_items(
+    item_type: RecommendationItemType,
+    ids: &[Uuid],
+    config: &AppConfig,
+) -> Result<HashMap<Uuid, (String, RecommendedItemViewObject)>, sea_orm::DbErr> {
+    Ok(match item_type {
+        RecommendationItemType::Album => config.album_repo.find_by_ids(ids)
+            .await?
+            .into_iter()
+            .map(|album| (album.id, (album.name.clone(), RecommendedItemViewObject::Album(album.into()))))
+            .collect(),
+        RecommendationItemType::Artist => config.artist_repo.find_by_ids(ids)
+            .await?
+            .into_iter()
+            .map(|artist| (artist.id, (artist.name.clone(), RecommendedItemViewObject::Artist(artist.into()))))
+            .collect(),
+    })
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::services::albums::model::AlbumDetailViewObject;
+use crate::services::artists::model::ArtistDetailViewObject;
+
+/// 推荐查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationQueryViewObject {
+    /// 返回数量，默认20
+    pub limit: Option<u64>,
+}
+
+/// 推荐来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecommendationSource {
+    /// 根据个人收听记录
+    Personal,
+    /// 收听记录不足时使用排行榜补充
+    Charts,
+}
+
+/// 推荐对象
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum RecommendedItemViewObject {
+    Album(AlbumDetailViewObject),
+    Artist(ArtistDetailViewObject),
+}
+
+/// 推荐理由中引用的对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BecauseViewObject {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// 推荐视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationViewObject {
+    #[serde(flatten)]
+    pub item: RecommendedItemViewObject,
+    pub source: RecommendationSource,
+    /// 推荐分数，排行榜补充的条目为空
+    pub score: Option<f64>,
+    /// 贡献最大的已收听对象
+    pub because: Option<BecauseViewObject>,
+    /// 简短的推荐理由
+    pub reason: String,
+}
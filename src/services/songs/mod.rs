@@ -2,25 +2,109 @@ pub mod model;
 use crate::models;
 use sea_orm::DbErr; 
 use uuid::Uuid;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use self::model::{SongViewObject, UpdateSongViewObject, UploadSongViewObject};
+use crate::services::media::{self, MediaServiceError, MediaUpload};
+use crate::services::media::model::AudioTags;
 use crate::models::song::SongRepository;
+use crate::models::song_version::{SongFile, SongVersion};
 use crate::models::album::AlbumRepository;
 use crate::models::artist::ArtistRepository;
+use crate::config::AppConfig;
+use crate::models::star::StarItemType;
+use crate::models::rating::{RatingItemType, RatingRepository};
+use crate::services::stars::star_status;
+use crate::services::ratings::rating_views;
+use crate::services::plays::play_stats;
+use crate::services::credits::credit_views;
+use crate::models::credit::CreditItemType;
+use crate::models::play_event::PlayEventRepository;
 
-// 获取歌曲列表服务
+#[derive(Debug)]
+pub enum SongServiceError {
+    DatabaseError(DbErr),
+    SongNotFound,
+    AlbumNotFound,
+    ArtistNotFound,
+    MissingField(String),
+    MediaError(MediaServiceError),
+    InvalidField(String),
+    VersionNotFound,
+    // 新文件与当前文件内容相同
+    SameFile,
+}
+
+impl fmt::Display for SongServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SongServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            SongServiceError::SongNotFound => write!(f, "Song not found"),
+            SongServiceError::AlbumNotFound => write!(f, "Album not found"),
+            SongServiceError::ArtistNotFound => write!(f, "Artist not found"),
+            SongServiceError::MissingField(field) => write!(f, "Missing field: {}", field),
+            SongServiceError::MediaError(e) => write!(f, "{}", e),
+            SongServiceError::InvalidField(field) => write!(f, "Invalid field: {}", field),
+            SongServiceError::VersionNotFound => write!(f, "Song version not found"),
+            SongServiceError::SameFile => write!(f, "The new file is identical to the current file"),
+        }
+    }
+}
+
+impl From<DbErr> for SongServiceError {
+    fn from(e: DbErr) -> Self {
+        SongServiceError::DatabaseError(e)
+    }
+}
+
+// 获取歌曲列表服务（包含评分信息和播放统计）
 pub async fn get_songs_service(
     query: models::SongQueryParams,
-    song_repo: Arc<dyn SongRepository + Send + Sync>
-) -> Result<Vec<models::Song>, DbErr> {
-    song_repo.find_all(&query).await
+    user_id: Uuid,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+    rating_repo: Arc<dyn RatingRepository + Send + Sync>,
+    play_event_repo: Arc<dyn PlayEventRepository + Send + Sync>,
+) -> Result<Vec<SongViewObject>, DbErr> {
+    let songs = song_repo.find_all(&query).await?;
+    let song_ids: Vec<Uuid> = songs.iter().map(|song| song.id).collect();
+    let mut ratings = rating_views(&rating_repo, user_id, RatingItemType::Song, &song_ids).await?;
+    let mut plays = play_stats(&play_event_repo, &song_ids).await?;
+
+    Ok(songs.into_iter()
+        .map(|song| SongViewObject {
+            rating: ratings.remove(&song.id).unwrap_or_default(),
+            plays: plays.remove(&song.id).unwrap_or_default(),
+            song,
+            star: None,
+            credits: None,
+        })
+        .collect())
 }
 
-// 根据ID获取歌曲详情服务
+// 根据ID获取歌曲详情服务（包含评分信息、播放统计和当前用户的收藏状态）
 pub async fn get_song_by_id_service(
     song_id: Uuid,
-    song_repo: Arc<dyn SongRepository + Send + Sync>
-) -> Result<Option<models::Song>, DbErr> {
-    song_repo.find_by_id(song_id).await
+    user_id: Uuid,
+    config: &AppConfig,
+) -> Result<Option<SongViewObject>, DbErr> {
+    let song_repo = &config.song_repo;
+    let star_repo = &config.star_repo;
+    let rating_repo = &config.rating_repo;
+    let Some(song) = song_repo.find_by_id(song_id).await? else {
+        return Ok(None);
+    };
+    let star = star_status(star_repo, user_id, StarItemType::Song, song.id).await?;
+    let rating = rating_views(rating_repo, user_id, RatingItemType::Song, &[song.id])
+        .await?
+        .remove(&song.id)
+        .unwrap_or_default();
+    let plays = play_stats(&config.play_event_repo, &[song.id])
+        .await?
+        .remove(&song.id)
+        .unwrap_or_default();
+    let credits = credit_views(&config.credit_repo, &config.artist_repo, CreditItemType::Song, song.id, song.artist_id).await?;
+    Ok(Some(SongViewObject { song, rating, plays, star: Some(star), credits: Some(credits) }))
 }
 
 // 创建歌曲服务
@@ -43,4 +127,292 @@ pub async fn create_song_service(
     }
 
     song_repo.create(&data).await
-}
\ No newline at end of file
+}
+// 上传歌曲服务：保存音频文件并创建歌曲记录
+// 未由客户端提供的字段从音频标签中提取
+pub async fn upload_song_service(
+    data: UploadSongViewObject,
+    upload: MediaUpload,
+    created_by: String,
+    media_root: &str,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+    album_repo: Arc<dyn AlbumRepository + Send + Sync>,
+    artist_repo: Arc<dyn ArtistRepository + Send + Sync>
+) -> Result<models::Song, SongServiceError> {
+    // 入库前在临时文件上读取标签，失败时只需丢弃临时文件
+    let prepared = prepare_upload(data, &upload, &album_repo, &artist_repo).await;
+    let (request, album, tags) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            upload.discard().await;
+            return Err(e);
+        }
+    };
+
+    let stored = upload.persist().await.map_err(SongServiceError::MediaError)?;
+    let request = models::CreateSongRequest {
+        file_path: stored.path.to_string_lossy().to_string(),
+        file_hash: Some(stored.hash.clone()),
+        file_size: Some(stored.size as i64),
+        created_by,
+        ..request
+    };
+
+    // 写库失败时清理刚写入的文件
+    let song = match song_repo.create(&request).await {
+        Ok(song) => song,
+        Err(e) => {
+            media::remove_stored_media(&stored).await;
+            return Err(SongServiceError::DatabaseError(e));
+        }
+    };
+
+    // 专辑还没有封面时使用内嵌封面
+    if album.cover_image.as_deref().unwrap_or_default().is_empty()
+        && let (Some(data), Some(media_type)) = (&tags.cover_art, &tags.cover_art_type)
+    {
+        match media::store_cover_art(media_root, data, media_type).await {
+            Ok(path) => {
+                if let Err(e) = album_repo.update_cover_image(album.id, &path.to_string_lossy()).await {
+                    log::warn!("Failed to update album cover: {:?}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to store cover art: {:?}", e),
+        }
+    }
+
+    Ok(song)
+}
+
+// 合并客户端字段与标签信息，并解析歌手和专辑
+async fn prepare_upload(
+    data: UploadSongViewObject,
+    upload: &MediaUpload,
+    album_repo: &Arc<dyn AlbumRepository + Send + Sync>,
+    artist_repo: &Arc<dyn ArtistRepository + Send + Sync>
+) -> Result<(models::CreateSongRequest, models::album::Album, AudioTags), SongServiceError> {
+    let tags = media::read_tags(upload.temp_path().to_path_buf(), Some(upload.extension().to_string()))
+        .await
+        .map_err(SongServiceError::MediaError)?;
+
+    let artist = match data.artist_id {
+        Some(artist_id) => artist_repo.find_by_id(artist_id).await.map_err(SongServiceError::DatabaseError)?,
+        None => match tags.artist.as_deref().or(tags.album_artist.as_deref()) {
+            Some(name) => artist_repo.find_by_name(name).await.map_err(SongServiceError::DatabaseError)?,
+            None => None,
+        },
+    };
+    let artist = artist.ok_or(SongServiceError::ArtistNotFound)?;
+
+    let album = match data.album_id {
+        Some(album_id) => album_repo.find_by_id(album_id).await.map_err(SongServiceError::DatabaseError)?,
+        None => match tags.album.as_deref() {
+            Some(name) => album_repo.find_by_name(artist.id, name).await.map_err(SongServiceError::DatabaseError)?,
+            None => None,
+        },
+    };
+    let album = album.ok_or(SongServiceError::AlbumNotFound)?;
+
+    // 没有标题时使用文件名
+    let title = data.title
+        .or_else(|| tags.title.clone())
+        .or_else(|| {
+            Path::new(upload.file_name())
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(|stem| stem.to_string())
+        })
+        .ok_or(SongServiceError::MissingField("title".to_string()))?;
+
+    let request = models::CreateSongRequest {
+        album_id: album.id,
+        artist_id: artist.id,
+        title,
+        genre: data.genre.or_else(|| tags.genre.clone()),
+        duration: data.duration
+            .or(tags.duration)
+            .ok_or(SongServiceError::MissingField("duration".to_string()))?,
+        quality: data.quality
+            .or_else(|| tags.quality.clone())
+            .ok_or(SongServiceError::MissingField("quality".to_string()))?,
+        file_path: String::new(),
+        file_hash: None,
+        file_size: None,
+        file_modified_at: None,
+        track_number: data.track_number.or(tags.track_number),
+        disc_number: data.disc_number.or(tags.disc_number),
+        created_by: String::new(),
+    };
+
+    Ok((request, album, tags))
+}
+
+// 获取歌曲音频文件中的原始标签服务
+pub async fn get_song_tags_service(
+    song_id: Uuid,
+    song_repo: Arc<dyn SongRepository + Send + Sync>
+) -> Result<AudioTags, SongServiceError> {
+    let song = song_repo.find_by_id(song_id)
+        .await
+        .map_err(SongServiceError::DatabaseError)?
+        .filter(|song| !song.delete_flag)
+        .ok_or(SongServiceError::SongNotFound)?;
+
+    media::read_tags(PathBuf::from(song.file_path), None)
+        .await
+        .map_err(SongServiceError::MediaError)
+}
+
+// 更新歌曲信息服务，只更新传入的字段
+pub async fn update_song_service(
+    song_id: Uuid,
+    data: UpdateSongViewObject,
+    updated_by: String,
+    config: &AppConfig,
+) -> Result<models::Song, SongServiceError> {
+    find_song(song_id, config).await?;
+    if let Some(album_id) = data.album_id
+        && config.album_repo.find_by_id(album_id).await?.is_none()
+    {
+        return Err(SongServiceError::AlbumNotFound);
+    }
+    if let Some(artist_id) = data.artist_id
+        && config.artist_repo.find_by_id(artist_id).await?.is_none()
+    {
+        return Err(SongServiceError::ArtistNotFound);
+    }
+    let title = match data.title.as_deref().map(str::trim) {
+        Some("") => return Err(SongServiceError::InvalidField("title".to_string())),
+        title => title.map(|title| title.to_string()),
+    };
+    if data.track_number.is_some_and(|track| track < 1) {
+        return Err(SongServiceError::InvalidField("trackNumber".to_string()));
+    }
+    if data.disc_number.is_some_and(|disc| disc < 1) {
+        return Err(SongServiceError::InvalidField("discNumber".to_string()));
+    }
+
+    let update = models::UpdateSongData {
+        album_id: data.album_id,
+        artist_id: data.artist_id,
+        title,
+        genre: data.genre,
+        track_number: data.track_number,
+        disc_number: data.disc_number,
+        updated_by,
+        ..Default::default()
+    };
+    Ok(config.song_repo.update(song_id, &update).await?)
+}
+
+// 删除歌曲服务（软删除，音频文件保留）
+pub async fn delete_song_service(
+    song_id: Uuid,
+    deleted_by: String,
+    config: &AppConfig,
+) -> Result<(), SongServiceError> {
+    find_song(song_id, config).await?;
+    let update = models::UpdateSongData {
+        delete_flag: Some(true),
+        updated_by: deleted_by,
+        ..Default::default()
+    };
+    config.song_repo.update(song_id, &update).await?;
+    Ok(())
+}
+
+// 替换歌曲音频文件服务（如换成重制版）
+// 歌曲ID和元数据不变，时长和音质从新文件中重新读取，原文件保留为历史版本
+pub async fn replace_song_file_service(
+    song_id: Uuid,
+    upload: MediaUpload,
+    updated_by: String,
+    config: &AppConfig,
+) -> Result<models::Song, SongServiceError> {
+    let prepared = async {
+        let song = find_song(song_id, config).await?;
+        let tags = media::read_tags(upload.temp_path().to_path_buf(), Some(upload.extension().to_string()))
+            .await
+            .map_err(SongServiceError::MediaError)?;
+        let duration = tags.duration.ok_or(SongServiceError::MissingField("duration".to_string()))?;
+        let quality = tags.quality.ok_or(SongServiceError::MissingField("quality".to_string()))?;
+        Ok::<_, SongServiceError>((song, duration, quality))
+    }.await;
+    let (song, duration, quality) = match prepared {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            upload.discard().await;
+            return Err(e);
+        }
+    };
+
+    let stored = upload.persist().await.map_err(SongServiceError::MediaError)?;
+    if song.file_hash.as_deref() == Some(stored.hash.as_str()) {
+        media::remove_stored_media(&stored).await;
+        return Err(SongServiceError::SameFile);
+    }
+
+    let file = SongFile {
+        file_path: stored.path.to_string_lossy().to_string(),
+        file_hash: Some(stored.hash.clone()),
+        file_size: Some(stored.size as i64),
+        file_modified_at: None,
+        duration,
+        quality,
+    };
+    let song = match config.song_version_repo.replace_file(song_id, &file, &updated_by).await {
+        Ok(song) => song,
+        Err(e) => {
+            media::remove_stored_media(&stored).await;
+            return Err(SongServiceError::DatabaseError(e));
+        }
+    };
+
+    // 缓存键包含文件哈希，旧文件的转码结果不会再被使用
+    config.transcode_cache.purge(Some(song_id));
+    Ok(song)
+}
+
+// 获取歌曲历史版本服务
+pub async fn get_song_versions_service(
+    song_id: Uuid,
+    config: &AppConfig,
+) -> Result<Vec<SongVersion>, SongServiceError> {
+    find_song(song_id, config).await?;
+    Ok(config.song_version_repo.find_by_song(song_id).await?)
+}
+
+// 恢复歌曲历史版本服务，当前文件归档为新版本
+pub async fn restore_song_version_service(
+    song_id: Uuid,
+    version_id: Uuid,
+    updated_by: String,
+    config: &AppConfig,
+) -> Result<models::Song, SongServiceError> {
+    find_song(song_id, config).await?;
+    let version = config.song_version_repo.find_by_song(song_id)
+        .await?
+        .into_iter()
+        .find(|version| version.id == version_id)
+        .ok_or(SongServiceError::VersionNotFound)?;
+    // 历史版本的文件已被删除时无法恢复
+    if !tokio::fs::try_exists(&version.file_path).await.unwrap_or(false) {
+        return Err(SongServiceError::MediaError(MediaServiceError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Audio file not found: {}", version.file_path),
+        ))));
+    }
+
+    let song = config.song_version_repo.restore(song_id, version_id, &updated_by)
+        .await?
+        .ok_or(SongServiceError::VersionNotFound)?;
+    config.transcode_cache.purge(Some(song_id));
+    Ok(song)
+}
+
+async fn find_song(song_id: Uuid, config: &AppConfig) -> Result<models::Song, SongServiceError> {
+    config.song_repo.find_by_id(song_id)
+        .await?
+        .filter(|song| !song.delete_flag)
+        .ok_or(SongServiceError::SongNotFound)
+}
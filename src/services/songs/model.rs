@@ -7,9 +7,16 @@ use chrono::{DateTime, Local};
 #[serde(rename_all = "camelCase")]
 pub struct SongQueryViewObject {
     pub album_id: Option<Uuid>,
+    /// 歌手ID，匹配主歌手及任一署名歌手
     pub artist_id: Option<Uuid>,
+    /// 只匹配以该角色署名的歌手，如artistRole=featuring
+    pub artist_role: Option<crate::models::credit::CreditRole>,
     pub genre: Option<String>,
     pub quality: Option<String>,
+    /// 最低平均评分，如minRating=4
+    pub min_rating: Option<f64>,
+    /// 排序方式：createdAt（默认）、rating、playCount或lastPlayed
+    pub sort: Option<crate::models::song::SongSort>,
     pub page: Option<u32>,
     pub limit: Option<u32>,
 }
@@ -24,7 +31,8 @@ pub struct CreateSongViewObject {
     pub genre: Option<String>,
     pub duration: u32,
     pub quality: String,
-    pub file_path: String,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
     pub release_date: chrono::NaiveDate,
 }
 
@@ -45,4 +53,46 @@ pub struct SongDetailViewObject {
     pub created_at: DateTime<Local>,
     #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
     pub updated_at: DateTime<Local>,
-}
\ No newline at end of file
+}
+// 歌曲及评分、播放统计信息，详情接口还包含当前用户的收藏状态
+#[derive(Debug, Serialize)]
+pub struct SongViewObject {
+    #[serde(flatten)]
+    pub song: crate::models::Song,
+    #[serde(flatten)]
+    pub rating: crate::services::ratings::model::RatingViewObject,
+    #[serde(flatten)]
+    pub plays: crate::services::plays::model::PlayStatsViewObject,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub star: Option<crate::services::stars::model::StarStatusViewObject>,
+    /// 署名歌手，仅详情接口返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credits: Option<Vec<crate::services::credits::model::CreditViewObject>>,
+}
+
+// 上传歌曲视图对象（multipart表单中的文本字段）
+// 未提供的字段从音频文件标签中提取，客户端提供的字段优先
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSongViewObject {
+    pub title: Option<String>,
+    pub album_id: Option<Uuid>,
+    pub artist_id: Option<Uuid>,
+    pub genre: Option<String>,
+    pub duration: Option<u32>,
+    pub quality: Option<String>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+}
+
+// 更新歌曲信息视图对象，未传的字段保持不变
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSongViewObject {
+    pub title: Option<String>,
+    pub album_id: Option<Uuid>,
+    pub artist_id: Option<Uuid>,
+    pub genre: Option<String>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+}
@@ -0,0 +1,124 @@
+pub mod model;
+use self::model::*;
+use crate::config::AppConfig;
+use crate::models::star::{StarItemType, StarRepository};
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub enum StarServiceError {
+    DatabaseError(sea_orm::DbErr),
+    InvalidItemType(String),
+    ItemNotFound(StarItemType),
+}
+
+impl fmt::Display for StarServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StarServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            StarServiceError::InvalidItemType(kind) => write!(f, "Invalid item type: {}", kind),
+            StarServiceError::ItemNotFound(item_type) => match item_type {
+                StarItemType::Song => write!(f, "Song not found"),
+                StarItemType::Album => write!(f, "Album not found"),
+                StarItemType::Artist => write!(f, "Artist not found"),
+            },
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for StarServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        StarServiceError::DatabaseError(e)
+    }
+}
+
+/// 收藏服务，重复收藏保留最初的收藏时间，已删除的对象不能收藏
+pub async fn star_item_service(
+    user_id: Uuid,
+    kind: &str,
+    item_id: Uuid,
+    config: &AppConfig,
+) -> Result<StarStatusViewObject, StarServiceError> {
+    let item_type = parse_item_type(kind)?;
+    if !item_exists(item_type, item_id, config).await? {
+        return Err(StarServiceError::ItemNotFound(item_type));
+    }
+
+    let star = config.star_repo.star(user_id, item_type, item_id).await?;
+    Ok(Some(star).into())
+}
+
+/// 取消收藏服务，未收藏时也视为成功
+pub async fn unstar_item_service(
+    user_id: Uuid,
+    kind: &str,
+    item_id: Uuid,
+    config: &AppConfig,
+) -> Result<StarStatusViewObject, StarServiceError> {
+    let item_type = parse_item_type(kind)?;
+    config.star_repo.unstar(user_id, item_type, item_id).await?;
+    Ok(StarStatusViewObject::default())
+}
+
+/// 获取当前用户的收藏列表服务，不返回已删除的对象
+pub async fn get_starred_service(
+    user_id: Uuid,
+    kind: &str,
+    query: StarredQueryViewObject,
+    config: &AppConfig,
+) -> Result<StarredListViewObject, StarServiceError> {
+    let item_type = parse_item_type(kind)?;
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 500);
+    let offset = (page - 1) * page_size;
+
+    let list = match item_type {
+        StarItemType::Song => StarredListViewObject::Songs(
+            config.star_repo.find_starred_songs(user_id, offset, page_size)
+                .await?
+                .into_iter()
+                .map(|(star, song)| StarredItemViewObject { item: song, starred_at: star.created_at })
+                .collect()
+        ),
+        StarItemType::Album => StarredListViewObject::Albums(
+            config.star_repo.find_starred_albums(user_id, offset, page_size)
+                .await?
+                .into_iter()
+                .map(|(star, album)| StarredItemViewObject { item: album.into(), starred_at: star.created_at })
+                .collect()
+        ),
+        StarItemType::Artist => StarredListViewObject::Artists(
+            config.star_repo.find_starred_artists(user_id, offset, page_size)
+                .await?
+                .into_iter()
+                .map(|(star, artist)| StarredItemViewObject { item: artist.into(), starred_at: star.created_at })
+                .collect()
+        ),
+    };
+    Ok(list)
+}
+
+/// 查询当前用户对单个对象的收藏状态，供详情接口使用
+pub async fn star_status(
+    star_repo: &Arc<dyn StarRepository + Send + Sync>,
+    user_id: Uuid,
+    item_type: StarItemType,
+    item_id: Uuid,
+) -> Result<StarStatusViewObject, sea_orm::DbErr> {
+    let star = star_repo.find_by_items(user_id, item_type, &[item_id]).await?.pop();
+    Ok(star.into())
+}
+
+fn parse_item_type(kind: &str) -> Result<StarItemType, StarServiceError> {
+    StarItemType::parse(kind).ok_or_else(|| StarServiceError::InvalidItemType(kind.to_string()))
+}
+
+async fn item_exists(item_type: StarItemType, item_id: Uuid, config: &AppConfig) -> Result<bool, StarServiceError> {
+    let exists = match item_type {
+        StarItemType::Song => config.song_repo.find_by_id(item_id).await?.is_some_and(|song| !song.delete_flag),
+        StarItemType::Album => config.album_repo.find_by_id(item_id).await?.is_some_and(|album| !album.delete_flag),
+        StarItemType::Artist => config.artist_repo.find_by_id(item_id).await?.is_some_and(|artist| !artist.delete_flag),
+    };
+    Ok(exists)
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local};
+use crate::models::Song;
+use crate::models::star::Star;
+use crate::services::albums::model::AlbumDetailViewObject;
+use crate::services::artists::model::ArtistDetailViewObject;
+
+/// 收藏列表查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarredQueryViewObject {
+    /// 页码
+    pub page: Option<u64>,
+    /// 每页数量
+    pub page_size: Option<u64>,
+}
+
+/// 当前用户对某个对象的收藏状态
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarStatusViewObject {
+    /// 是否已收藏
+    pub starred: bool,
+    /// 收藏时间
+    #[serde(serialize_with = "crate::utils::date_time::format_optional_datetime")]
+    pub starred_at: Option<DateTime<Local>>,
+}
+
+impl From<Option<Star>> for StarStatusViewObject {
+    fn from(star: Option<Star>) -> Self {
+        StarStatusViewObject {
+            starred: star.is_some(),
+            starred_at: star.map(|star| star.created_at),
+        }
+    }
+}
+
+/// 收藏列表中的对象，附带收藏时间
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarredItemViewObject<T: Serialize> {
+    #[serde(flatten)]
+    pub item: T,
+    #[serde(serialize_with = "crate::utils::date_time::format_datetime")]
+    pub starred_at: DateTime<Local>,
+}
+
+/// 收藏列表，按收藏时间倒序
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum StarredListViewObject {
+    Songs(Vec<StarredItemViewObject<Song>>),
+    Albums(Vec<StarredItemViewObject<AlbumDetailViewObject>>),
+    Artists(Vec<StarredItemViewObject<ArtistDetailViewObject>>),
+}
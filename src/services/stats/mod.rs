@@ -0,0 +1,298 @@
+pub mod model;
+use self::model::*;
+use chrono::{Duration, NaiveDate};
+use crate::config::AppConfig;
+use crate::models::listening_stat::ListeningTotal;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+// 默认统计最近30天
+const DEFAULT_RANGE_DAYS: i64 = 29;
+// 排行榜默认及最大数量
+const DEFAULT_TOP_LIMIT: u64 = 10;
+const MAX_TOP_LIMIT: u64 = 100;
+
+#[derive(Debug)]
+pub enum StatsServiceError {
+    DatabaseError(sea_orm::DbErr),
+    UserNotFound,
+    InvalidRange,
+    InvalidYear(i32),
+    InvalidTimezone(String),
+}
+
+impl fmt::Display for StatsServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            StatsServiceError::UserNotFound => write!(f, "User not found"),
+            StatsServiceError::InvalidRange => write!(f, "from must not be later than to"),
+            StatsServiceError::InvalidYear(year) => write!(f, "Invalid year: {}", year),
+            StatsServiceError::InvalidTimezone(timezone) => write!(f, "Unknown timezone: {}", timezone),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for StatsServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        StatsServiceError::DatabaseError(e)
+    }
+}
+
+/// 获取当前用户的听歌统计服务，日期按用户时区划分
+pub async fn get_listening_stats_service(
+    user_id: Uuid,
+    query: ListeningStatsQueryViewObject,
+    config: &AppConfig,
+) -> Result<ListeningStatsViewObject, StatsServiceError> {
+    let timezone = user_timezone(user_id, config).await?;
+    let today = config.listening_stat_repo.local_today(&timezone).await?;
+    let to = query.to.unwrap_or(today);
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS));
+    if from > to {
+        return Err(StatsServiceError::InvalidRange);
+    }
+    listening_stats(user_id, from, to, today, timezone, query.limit, config).await
+}
+
+/// 年度报告服务，统计用户时区内指定年份全年的数据
+pub async fn get_year_in_review_service(
+    user_id: Uuid,
+    year: i32,
+    query: YearInReviewQueryViewObject,
+    config: &AppConfig,
+) -> Result<ListeningStatsViewObject, StatsServiceError> {
+    let (Some(from), Some(to)) = (NaiveDate::from_ymd_opt(year, 1, 1), NaiveDate::from_ymd_opt(year, 12, 31)) else {
+        return Err(StatsServiceError::InvalidYear(year));
+    };
+    let timezone = user_timezone(user_id, config).await?;
+    let today = config.listening_stat_repo.local_today(&timezone).await?;
+    listening_stats(user_id, from, to, today, timezone, query.limit, config).await
+}
+
+/// 设置时区服务，按新时区重新生成听歌汇总
+pub async fn set_timezone_service(
+    user_id: Uuid,
+    data: TimezoneViewObject,
+    config: &AppConfig,
+) -> Result<TimezoneViewObject, StatsServiceError> {
+    let timezone = data.timezone
+        .map(|timezone| timezone.trim().to_string())
+        .filter(|timezone| !timezone.is_empty());
+    if let Some(timezone) = &timezone
+        && !config.user_repo.is_known_timezone(timezone).await?
+    {
+        return Err(StatsServiceError::InvalidTimezone(timezone.clone()));
+    }
+    config.user_repo.find_by_id(user_id)
+        .await?
+        .ok_or(StatsServiceError::UserNotFound)?;
+
+    config.user_repo.update_timezone(user_id, timezone.clone()).await?;
+    let effective = timezone.unwrap_or_else(|| config.default_timezone.clone());
+    config.listening_stat_repo.rebuild(user_id, &effective).await?;
+    Ok(TimezoneViewObject { timezone: Some(effective) })
+}
+
+/// 用户的统计时区，未设置时使用默认时区
+pub async fn user_timezone(user_id: Uuid, config: &AppConfig) -> Result<String, sea_orm::DbErr> {
+    let timezone = config.user_repo.find_by_id(user_id)
+        .await?
+        .and_then(|user| user.timezone)
+        .filter(|timezone| !timezone.is_empty());
+    Ok(timezone.unwrap_or_else(|| config.default_timezone.clone()))
+}
+
+async fn listening_stats(
+    user_id: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+    today: NaiveDate,
+    timezone: String,
+    limit: Option<u64>,
+    config: &AppConfig,
+) -> Result<ListeningStatsViewObject, StatsServiceError> {
+    let limit = limit.unwrap_or(DEFAULT_TOP_LIMIT).clamp(1, MAX_TOP_LIMIT);
+    let repo = &config.listening_stat_repo;
+    let (total_plays, total_listening_seconds) = repo.totals(user_id, from, to).await?;
+
+    let top_songs = repo.top_songs(user_id, from, to, limit).await?;
+    let songs = config.song_repo.find_by_ids(&ids(&top_songs)).await?;
+    let top_songs = ranked(top_songs, songs.into_iter().map(|song| (song.id, song)).collect());
+
+    let top_albums = repo.top_albums(user_id, from, to, limit).await?;
+    let albums = config.album_repo.find_by_ids(&ids(&top_albums)).await?;
+    let top_albums = ranked(top_albums, albums.into_iter().map(|album| (album.id, album.into())).collect());
+
+    let top_artists = repo.top_artists(user_id, from, to, limit).await?;
+    let artists = config.artist_repo.find_by_ids(&ids(&top_artists)).await?;
+    let top_artists = ranked(top_artists, artists.into_iter().map(|artist| (artist.id, artist.into())).collect());
+
+    let top_genres = repo.top_genres(user_id, from, to, limit)
+        .await?
+        .into_iter()
+        .map(|(genre, play_count, listening_seconds)| RankedItemViewObject {
+            item: GenreViewObject { genre },
+            play_count,
+            listening_seconds,
+        })
+        .collect();
+
+    // 补齐没有收听记录的小时和星期
+    let hours: HashMap<i16, (i64, i64)> = repo.by_hour(user_id, from, to)
+        .await?
+        .into_iter()
+        .map(|(hour, plays, seconds)| (hour, (plays, seconds)))
+        .collect();
+    let by_hour = (0..24)
+        .map(|hour| {
+            let (play_count, listening_seconds) = hours.get(&hour).copied().unwrap_or_default();
+            HourlyListeningViewObject { hour, play_count, listening_seconds }
+        })
+        .collect();
+    let weekdays: HashMap<i32, (i64, i64)> = repo.by_weekday(user_id, from, to)
+        .await?
+        .into_iter()
+        .map(|(weekday, plays, seconds)| (weekday, (plays, seconds)))
+        .collect();
+    let by_weekday = (1..=7)
+        .map(|weekday| {
+            let (play_count, listening_seconds) = weekdays.get(&weekday).copied().unwrap_or_default();
+            WeekdayListeningViewObject { weekday, play_count, listening_seconds }
+        })
+        .collect();
+
+    let active_days = repo.active_days(user_id, from, to).await?;
+    let streaks = streaks(&active_days, from, to.min(today), today);
+
+    Ok(ListeningStatsViewObject {
+        from,
+        to,
+        timezone,
+        total_plays,
+        total_listening_seconds,
+        top_songs,
+        top_albums,
+        top_artists,
+        top_genres,
+        by_hour,
+        by_weekday,
+        streaks,
+    })
+}
+
+fn ids(totals: &[ListeningTotal<Uuid>]) -> Vec<Uuid> {
+    totals.iter().map(|(id, _, _)| *id).collect()
+}
+
+// 按排行顺序组装条目，已删除或不存在的对象被跳过
+fn ranked<T>(totals: Vec<ListeningTotal<Uuid>>, mut items: HashMap<Uuid, T>) -> Vec<RankedItemViewObject<T>> {
+    totals.into_iter()
+        .filter_map(|(id, play_count, listening_seconds)| {
+            items.remove(&id).map(|item| RankedItemViewObject { item, play_count, listening_seconds })
+        })
+        .collect()
+}
+
+// 计算连续收听天数，days为升序的有收听记录的日期
+// 当前连续天数从end往前计算，end为今天且今天还没有收听时从昨天开始
+fn streaks(days: &[NaiveDate], from: NaiveDate, end: NaiveDate, today: NaiveDate) -> StreakViewObject {
+    let mut streak = StreakViewObject {
+        active_days: days.len() as u32,
+        ..StreakViewObject::default()
+    };
+
+    let mut run: Option<(NaiveDate, NaiveDate, u32)> = None;
+    for &day in days {
+        run = match run {
+            Some((start, last, length)) if last.succ_opt() == Some(day) => Some((start, day, length + 1)),
+            _ => Some((day, day, 1)),
+        };
+        if let Some((start, last, length)) = run
+            && length > streak.longest_days
+        {
+            streak.longest_days = length;
+            streak.longest_start = Some(start);
+            streak.longest_end = Some(last);
+        }
+    }
+
+    let active: HashSet<NaiveDate> = days.iter().copied().collect();
+    let mut day = end;
+    if day == today && !active.contains(&day) {
+        day = day.pred_opt().unwrap_or(day);
+    }
+    while day >= from && active.contains(&day) {
+        streak.current_days += 1;
+        match day.pred_opt() {
+            Some(previous) => day = previous,
+            None => break,
+        }
+    }
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+    }
+
+    fn dates(days: &[&str]) -> Vec<NaiveDate> {
+        days.iter().map(|day| date(day)).collect()
+    }
+
+    #[test]
+    fn finds_longest_and_current_streak() {
+        let days = dates(&["2024-03-01", "2024-03-02", "2024-03-03", "2024-03-05", "2024-03-09", "2024-03-10"]);
+        let streak = streaks(&days, date("2024-03-01"), date("2024-03-10"), date("2024-03-20"));
+
+        assert_eq!(streak.active_days, 6);
+        assert_eq!(streak.longest_days, 3);
+        assert_eq!(streak.longest_start, Some(date("2024-03-01")));
+        assert_eq!(streak.longest_end, Some(date("2024-03-03")));
+        assert_eq!(streak.current_days, 2);
+    }
+
+    #[test]
+    fn keeps_the_first_of_equally_long_streaks() {
+        let days = dates(&["2024-03-01", "2024-03-02", "2024-03-05", "2024-03-06"]);
+        let streak = streaks(&days, date("2024-03-01"), date("2024-03-06"), date("2024-03-06"));
+
+        assert_eq!(streak.longest_days, 2);
+        assert_eq!(streak.longest_start, Some(date("2024-03-01")));
+        assert_eq!(streak.current_days, 2);
+    }
+
+    #[test]
+    fn current_streak_starts_yesterday_until_today_has_plays() {
+        let days = dates(&["2024-03-08", "2024-03-09"]);
+        let streak = streaks(&days, date("2024-03-01"), date("2024-03-10"), date("2024-03-10"));
+        assert_eq!(streak.current_days, 2);
+
+        // 结束日期不是今天时，当天没有收听即没有当前连续天数
+        let streak = streaks(&days, date("2024-03-01"), date("2024-03-10"), date("2024-03-20"));
+        assert_eq!(streak.current_days, 0);
+    }
+
+    #[test]
+    fn current_streak_stops_at_range_start() {
+        let days = dates(&["2024-02-28", "2024-02-29", "2024-03-01", "2024-03-02"]);
+        let streak = streaks(&days, date("2024-03-01"), date("2024-03-02"), date("2024-03-02"));
+
+        assert_eq!(streak.current_days, 2);
+    }
+
+    #[test]
+    fn empty_range_has_no_streaks() {
+        let streak = streaks(&[], date("2024-03-01"), date("2024-03-10"), date("2024-03-10"));
+
+        assert_eq!(streak.active_days, 0);
+        assert_eq!(streak.longest_days, 0);
+        assert_eq!(streak.longest_start, None);
+        assert_eq!(streak.current_days, 0);
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+use crate::models::Song;
+use crate::services::albums::model::AlbumDetailViewObject;
+use crate::services::artists::model::ArtistDetailViewObject;
+
+/// 听歌统计查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningStatsQueryViewObject {
+    /// 开始日期（含），默认为结束日期前29天
+    pub from: Option<NaiveDate>,
+    /// 结束日期（含），默认为用户时区的今天
+    pub to: Option<NaiveDate>,
+    /// 排行榜数量
+    pub limit: Option<u64>,
+}
+
+/// 年度报告查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearInReviewQueryViewObject {
+    /// 排行榜数量
+    pub limit: Option<u64>,
+}
+
+/// 设置时区视图对象
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimezoneViewObject {
+    /// IANA时区名称，如Europe/Berlin；为空时恢复默认时区
+    pub timezone: Option<String>,
+}
+
+/// 排行榜条目，附带播放次数和收听时长
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedItemViewObject<T> {
+    #[serde(flatten)]
+    pub item: T,
+    /// 播放次数
+    pub play_count: i64,
+    /// 收听时长（秒）
+    pub listening_seconds: i64,
+}
+
+/// 流派
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenreViewObject {
+    pub genre: String,
+}
+
+/// 按小时的收听分布
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HourlyListeningViewObject {
+    /// 0-23
+    pub hour: i16,
+    pub play_count: i64,
+    pub listening_seconds: i64,
+}
+
+/// 按星期的收听分布
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekdayListeningViewObject {
+    /// 1为周一，7为周日
+    pub weekday: i32,
+    pub play_count: i64,
+    pub listening_seconds: i64,
+}
+
+/// 连续收听天数
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreakViewObject {
+    /// 截至结束日期（或今天）的连续收听天数
+    pub current_days: u32,
+    /// 日期范围内最长的连续收听天数
+    pub longest_days: u32,
+    pub longest_start: Option<NaiveDate>,
+    pub longest_end: Option<NaiveDate>,
+    /// 日期范围内有收听记录的天数
+    pub active_days: u32,
+}
+
+/// 听歌统计视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningStatsViewObject {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// 划分日期使用的时区
+    pub timezone: String,
+    pub total_plays: i64,
+    /// 总收听时长（秒）
+    pub total_listening_seconds: i64,
+    pub top_songs: Vec<RankedItemViewObject<Song>>,
+    pub top_albums: Vec<RankedItemViewObject<AlbumDetailViewObject>>,
+    pub top_artists: Vec<RankedItemViewObject<ArtistDetailViewObject>>,
+    pub top_genres: Vec<RankedItemViewObject<GenreViewObject>>,
+    pub by_hour: Vec<HourlyListeningViewObject>,
+    pub by_weekday: Vec<WeekdayListeningViewObject>,
+    pub streaks: StreakViewObject,
+}
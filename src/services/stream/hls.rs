@@ -0,0 +1,144 @@
+use super::{find_playable_song, song_file_path, source_bitrate, StreamServiceError};
+use crate::models::song::SongRepository;
+use crate::services::transcode::cache::{CacheKey, TranscodeCache};
+use crate::services::transcode::{TranscodeError, Transcoder};
+use actix_files::NamedFile;
+use std::fmt::Write;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// HLS分片时长(秒)
+pub const SEGMENT_SECONDS: u32 = 10;
+/// 自适应码率的AAC码率档位(kbps)
+pub const HLS_BITRATES: [u32; 3] = [64, 128, 256];
+
+pub const PLAYLIST_CONTENT_TYPE: &str = "application/vnd.apple.mpegurl";
+pub const SEGMENT_CONTENT_TYPE: &str = "video/mp2t";
+
+/// 生成主播放列表，列出各码率档位
+///
+/// 高于源文件码率的档位没有意义，只保留最低一档以保证至少有一个变体
+pub async fn master_playlist_service(
+    song_id: Uuid,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<String, StreamServiceError> {
+    let song = find_playable_song(song_id, song_repo).await?;
+    let source = source_bitrate(&song);
+
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+    for (index, bitrate) in HLS_BITRATES.iter().enumerate() {
+        if index > 0 && source.is_some_and(|source| source < *bitrate) {
+            continue;
+        }
+        // 带宽按码率加上约10%的TS封装开销估算
+        let _ = writeln!(
+            playlist,
+            "#EXT-X-STREAM-INF:BANDWIDTH={},CODECS=\"mp4a.40.2\"\n{}/index.m3u8",
+            bitrate * 1100,
+            bitrate
+        );
+    }
+    Ok(playlist)
+}
+
+/// 生成指定码率的媒体播放列表
+pub async fn variant_playlist_service(
+    song_id: Uuid,
+    bitrate: u32,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<String, StreamServiceError> {
+    check_bitrate(bitrate)?;
+    let song = find_playable_song(song_id, song_repo).await?;
+    if song.duration == 0 {
+        return Err(StreamServiceError::DurationUnknown);
+    }
+
+    let mut playlist = format!(
+        "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:{}\n#EXT-X-MEDIA-SEQUENCE:0\n#EXT-X-PLAYLIST-TYPE:VOD\n",
+        SEGMENT_SECONDS
+    );
+    for index in 0..segment_count(song.duration) {
+        let _ = writeln!(playlist, "#EXTINF:{:.3},\n{}.ts", segment_length(song.duration, index) as f64, index);
+    }
+    playlist.push_str("#EXT-X-ENDLIST\n");
+    Ok(playlist)
+}
+
+/// 获取HLS分片，首次请求时转码生成并写入转码缓存
+pub async fn segment_service(
+    song_id: Uuid,
+    bitrate: u32,
+    index: u32,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+    media_root: &str,
+    transcoder: &Transcoder,
+    cache: &TranscodeCache,
+) -> Result<NamedFile, StreamServiceError> {
+    check_bitrate(bitrate)?;
+    let song = find_playable_song(song_id, song_repo).await?;
+    if song.duration == 0 {
+        return Err(StreamServiceError::DurationUnknown);
+    }
+    if index >= segment_count(song.duration) {
+        return Err(StreamServiceError::SegmentNotFound);
+    }
+
+    let source = song_file_path(&song, media_root).await?;
+
+    let start = index * SEGMENT_SECONDS;
+    let length = segment_length(song.duration, index);
+    let content_type = SEGMENT_CONTENT_TYPE.parse().unwrap_or(actix_web::mime::APPLICATION_OCTET_STREAM);
+
+    let key = match song.file_hash.as_deref() {
+        Some(hash) => CacheKey::segment(song.id, hash, bitrate, index),
+        None => {
+            // 没有内容哈希时不缓存：生成临时文件，打开后即删除
+            let output = std::env::temp_dir().join(format!("{}.ts", Uuid::new_v4()));
+            transcoder.transcode_segment(&source, bitrate, start, length, &output)
+                .await
+                .map_err(transcode_error)?;
+            let file = NamedFile::open_async(&output).await.map_err(StreamServiceError::IoError);
+            let _ = tokio::fs::remove_file(&output).await;
+            return Ok(file?.set_content_type(content_type));
+        }
+    };
+
+    let path = match cache.lookup(&key) {
+        Some(path) => path,
+        None => {
+            let output = cache.temp_path(&key);
+            transcoder.transcode_segment(&source, bitrate, start, length, &output)
+                .await
+                .map_err(transcode_error)?;
+            cache.insert(&key, &output).map_err(StreamServiceError::IoError)?
+        }
+    };
+
+    NamedFile::open_async(&path)
+        .await
+        .map(|file| file.set_content_type(content_type))
+        .map_err(StreamServiceError::IoError)
+}
+
+fn check_bitrate(bitrate: u32) -> Result<(), StreamServiceError> {
+    if HLS_BITRATES.contains(&bitrate) {
+        Ok(())
+    } else {
+        Err(StreamServiceError::UnsupportedFormat(format!("aac {}kbps", bitrate)))
+    }
+}
+
+fn segment_count(duration: u32) -> u32 {
+    duration.div_ceil(SEGMENT_SECONDS)
+}
+
+fn segment_length(duration: u32, index: u32) -> u32 {
+    (duration - index * SEGMENT_SECONDS).min(SEGMENT_SECONDS)
+}
+
+fn transcode_error(e: TranscodeError) -> StreamServiceError {
+    match e {
+        TranscodeError::Busy => StreamServiceError::TranscoderBusy,
+        e => StreamServiceError::TranscodeFailed(e.to_string()),
+    }
+}
@@ -0,0 +1,161 @@
+pub mod hls;
+pub mod model;
+use self::model::StreamQueryViewObject;
+use actix_files::NamedFile;
+use sea_orm::DbErr;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::models::song::{Song, SongRepository};
+use crate::services::transcode::{TranscodeError, TranscodeProfile, TranscodeStream, Transcoder};
+use crate::services::transcode::cache::{CacheKey, TranscodeCache};
+
+#[derive(Debug)]
+pub enum StreamServiceError {
+    DatabaseError(DbErr),
+    SongNotFound,
+    SongDeleted,
+    FileMissing(String),
+    IoError(io::Error),
+    UnsupportedFormat(String),
+    TranscoderBusy,
+    TranscodeFailed(String),
+    DurationUnknown,
+    SegmentNotFound,
+}
+
+impl fmt::Display for StreamServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            StreamServiceError::SongNotFound => write!(f, "Song not found"),
+            StreamServiceError::SongDeleted => write!(f, "Song has been deleted"),
+            StreamServiceError::FileMissing(path) => write!(f, "Audio file not found: {}", path),
+            StreamServiceError::IoError(e) => write!(f, "IO error: {:?}", e),
+            StreamServiceError::UnsupportedFormat(format) => write!(f, "Unsupported format: {}", format),
+            StreamServiceError::TranscoderBusy => write!(f, "Too many concurrent transcodes, please retry later"),
+            StreamServiceError::TranscodeFailed(message) => write!(f, "Transcoding failed: {}", message),
+            StreamServiceError::DurationUnknown => write!(f, "Song duration is unknown"),
+            StreamServiceError::SegmentNotFound => write!(f, "Segment not found"),
+        }
+    }
+}
+
+/// 歌曲音频流：磁盘文件（原始文件或转码缓存）或实时转码输出
+pub enum SongStream {
+    File(Box<NamedFile>),
+    Transcoded {
+        content_type: &'static str,
+        body: TranscodeStream,
+    },
+}
+
+/// 按请求的格式和码率播放歌曲
+///
+/// 优先使用转码缓存，未命中时边转码边写入缓存；
+/// 不需要转码、编码器不可用或转码失败时回退到原始文件
+pub async fn stream_song_service(
+    song_id: Uuid,
+    query: &StreamQueryViewObject,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+    media_root: &str,
+    transcoder: &Transcoder,
+    cache: &TranscodeCache,
+) -> Result<SongStream, StreamServiceError> {
+    let song = find_playable_song(song_id, song_repo).await?;
+    let path = song_file_path(&song, media_root).await?;
+    let extension = Path::new(&song.file_path).extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let profile = TranscodeProfile::resolve(query.format.as_deref(), query.max_bitrate, &extension, source_bitrate(&song))
+        .map_err(|e| match e {
+            TranscodeError::UnsupportedFormat(format) => StreamServiceError::UnsupportedFormat(format),
+            _ => StreamServiceError::IoError(io::Error::other(e.to_string())),
+        })?;
+    let profile = match profile {
+        Some(profile) => profile,
+        None => return open_song_file(&song, &path).await.map(|file| SongStream::File(Box::new(file))),
+    };
+
+    // 没有内容哈希的歌曲无法判断文件是否变化，不做缓存
+    let cache_key = song.file_hash.as_deref().map(|hash| CacheKey::new(song.id, hash, &profile));
+    if let Some(cached) = cache_key.as_ref().and_then(|key| cache.lookup(key)) {
+        let file = NamedFile::open_async(&cached)
+            .await
+            .map_err(StreamServiceError::IoError)?
+            .set_content_type(profile.format.content_type().parse().unwrap_or(actix_web::mime::APPLICATION_OCTET_STREAM));
+        return Ok(SongStream::File(Box::new(file)));
+    }
+
+    match transcoder.start(&path, &profile).await {
+        Ok(body) => Ok(SongStream::Transcoded {
+            content_type: profile.format.content_type(),
+            body: match cache_key {
+                Some(key) => cache.store_stream(key, body),
+                None => body,
+            },
+        }),
+        Err(TranscodeError::Busy) => Err(StreamServiceError::TranscoderBusy),
+        Err(e) => {
+            log::warn!("Transcoding song {} failed, falling back to original file: {}", song.id, e);
+            open_song_file(&song, &path).await.map(|file| SongStream::File(Box::new(file)))
+        }
+    }
+}
+
+async fn find_playable_song(
+    song_id: Uuid,
+    song_repo: Arc<dyn SongRepository + Send + Sync>,
+) -> Result<Song, StreamServiceError> {
+    let song = song_repo.find_by_id(song_id)
+        .await
+        .map_err(StreamServiceError::DatabaseError)?
+        .ok_or(StreamServiceError::SongNotFound)?;
+
+    if song.delete_flag {
+        return Err(StreamServiceError::SongDeleted);
+    }
+    Ok(song)
+}
+
+/// 根据文件大小和时长估算源文件码率(kbps)
+pub fn source_bitrate(song: &Song) -> Option<u32> {
+    let size = song.file_size.filter(|size| *size > 0)?;
+    if song.duration == 0 {
+        return None;
+    }
+    Some((size * 8 / 1000 / song.duration as i64) as u32)
+}
+
+/// 解析歌曲音频文件的规范路径，只允许媒体根目录内的文件
+async fn song_file_path(song: &Song, media_root: &str) -> Result<PathBuf, StreamServiceError> {
+    let canonicalize = |path| async move {
+        tokio::fs::canonicalize(path).await.map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => StreamServiceError::FileMissing(song.file_path.clone()),
+            _ => StreamServiceError::IoError(e),
+        })
+    };
+    let root = canonicalize(Path::new(media_root)).await?;
+    let path = canonicalize(Path::new(&song.file_path)).await?;
+    if !path.starts_with(&root) {
+        log::warn!("Song {} points outside the media root: {}", song.id, song.file_path);
+        return Err(StreamServiceError::FileMissing(song.file_path.clone()));
+    }
+    Ok(path)
+}
+
+/// 打开歌曲对应的音频文件
+///
+/// 返回的 NamedFile 负责 Range 解析、206/416 响应以及按扩展名推断 MIME 类型
+async fn open_song_file(song: &Song, path: &Path) -> Result<NamedFile, StreamServiceError> {
+    NamedFile::open_async(path)
+        .await
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => StreamServiceError::FileMissing(song.file_path.clone()),
+            _ => StreamServiceError::IoError(e),
+        })
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+// 播放参数视图对象
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamQueryViewObject {
+    /// 目标格式（mp3/opus/aac），raw表示原始文件
+    pub format: Option<String>,
+    /// 最大码率(kbps)，0表示不限制
+    pub max_bitrate: Option<u32>,
+}
@@ -0,0 +1,699 @@
+pub mod model;
+pub mod response;
+use self::model::{SubsonicAlbum, SubsonicArtist, SubsonicChild};
+use crate::config::AppConfig;
+use crate::models::album::{Album, AlbumListOrder, AlbumListParams};
+use crate::models::artist::Artist;
+use crate::models::song::RandomSongParams;
+use crate::models::star::StarItemType;
+use crate::services::stars::{StarServiceError, star_item_service, unstar_item_service};
+use crate::models::rating::RatingItemType;
+use crate::services::ratings::{RatingServiceError, clear_rating_service, rate_item_service, rating_views};
+use crate::models::play_event::PlayEventType;
+use crate::services::plays::{PlayServiceError, play_stats, scrobble_service, model::ScrobbleViewObject};
+use crate::services::radio::{RadioServiceError, get_radio_service, model::RadioQueryViewObject};
+use crate::services::play_queue::{PlayQueueServiceError, get_play_queue_service, save_play_queue_service, model::SavePlayQueueViewObject};
+use crate::models::{ArtistQueryData, Song, User};
+use crate::services::stream::source_bitrate;
+use crate::utils::crypto::decrypt_secret;
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use md5::{Digest, Md5};
+use sea_orm::DbErr;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+use uuid::Uuid;
+
+// 列表类接口单次返回的上限
+const MAX_PAGE_SIZE: u64 = 500;
+
+/// Subsonic错误，code为协议定义的错误码
+#[derive(Debug)]
+pub struct SubsonicError {
+    pub code: u32,
+    pub message: String,
+}
+
+impl SubsonicError {
+    pub fn generic(message: &str) -> Self {
+        Self { code: 0, message: message.to_string() }
+    }
+
+    pub fn missing_parameter(name: &str) -> Self {
+        Self { code: 10, message: format!("Required parameter is missing: {}", name) }
+    }
+
+    pub fn wrong_credentials() -> Self {
+        Self { code: 40, message: "Wrong username or password".to_string() }
+    }
+
+    pub fn token_not_supported() -> Self {
+        Self { code: 41, message: "Token authentication requires a subsonic password, set one via /api/users/me/subsonic-password".to_string() }
+    }
+
+    pub fn not_found(what: &str) -> Self {
+        Self { code: 70, message: format!("{} not found", what) }
+    }
+}
+
+impl fmt::Display for SubsonicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Subsonic error {}: {}", self.code, self.message)
+    }
+}
+
+impl From<DbErr> for SubsonicError {
+    fn from(e: DbErr) -> Self {
+        log::error!("Subsonic database error: {:?}", e);
+        SubsonicError::generic("Database error")
+    }
+}
+
+/// Subsonic请求参数（查询字符串）
+pub struct SubsonicParams(Vec<(String, String)>);
+
+impl SubsonicParams {
+    pub fn from_query(query: &str) -> Self {
+        let params = actix_web::web::Query::<Vec<(String, String)>>::from_query(query)
+            .map(|query| query.into_inner())
+            .unwrap_or_default();
+        Self(params)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn require(&self, name: &str) -> Result<&str, SubsonicError> {
+        self.get(name).ok_or_else(|| SubsonicError::missing_parameter(name))
+    }
+
+    pub fn parse<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.get(name).and_then(|value| value.parse().ok())
+    }
+
+    // 同名参数可以重复出现（如star的id）
+    pub fn get_ids(&self, name: &str) -> Result<Vec<Uuid>, SubsonicError> {
+        self.0.iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| Uuid::parse_str(value).map_err(|_| SubsonicError::not_found("Item")))
+            .collect()
+    }
+
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.0.iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    pub fn require_id(&self) -> Result<Uuid, SubsonicError> {
+        let id = self.require("id")?;
+        Uuid::parse_str(id).map_err(|_| SubsonicError::not_found("Item"))
+    }
+
+    fn page(&self, size_name: &str, offset_name: &str, default_size: u64) -> (u64, u64) {
+        let size = self.parse::<u64>(size_name).unwrap_or(default_size).min(MAX_PAGE_SIZE);
+        let offset = self.parse::<u64>(offset_name).unwrap_or(0);
+        (offset, size)
+    }
+}
+
+/// Subsonic认证服务
+///
+/// 支持令牌认证（t=md5(password+s)，使用单独设置的Subsonic密码）
+/// 和密码认证（p，可为enc:十六进制，匹配Subsonic密码或登录密码）
+pub async fn authenticate_service(params: &SubsonicParams, config: &AppConfig) -> Result<User, SubsonicError> {
+    let username = params.require("u")?;
+    let user = config.user_repo.find_by_username(username)
+        .await?
+        .ok_or_else(SubsonicError::wrong_credentials)?;
+    let subsonic_password = user.subsonic_password.as_deref()
+        .and_then(|encrypted| decrypt_secret(encrypted, &config.jwt_secret));
+
+    if let Some(token) = params.get("t") {
+        let salt = params.require("s")?;
+        let password = subsonic_password.ok_or_else(SubsonicError::token_not_supported)?;
+        let expected = hex::encode(Md5::digest(format!("{}{}", password, salt).as_bytes()));
+        return if expected.eq_ignore_ascii_case(token) {
+            Ok(user)
+        } else {
+            Err(SubsonicError::wrong_credentials())
+        };
+    }
+
+    let password = params.require("p")?;
+    let password = match password.strip_prefix("enc:") {
+        Some(encoded) => hex::decode(encoded)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(SubsonicError::wrong_credentials)?,
+        None => password.to_string(),
+    };
+    if subsonic_password.as_deref() == Some(password.as_str()) {
+        return Ok(user);
+    }
+
+    let password_hash = user.password_hash.clone();
+    let valid = tokio::task::spawn_blocking(move || bcrypt::verify(&password, &password_hash).unwrap_or(false))
+        .await
+        .unwrap_or(false);
+    if valid { Ok(user) } else { Err(SubsonicError::wrong_credentials()) }
+}
+
+/// 处理返回XML/JSON数据的Subsonic接口
+pub async fn handle_request_service(method: &str, params: &SubsonicParams, user: &User, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let user_id = user.id;
+    match method {
+        "ping" => Ok(json!({})),
+        "getLicense" => Ok(json!({ "license": { "valid": true } })),
+        "getMusicFolders" => Ok(json!({
+            "musicFolders": { "musicFolder": [{ "id": 1, "name": "Music" }] }
+        })),
+        "getIndexes" => get_indexes(config).await,
+        "getArtists" => get_artists(user_id, config).await,
+        "getArtist" => get_artist(params, user_id, config).await,
+        "getAlbum" => get_album(params, user_id, config).await,
+        "getSong" => get_song(params, user_id, config).await,
+        "search3" => search3(params, user_id, config).await,
+        "getAlbumList2" => get_album_list2(params, user_id, config).await,
+        "getRandomSongs" => get_random_songs(params, user_id, config).await,
+        "star" => star(params, user_id, config, true).await,
+        "unstar" => star(params, user_id, config, false).await,
+        "setRating" => set_rating(params, user_id, config).await,
+        "scrobble" => scrobble(params, user_id, config).await,
+        "getStarred" => get_starred(user_id, config, "starred").await,
+        "getStarred2" => get_starred(user_id, config, "starred2").await,
+        "getSimilarSongs" => get_similar_songs(params, user_id, config, "similarSongs").await,
+        "getSimilarSongs2" => get_similar_songs(params, user_id, config, "similarSongs2").await,
+        "getPlayQueue" => get_play_queue(user, config).await,
+        "savePlayQueue" => save_play_queue(params, user_id, config).await,
+        _ => Err(SubsonicError { code: 0, message: format!("Unsupported method: {}", method) }),
+    }
+}
+
+/// 查找封面图片，id可以是专辑、歌曲或歌手
+pub async fn find_cover_art_service(params: &SubsonicParams, config: &AppConfig) -> Result<String, SubsonicError> {
+    let id = params.require_id()?;
+
+    let album_id = match config.song_repo.find_by_id(id).await? {
+        Some(song) => song.album_id,
+        None => id,
+    };
+    let cover = match config.album_repo.find_by_id(album_id).await? {
+        Some(album) => album.cover_image,
+        None => config.artist_repo.find_by_id(id).await?.and_then(|artist| artist.avatar),
+    };
+    cover.filter(|cover| !cover.is_empty())
+        .ok_or_else(|| SubsonicError::not_found("Cover art"))
+}
+
+async fn get_indexes(config: &AppConfig) -> Result<Value, SubsonicError> {
+    let artists = load_artists(config).await?;
+    let last_modified = artists.iter()
+        .map(|artist| artist.updated_at.timestamp_millis())
+        .max()
+        .unwrap_or_default();
+    let index: Vec<Value> = group_by_letter(&artists)
+        .into_iter()
+        .map(|(letter, artists)| json!({
+            "name": letter,
+            "artist": artists.iter().map(|artist| json!({ "id": artist.id, "name": artist.name })).collect::<Vec<_>>(),
+        }))
+        .collect();
+
+    Ok(json!({
+        "indexes": { "lastModified": last_modified, "ignoredArticles": "", "index": index }
+    }))
+}
+
+async fn get_artists(user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let artists = load_artists(config).await?;
+    let album_counts: HashMap<Uuid, i64> = config.album_repo.count_by_artist().await?.into_iter().collect();
+    let starred = starred_map(user_id, StarItemType::Artist, &artists.iter().map(|artist| artist.id).collect::<Vec<_>>(), config).await?;
+    let index: Vec<Value> = group_by_letter(&artists)
+        .into_iter()
+        .map(|(letter, artists)| json!({
+            "name": letter,
+            "artist": artists.iter()
+                .map(|artist| to_subsonic_artist(artist, album_counts.get(&artist.id).copied().unwrap_or(0), starred.get(&artist.id).cloned()))
+                .collect::<Vec<_>>(),
+        }))
+        .collect();
+
+    Ok(json!({ "artists": { "ignoredArticles": "", "index": index } }))
+}
+
+async fn get_artist(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let artist = config.artist_repo.find_by_id(params.require_id()?)
+        .await?
+        .filter(|artist| !artist.delete_flag)
+        .ok_or_else(|| SubsonicError::not_found("Artist"))?;
+    let albums = config.album_repo.find_by_artist(artist.id).await?;
+
+    let starred = starred_map(user_id, StarItemType::Artist, &[artist.id], config).await?;
+
+    let mut value = json!(to_subsonic_artist(&artist, albums.len() as i64, starred.get(&artist.id).cloned()));
+    value["album"] = json!(build_albums(albums, user_id, config).await?);
+    Ok(json!({ "artist": value }))
+}
+
+async fn get_album(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let album = config.album_repo.find_by_id(params.require_id()?)
+        .await?
+        .filter(|album| !album.delete_flag)
+        .ok_or_else(|| SubsonicError::not_found("Album"))?;
+    let songs = config.song_repo.find_by_album(album.id).await?;
+
+    let mut value = json!(build_albums(vec![album], user_id, config).await?.pop());
+    value["song"] = json!(build_children(songs, user_id, config).await?);
+    Ok(json!({ "album": value }))
+}
+
+async fn get_song(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let song = config.song_repo.find_by_id(params.require_id()?)
+        .await?
+        .filter(|song| !song.delete_flag)
+        .ok_or_else(|| SubsonicError::not_found("Song"))?;
+    Ok(json!({ "song": build_children(vec![song], user_id, config).await?.pop() }))
+}
+
+async fn search3(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    // 部分客户端用空查询（""）获取全部内容
+    let keyword = params.get("query").unwrap_or_default().trim().trim_matches('"');
+    let (artist_offset, artist_count) = params.page("artistCount", "artistOffset", 20);
+    let (album_offset, album_count) = params.page("albumCount", "albumOffset", 20);
+    let (song_offset, song_count) = params.page("songCount", "songOffset", 20);
+
+    let artists = config.artist_repo.search(keyword, artist_offset, artist_count).await?;
+    let albums = config.album_repo.search(keyword, album_offset, album_count).await?;
+    let songs = config.song_repo.search(keyword, song_offset, song_count).await?;
+
+    let album_counts: HashMap<Uuid, i64> = config.album_repo.count_by_artist().await?.into_iter().collect();
+    let starred = starred_map(user_id, StarItemType::Artist, &artists.iter().map(|artist| artist.id).collect::<Vec<_>>(), config).await?;
+    Ok(json!({
+        "searchResult3": {
+            "artist": artists.iter()
+                .map(|artist| to_subsonic_artist(artist, album_counts.get(&artist.id).copied().unwrap_or(0), starred.get(&artist.id).cloned()))
+                .collect::<Vec<_>>(),
+            "album": build_albums(albums, user_id, config).await?,
+            "song": build_children(songs, user_id, config).await?,
+        }
+    }))
+}
+
+async fn get_album_list2(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let list_type = params.require("type")?;
+    let (offset, limit) = params.page("size", "offset", 10);
+    let mut list_params = AlbumListParams {
+        order: AlbumListOrder::Newest,
+        genre: None,
+        from_year: None,
+        to_year: None,
+        offset,
+        limit,
+    };
+
+    match list_type {
+        "random" => list_params.order = AlbumListOrder::Random,
+        "newest" => list_params.order = AlbumListOrder::Newest,
+        "alphabeticalByName" => list_params.order = AlbumListOrder::Name,
+        "alphabeticalByArtist" => list_params.order = AlbumListOrder::ArtistName,
+        "byYear" => {
+            let from_year = params.parse::<i32>("fromYear").ok_or_else(|| SubsonicError::missing_parameter("fromYear"))?;
+            let to_year = params.parse::<i32>("toYear").ok_or_else(|| SubsonicError::missing_parameter("toYear"))?;
+            // fromYear大于toYear时按倒序返回
+            list_params.order = if from_year <= to_year { AlbumListOrder::ReleaseDateAsc } else { AlbumListOrder::ReleaseDateDesc };
+            list_params.from_year = Some(from_year.min(to_year));
+            list_params.to_year = Some(from_year.max(to_year));
+        }
+        "byGenre" => {
+            list_params.order = AlbumListOrder::Name;
+            list_params.genre = Some(params.require("genre")?.to_string());
+        }
+        "starred" => {
+            let albums = config.star_repo.find_starred_albums(user_id, offset, limit)
+                .await?
+                .into_iter()
+                .map(|(_, album)| album)
+                .collect();
+            return Ok(json!({ "albumList2": { "album": build_albums(albums, user_id, config).await? } }));
+        }
+        "highest" => list_params.order = AlbumListOrder::Highest,
+        "frequent" => list_params.order = AlbumListOrder::Frequent,
+        "recent" => list_params.order = AlbumListOrder::Recent,
+        _ => return Err(SubsonicError::generic(&format!("Unsupported list type: {}", list_type))),
+    }
+
+    let albums = config.album_repo.find_list(&list_params).await?;
+    Ok(json!({ "albumList2": { "album": build_albums(albums, user_id, config).await? } }))
+}
+
+async fn get_random_songs(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let query = RandomSongParams {
+        genre: params.get("genre").map(|genre| genre.to_string()),
+        from_year: params.parse("fromYear"),
+        to_year: params.parse("toYear"),
+        limit: params.parse::<u64>("size").unwrap_or(10).min(MAX_PAGE_SIZE),
+    };
+    let songs = config.song_repo.find_random(&query).await?;
+    Ok(json!({ "randomSongs": { "song": build_children(songs, user_id, config).await? } }))
+}
+
+// 收藏或取消收藏：id可以是歌曲（旧版客户端也会传专辑或歌手ID），albumId和artistId分别对应专辑和歌手
+async fn star(params: &SubsonicParams, user_id: Uuid, config: &AppConfig, starred: bool) -> Result<Value, SubsonicError> {
+    let mut items: Vec<(StarItemType, Uuid)> = Vec::new();
+    for id in params.get_ids("id")? {
+        let item_type = if config.song_repo.find_by_id(id).await?.is_some() {
+            StarItemType::Song
+        } else if config.album_repo.find_by_id(id).await?.is_some() {
+            StarItemType::Album
+        } else {
+            StarItemType::Artist
+        };
+        items.push((item_type, id));
+    }
+    items.extend(params.get_ids("albumId")?.into_iter().map(|id| (StarItemType::Album, id)));
+    items.extend(params.get_ids("artistId")?.into_iter().map(|id| (StarItemType::Artist, id)));
+    if items.is_empty() {
+        return Err(SubsonicError::missing_parameter("id"));
+    }
+
+    for (item_type, id) in items {
+        let result = if starred {
+            star_item_service(user_id, item_type.as_str(), id, config).await
+        } else {
+            unstar_item_service(user_id, item_type.as_str(), id, config).await
+        };
+        result.map_err(|e| match e {
+            StarServiceError::ItemNotFound(_) => SubsonicError::not_found("Item"),
+            e => SubsonicError::generic(&e.to_string()),
+        })?;
+    }
+    Ok(json!({}))
+}
+
+// 评分：rating为1-5，0表示清除评分；id可以是歌曲或专辑
+async fn set_rating(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let id = params.require_id()?;
+    let rating = params.parse::<i16>("rating").ok_or_else(|| SubsonicError::missing_parameter("rating"))?;
+    let kind = if config.song_repo.find_by_id(id).await?.is_some() {
+        RatingItemType::Song
+    } else {
+        RatingItemType::Album
+    };
+
+    let result = if rating == 0 {
+        clear_rating_service(user_id, kind.as_str(), id, config).await
+    } else {
+        rate_item_service(user_id, kind.as_str(), id, rating, config).await
+    };
+    result.map_err(|e| match e {
+        RatingServiceError::ItemNotFound(_) => SubsonicError::not_found("Item"),
+        e => SubsonicError::generic(&e.to_string()),
+    })?;
+    Ok(json!({}))
+}
+
+// 播放上报：submission=false表示开始播放，否则为播放完成；time为播放时间（毫秒时间戳），与id一一对应
+async fn scrobble(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let ids = params.get_ids("id")?;
+    if ids.is_empty() {
+        return Err(SubsonicError::missing_parameter("id"));
+    }
+    let times = params.get_all("time");
+    let event = match params.get("submission") {
+        Some("false") => PlayEventType::NowPlaying,
+        _ => PlayEventType::Completed,
+    };
+
+    for (index, id) in ids.into_iter().enumerate() {
+        let played_at = times.get(index)
+            .and_then(|time| time.parse::<i64>().ok())
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|time| time.with_timezone(&Local));
+        let data = ScrobbleViewObject { event, position: None, played_at };
+        scrobble_service(user_id, id, data, config).await.map_err(|e| match e {
+            PlayServiceError::SongNotFound => SubsonicError::not_found("Song"),
+            e => SubsonicError::generic(&e.to_string()),
+        })?;
+    }
+    Ok(json!({}))
+}
+
+// 相似歌曲：getSimilarSongs的id可以是歌曲、专辑或歌手，getSimilarSongs2的id为歌手
+async fn get_similar_songs(params: &SubsonicParams, user_id: Uuid, config: &AppConfig, root: &str) -> Result<Value, SubsonicError> {
+    let id = params.require_id()?;
+    let kind = if root == "similarSongs2" {
+        "artist"
+    } else if config.song_repo.find_by_id(id).await?.is_some() {
+        "song"
+    } else if config.album_repo.find_by_id(id).await?.is_some() {
+        "album"
+    } else {
+        "artist"
+    };
+    let query = RadioQueryViewObject {
+        seed: None,
+        offset: None,
+        limit: Some(params.parse::<u64>("count").unwrap_or(50)),
+    };
+    let radio = get_radio_service(user_id, kind, id, query, config).await.map_err(|e| match e {
+        RadioServiceError::ItemNotFound(_) => SubsonicError::not_found("Item"),
+        e => SubsonicError::generic(&e.to_string()),
+    })?;
+    let songs = radio.songs.into_iter().map(|song| song.song).collect();
+
+    let mut value = json!({});
+    value[root] = json!({ "song": build_children(songs, user_id, config).await? });
+    Ok(value)
+}
+
+// 播放队列：current为当前歌曲ID，position为当前歌曲的播放进度（毫秒）
+async fn get_play_queue(user: &User, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let queue = get_play_queue_service(user.id, config).await.map_err(|e| SubsonicError::generic(&e.to_string()))?;
+    let Some(changed) = queue.updated_at else {
+        return Ok(json!({}));
+    };
+
+    let mut play_queue = json!({
+        "position": queue.position_ms,
+        "username": user.username,
+        "changed": changed.to_rfc3339(),
+        "changedBy": queue.client_name.unwrap_or_default(),
+        "entry": build_children(queue.songs, user.id, config).await?,
+    });
+    if let Some(current) = queue.song_ids.get(queue.current_index as usize) {
+        play_queue["current"] = json!(current);
+    }
+    Ok(json!({ "playQueue": play_queue }))
+}
+
+// 保存播放队列：id可以重复出现，不传id时清空队列；Subsonic客户端不带版本，后保存的覆盖先保存的
+async fn save_play_queue(params: &SubsonicParams, user_id: Uuid, config: &AppConfig) -> Result<Value, SubsonicError> {
+    let song_ids = params.get_ids("id")?;
+    let current_index = match params.get("current") {
+        Some(current) => {
+            let current = Uuid::parse_str(current).map_err(|_| SubsonicError::not_found("Song"))?;
+            let index = song_ids.iter()
+                .position(|id| *id == current)
+                .ok_or_else(|| SubsonicError::generic("current must be one of the queued songs"))?;
+            Some(index as i32)
+        }
+        None => None,
+    };
+    let data = SavePlayQueueViewObject {
+        song_ids,
+        current_index,
+        position_ms: params.parse::<i64>("position"),
+        client_name: params.get("c").map(|client| client.to_string()),
+        version: None,
+    };
+    save_play_queue_service(user_id, data, config).await.map_err(|e| match e {
+        PlayQueueServiceError::SongNotFound(_) => SubsonicError::not_found("Song"),
+        e => SubsonicError::generic(&e.to_string()),
+    })?;
+    Ok(json!({}))
+}
+
+// getStarred和getStarred2返回相同内容，只是根元素不同
+async fn get_starred(user_id: Uuid, config: &AppConfig, root: &str) -> Result<Value, SubsonicError> {
+    let artists = config.star_repo.find_starred_artists(user_id, 0, MAX_PAGE_SIZE).await?;
+    let albums = config.star_repo.find_starred_albums(user_id, 0, MAX_PAGE_SIZE).await?;
+    let songs = config.star_repo.find_starred_songs(user_id, 0, MAX_PAGE_SIZE).await?;
+
+    let album_counts: HashMap<Uuid, i64> = config.album_repo.count_by_artist().await?.into_iter().collect();
+    let artists: Vec<SubsonicArtist> = artists.iter()
+        .map(|(star, artist)| to_subsonic_artist(artist, album_counts.get(&artist.id).copied().unwrap_or(0), Some(star.created_at.to_rfc3339())))
+        .collect();
+    let albums = build_albums(albums.into_iter().map(|(_, album)| album).collect(), user_id, config).await?;
+    let songs = build_children(songs.into_iter().map(|(_, song)| song).collect(), user_id, config).await?;
+
+    let mut value = json!({});
+    value[root] = json!({ "artist": artists, "album": albums, "song": songs });
+    Ok(value)
+}
+
+async fn load_artists(config: &AppConfig) -> Result<Vec<Artist>, SubsonicError> {
+    let query = ArtistQueryData {
+        id: None,
+        name: None,
+        nationality: None,
+        sex: None,
+        page: None,
+        page_size: None,
+    };
+    let mut artists: Vec<Artist> = config.artist_repo.find_all(&query)
+        .await?
+        .into_iter()
+        .filter(|artist| !artist.delete_flag)
+        .collect();
+    artists.sort_by_key(|artist| artist.name.to_lowercase());
+    Ok(artists)
+}
+
+// 按名称首字母分组，非字母开头的归入#
+fn group_by_letter(artists: &[Artist]) -> BTreeMap<String, Vec<&Artist>> {
+    let mut groups: BTreeMap<String, Vec<&Artist>> = BTreeMap::new();
+    for artist in artists {
+        let letter = artist.name.chars()
+            .next()
+            .filter(|c| c.is_alphabetic())
+            .map(|c| c.to_uppercase().to_string())
+            .unwrap_or_else(|| "#".to_string());
+        groups.entry(letter).or_default().push(artist);
+    }
+    groups
+}
+
+fn to_subsonic_artist(artist: &Artist, album_count: i64, starred: Option<String>) -> SubsonicArtist {
+    SubsonicArtist {
+        id: artist.id,
+        name: artist.name.clone(),
+        cover_art: artist.avatar.as_ref().filter(|avatar| !avatar.is_empty()).map(|_| artist.id.to_string()),
+        album_count,
+        starred,
+    }
+}
+
+async fn build_albums(albums: Vec<Album>, user_id: Uuid, config: &AppConfig) -> Result<Vec<SubsonicAlbum>, SubsonicError> {
+    let album_ids: Vec<Uuid> = albums.iter().map(|album| album.id).collect();
+    let artist_ids: Vec<Uuid> = albums.iter().map(|album| album.artist_id).collect();
+    let artists = artist_names(&artist_ids, config).await?;
+    let starred = starred_map(user_id, StarItemType::Album, &album_ids, config).await?;
+    let mut ratings = rating_views(&config.rating_repo, user_id, RatingItemType::Album, &album_ids).await?;
+    let summaries: HashMap<Uuid, (i64, i64)> = config.song_repo.album_summaries(&album_ids)
+        .await?
+        .into_iter()
+        .map(|(album_id, count, duration)| (album_id, (count, duration)))
+        .collect();
+
+    Ok(albums.into_iter().map(|album| {
+        let (song_count, duration) = summaries.get(&album.id).copied().unwrap_or_default();
+        let rating = ratings.remove(&album.id).unwrap_or_default();
+        SubsonicAlbum {
+            id: album.id,
+            artist: artists.get(&album.artist_id).cloned().unwrap_or_default(),
+            artist_id: album.artist_id,
+            cover_art: album.cover_image.as_ref().filter(|cover| !cover.is_empty()).map(|_| album.id.to_string()),
+            song_count,
+            duration,
+            created: album.created_at.to_rfc3339(),
+            year: release_year(album.release_date),
+            genre: album.genre,
+            starred: starred.get(&album.id).cloned(),
+            user_rating: rating.user_rating,
+            average_rating: rating.average_rating,
+            name: album.name,
+        }
+    }).collect())
+}
+
+async fn build_children(songs: Vec<Song>, user_id: Uuid, config: &AppConfig) -> Result<Vec<SubsonicChild>, SubsonicError> {
+    let song_ids: Vec<Uuid> = songs.iter().map(|song| song.id).collect();
+    let starred = starred_map(user_id, StarItemType::Song, &song_ids, config).await?;
+    let mut ratings = rating_views(&config.rating_repo, user_id, RatingItemType::Song, &song_ids).await?;
+    let mut plays = play_stats(&config.play_event_repo, &song_ids).await?;
+    let album_ids: Vec<Uuid> = songs.iter().map(|song| song.album_id).collect();
+    let artist_ids: Vec<Uuid> = songs.iter().map(|song| song.artist_id).collect();
+    let artists = artist_names(&artist_ids, config).await?;
+    let albums: HashMap<Uuid, Album> = config.album_repo.find_by_ids(&album_ids)
+        .await?
+        .into_iter()
+        .map(|album| (album.id, album))
+        .collect();
+
+    Ok(songs.into_iter().map(|song| {
+        let album = albums.get(&song.album_id);
+        let suffix = Path::new(&song.file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let rating = ratings.remove(&song.id).unwrap_or_default();
+        let plays = plays.remove(&song.id).unwrap_or_default();
+        SubsonicChild {
+            id: song.id,
+            parent: song.album_id,
+            is_dir: false,
+            album: album.map(|album| album.name.clone()).unwrap_or_default(),
+            artist: artists.get(&song.artist_id).cloned().unwrap_or_default(),
+            track: song.track_number,
+            disc_number: song.disc_number,
+            year: album.and_then(|album| release_year(album.release_date)),
+            genre: song.genre.clone(),
+            cover_art: album
+                .filter(|album| album.cover_image.as_ref().is_some_and(|cover| !cover.is_empty()))
+                .map(|album| album.id.to_string()),
+            size: song.file_size,
+            content_type: content_type(&suffix).to_string(),
+            bit_rate: source_bitrate(&song),
+            duration: song.duration,
+            album_id: song.album_id,
+            artist_id: song.artist_id,
+            media_type: "music".to_string(),
+            created: song.created_at.to_rfc3339(),
+            starred: starred.get(&song.id).cloned(),
+            user_rating: rating.user_rating,
+            average_rating: rating.average_rating,
+            play_count: plays.play_count,
+            played: plays.last_played_at.map(|played| played.to_rfc3339()),
+            title: song.title,
+            suffix,
+        }
+    }).collect())
+}
+
+// 当前用户对一组对象的收藏时间
+async fn starred_map(user_id: Uuid, item_type: StarItemType, ids: &[Uuid], config: &AppConfig) -> Result<HashMap<Uuid, String>, SubsonicError> {
+    Ok(config.star_repo.find_by_items(user_id, item_type, ids)
+        .await?
+        .into_iter()
+        .map(|star| (star.item_id, star.created_at.to_rfc3339()))
+        .collect())
+}
+
+async fn artist_names(ids: &[Uuid], config: &AppConfig) -> Result<HashMap<Uuid, String>, SubsonicError> {
+    Ok(config.artist_repo.find_by_ids(ids)
+        .await?
+        .into_iter()
+        .map(|artist| (artist.id, artist.name))
+        .collect())
+}
+
+// 未知发行日期（默认值）不输出年份
+fn release_year(date: NaiveDate) -> Option<i32> {
+    (date != NaiveDate::default()).then(|| date.year())
+}
+
+fn content_type(suffix: &str) -> &'static str {
+    match suffix {
+        "mp3" => "audio/mpeg",
+        "flac" => "audio/flac",
+        "m4a" => "audio/mp4",
+        "ogg" | "opus" => "audio/ogg",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+// Subsonic歌手视图对象（ArtistID3）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsonicArtist {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_art: Option<String>,
+    pub album_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starred: Option<String>,
+}
+
+// Subsonic专辑视图对象（AlbumID3）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsonicAlbum {
+    pub id: Uuid,
+    pub name: String,
+    pub artist: String,
+    pub artist_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_art: Option<String>,
+    pub song_count: i64,
+    pub duration: i64,
+    pub created: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starred: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_rating: Option<i16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_rating: Option<f64>,
+}
+
+// Subsonic歌曲视图对象（Child）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsonicChild {
+    pub id: Uuid,
+    pub parent: Uuid,
+    pub is_dir: bool,
+    pub title: String,
+    pub album: String,
+    pub artist: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disc_number: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_art: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    pub content_type: String,
+    pub suffix: String,
+    pub duration: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bit_rate: Option<u32>,
+    pub album_id: Uuid,
+    pub artist_id: Uuid,
+    #[serde(rename = "type")]
+    pub media_type: String,
+    pub created: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starred: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_rating: Option<i16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_rating: Option<f64>,
+    pub play_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub played: Option<String>,
+}
@@ -0,0 +1,115 @@
+use super::SubsonicError;
+use serde_json::{json, Map, Value};
+
+/// 兼容的Subsonic API版本
+pub const API_VERSION: &str = "1.16.1";
+const XML_NAMESPACE: &str = "http://subsonic.org/restapi";
+
+/// 响应格式（f参数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Xml,
+    Json,
+}
+
+impl ResponseFormat {
+    pub fn parse(format: Option<&str>) -> Self {
+        match format {
+            Some("json") => ResponseFormat::Json,
+            _ => ResponseFormat::Xml,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResponseFormat::Xml => "application/xml; charset=utf-8",
+            ResponseFormat::Json => "application/json; charset=utf-8",
+        }
+    }
+}
+
+/// 渲染成功响应，payload为响应体中除状态字段外的内容
+pub fn render_ok(format: ResponseFormat, payload: Value) -> String {
+    render(format, "ok", payload)
+}
+
+/// 渲染错误响应
+pub fn render_error(format: ResponseFormat, error: &SubsonicError) -> String {
+    render(format, "failed", json!({
+        "error": { "code": error.code, "message": error.message }
+    }))
+}
+
+fn render(format: ResponseFormat, status: &str, payload: Value) -> String {
+    let mut body = Map::new();
+    body.insert("status".to_string(), json!(status));
+    body.insert("version".to_string(), json!(API_VERSION));
+    body.insert("type".to_string(), json!("music-server"));
+    body.insert("serverVersion".to_string(), json!(env!("CARGO_PKG_VERSION")));
+    body.insert("openSubsonic".to_string(), json!(true));
+    if let Value::Object(payload) = payload {
+        body.extend(payload);
+    }
+
+    match format {
+        ResponseFormat::Json => json!({ "subsonic-response": body }).to_string(),
+        ResponseFormat::Xml => {
+            body.insert("xmlns".to_string(), json!(XML_NAMESPACE));
+            let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+            write_element(&mut xml, "subsonic-response", &Value::Object(body));
+            xml
+        }
+    }
+}
+
+// 按Subsonic的XML约定输出：标量字段为属性，对象为子元素，数组为同名的重复子元素
+fn write_element(xml: &mut String, name: &str, value: &Value) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                write_element(xml, name, item);
+            }
+        }
+        Value::Object(fields) => {
+            xml.push('<');
+            xml.push_str(name);
+            for (key, value) in fields {
+                if let Some(text) = scalar_text(value) {
+                    xml.push_str(&format!(" {}=\"{}\"", key, escape(&text)));
+                }
+            }
+            let children: Vec<_> = fields.iter().filter(|(_, value)| scalar_text(value).is_none() && !value.is_null()).collect();
+            if children.is_empty() {
+                xml.push_str("/>");
+                return;
+            }
+            xml.push('>');
+            for (key, value) in children {
+                write_element(xml, key, value);
+            }
+            xml.push_str(&format!("</{}>", name));
+        }
+        Value::Null => {}
+        scalar => {
+            let text = scalar_text(scalar).unwrap_or_default();
+            xml.push_str(&format!("<{}>{}</{}>", name, escape(&text), name));
+        }
+    }
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
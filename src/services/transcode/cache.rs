@@ -0,0 +1,314 @@
+use super::model::{CachePurgeViewObject, CacheStatsViewObject};
+use super::{TranscodeProfile, TranscodeStream};
+use actix_web::web::Bytes;
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+// 写入中的临时文件后缀
+const PART_SUFFIX: &str = ".part";
+
+/// 转码缓存键：歌曲、源文件哈希和输出参数共同决定一份缓存
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub song_id: Uuid,
+    pub file_hash: String,
+    /// 输出变体，如 opus-128
+    pub variant: String,
+    pub extension: &'static str,
+}
+
+impl CacheKey {
+    pub fn new(song_id: Uuid, file_hash: &str, profile: &TranscodeProfile) -> Self {
+        Self {
+            song_id,
+            file_hash: file_hash.to_string(),
+            variant: format!("{}-{}", profile.format.name(), profile.bitrate),
+            extension: profile.format.name(),
+        }
+    }
+
+    /// HLS分片缓存键
+    pub fn segment(song_id: Uuid, file_hash: &str, bitrate: u32, index: u32) -> Self {
+        Self {
+            song_id,
+            file_hash: file_hash.to_string(),
+            variant: format!("hls-aac-{}-{:05}", bitrate, index),
+            extension: "ts",
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}_{}_{}.{}", self.song_id, self.file_hash, self.variant, self.extension)
+    }
+}
+
+struct CacheEntry {
+    song_id: Uuid,
+    size: u64,
+    last_access: SystemTime,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    total_size: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// 磁盘转码缓存，超过容量上限时按最近最少使用淘汰
+#[derive(Clone)]
+pub struct TranscodeCache {
+    dir: PathBuf,
+    max_size: u64,
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl TranscodeCache {
+    /// 打开缓存目录并加载已有缓存文件，清理上次未写完的临时文件
+    pub fn open(dir: &str, max_size: u64) -> io::Result<Self> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir)?;
+
+        let mut state = CacheState::default();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(PART_SUFFIX) {
+                let _ = fs::remove_file(entry.path());
+                continue;
+            }
+            let song_id = match name.split('_').next().and_then(|id| Uuid::parse_str(id).ok()) {
+                Some(song_id) => song_id,
+                None => continue,
+            };
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            state.total_size += metadata.len();
+            state.entries.insert(name, CacheEntry {
+                song_id,
+                size: metadata.len(),
+                last_access: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+        log::info!("Transcode cache loaded {} entries ({} bytes)", state.entries.len(), state.total_size);
+
+        let cache = Self { dir, max_size, state: Arc::new(Mutex::new(state)) };
+        cache.evict();
+        Ok(cache)
+    }
+
+    /// 查找缓存文件，命中时更新访问时间
+    pub fn lookup(&self, key: &CacheKey) -> Option<PathBuf> {
+        let name = key.file_name();
+        let path = self.dir.join(&name);
+        let mut state = self.lock();
+
+        let hit = match state.entries.get_mut(&name) {
+            Some(entry) if path.exists() => {
+                entry.last_access = SystemTime::now();
+                true
+            }
+            _ => false,
+        };
+        if hit {
+            state.hits += 1;
+            // 修改时间作为持久化的访问时间，重启后用于LRU排序
+            if let Ok(file) = fs::File::options().write(true).open(&path) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+            Some(path)
+        } else {
+            if let Some(entry) = state.entries.remove(&name) {
+                state.total_size -= entry.size;
+            }
+            state.misses += 1;
+            None
+        }
+    }
+
+    /// 边转码边写入缓存，输出流完整结束后才会登记为可用缓存
+    pub fn store_stream(&self, key: CacheKey, body: TranscodeStream) -> TranscodeStream {
+        let writer = CacheWriter {
+            cache: self.clone(),
+            part_path: self.temp_path(&key),
+            key,
+            file: None,
+            size: 0,
+            failed: false,
+        };
+        Box::pin(stream::unfold(Some((body, writer)), |state| async move {
+            let (mut body, mut writer) = state?;
+            match body.next().await {
+                Some(Ok(chunk)) => {
+                    writer.write(&chunk).await;
+                    Some((Ok(chunk), Some((body, writer))))
+                }
+                Some(Err(e)) => Some((Err(e), None)),
+                None => {
+                    writer.finish().await;
+                    None
+                }
+            }
+        }))
+    }
+
+    /// 将已生成的文件登记到缓存
+    pub fn insert(&self, key: &CacheKey, source: &Path) -> io::Result<PathBuf> {
+        let name = key.file_name();
+        let path = self.dir.join(&name);
+        fs::rename(source, &path)?;
+        let size = fs::metadata(&path)?.len();
+        {
+            let mut state = self.lock();
+            if let Some(previous) = state.entries.insert(name, CacheEntry {
+                song_id: key.song_id,
+                size,
+                last_access: SystemTime::now(),
+            }) {
+                state.total_size -= previous.size;
+            }
+            state.total_size += size;
+        }
+        self.evict();
+        Ok(path)
+    }
+
+    /// 生成缓存目录内的临时文件路径
+    pub fn temp_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(format!("{}.{}{}", key.file_name(), Uuid::new_v4(), PART_SUFFIX))
+    }
+
+    /// 清理缓存，指定歌曲时只清理该歌曲的缓存
+    pub fn purge(&self, song_id: Option<Uuid>) -> CachePurgeViewObject {
+        let mut state = self.lock();
+        let names: Vec<String> = state.entries.iter()
+            .filter(|(_, entry)| song_id.is_none_or(|id| entry.song_id == id))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        let mut purged = CachePurgeViewObject { removed_entries: 0, freed_size: 0 };
+        for name in names {
+            if let Some(entry) = state.entries.remove(&name) {
+                remove_cache_file(&self.dir.join(&name));
+                state.total_size -= entry.size;
+                purged.removed_entries += 1;
+                purged.freed_size += entry.size;
+            }
+        }
+        purged
+    }
+
+    pub fn stats(&self) -> CacheStatsViewObject {
+        let state = self.lock();
+        let requests = state.hits + state.misses;
+        CacheStatsViewObject {
+            entries: state.entries.len(),
+            total_size: state.total_size,
+            max_size: self.max_size,
+            hits: state.hits,
+            misses: state.misses,
+            hit_rate: if requests == 0 { 0.0 } else { state.hits as f64 / requests as f64 },
+        }
+    }
+
+    // 超过容量上限时淘汰最久未访问的缓存
+    fn evict(&self) {
+        let mut state = self.lock();
+        while state.total_size > self.max_size {
+            let oldest = state.entries.iter()
+                .min_by_key(|(_, entry)| entry.last_access)
+                .map(|(name, _)| name.clone());
+            let name = match oldest {
+                Some(name) => name,
+                None => break,
+            };
+            if let Some(entry) = state.entries.remove(&name) {
+                remove_cache_file(&self.dir.join(&name));
+                state.total_size -= entry.size;
+                log::debug!("Evicted transcode cache entry {}", name);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// 转码输出写入器，流被提前丢弃（如客户端断开）时删除未写完的临时文件
+struct CacheWriter {
+    cache: TranscodeCache,
+    key: CacheKey,
+    part_path: PathBuf,
+    file: Option<tokio::fs::File>,
+    size: u64,
+    failed: bool,
+}
+
+impl CacheWriter {
+    async fn write(&mut self, chunk: &Bytes) {
+        if self.failed {
+            return;
+        }
+        if self.file.is_none() {
+            match tokio::fs::File::create(&self.part_path).await {
+                Ok(file) => self.file = Some(file),
+                Err(e) => return self.fail(e),
+            }
+        }
+        if let Some(file) = self.file.as_mut() {
+            match file.write_all(chunk).await {
+                Ok(()) => self.size += chunk.len() as u64,
+                Err(e) => self.fail(e),
+            }
+        }
+    }
+
+    async fn finish(&mut self) {
+        let mut file = match self.file.take() {
+            Some(file) if !self.failed && self.size > 0 => file,
+            _ => return,
+        };
+        if let Err(e) = file.flush().await {
+            return self.fail(e);
+        }
+        drop(file);
+        match self.cache.insert(&self.key, &self.part_path) {
+            Ok(path) => log::debug!("Stored transcode cache {:?} ({} bytes)", path, self.size),
+            Err(e) => self.fail(e),
+        }
+    }
+
+    fn fail(&mut self, e: io::Error) {
+        log::warn!("Failed to write transcode cache {}: {:?}", self.key.file_name(), e);
+        self.failed = true;
+        self.file = None;
+        remove_cache_file(&self.part_path);
+    }
+}
+
+impl Drop for CacheWriter {
+    fn drop(&mut self) {
+        if self.file.is_some() {
+            remove_cache_file(&self.part_path);
+        }
+    }
+}
+
+fn remove_cache_file(path: &Path) {
+    if let Err(e) = fs::remove_file(path)
+        && e.kind() != io::ErrorKind::NotFound
+    {
+        log::warn!("Failed to remove cache file {:?}: {:?}", path, e);
+    }
+}
@@ -0,0 +1,253 @@
+pub mod cache;
+pub mod model;
+use actix_web::web::Bytes;
+use futures_util::stream::{self, Stream};
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, ChildStdout, Command};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// 每次从编码器读取的块大小
+const CHUNK_SIZE: usize = 64 * 1024;
+const MIN_BITRATE: u32 = 32;
+const MAX_BITRATE: u32 = 320;
+
+#[derive(Debug)]
+pub enum TranscodeError {
+    UnsupportedFormat(String),
+    Busy,
+    EncoderUnavailable(io::Error),
+    EncoderFailed(String),
+}
+
+impl fmt::Display for TranscodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TranscodeError::UnsupportedFormat(format) => write!(f, "Unsupported transcode format: {}", format),
+            TranscodeError::Busy => write!(f, "Too many concurrent transcodes"),
+            TranscodeError::EncoderUnavailable(e) => write!(f, "Encoder unavailable: {:?}", e),
+            TranscodeError::EncoderFailed(message) => write!(f, "Encoder failed: {}", message),
+        }
+    }
+}
+
+/// 转码目标格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TranscodeFormat {
+    Mp3,
+    Opus,
+    Aac,
+}
+
+impl TranscodeFormat {
+    pub fn parse(format: &str) -> Result<Self, TranscodeError> {
+        match format.to_lowercase().as_str() {
+            "mp3" => Ok(TranscodeFormat::Mp3),
+            "opus" => Ok(TranscodeFormat::Opus),
+            "aac" => Ok(TranscodeFormat::Aac),
+            _ => Err(TranscodeError::UnsupportedFormat(format.to_string())),
+        }
+    }
+
+    /// 格式名称，同时作为输出文件扩展名
+    pub fn name(&self) -> &'static str {
+        match self {
+            TranscodeFormat::Mp3 => "mp3",
+            TranscodeFormat::Opus => "opus",
+            TranscodeFormat::Aac => "aac",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            TranscodeFormat::Mp3 => "audio/mpeg",
+            TranscodeFormat::Opus => "audio/ogg",
+            TranscodeFormat::Aac => "audio/aac",
+        }
+    }
+
+    pub fn default_bitrate(&self) -> u32 {
+        match self {
+            TranscodeFormat::Mp3 => 192,
+            TranscodeFormat::Opus => 128,
+            TranscodeFormat::Aac => 160,
+        }
+    }
+
+    // 编码器参数：音频编码器和输出容器
+    fn encoder_args(&self) -> [&'static str; 4] {
+        match self {
+            TranscodeFormat::Mp3 => ["-c:a", "libmp3lame", "-f", "mp3"],
+            TranscodeFormat::Opus => ["-c:a", "libopus", "-f", "ogg"],
+            TranscodeFormat::Aac => ["-c:a", "aac", "-f", "adts"],
+        }
+    }
+}
+
+/// 转码参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TranscodeProfile {
+    pub format: TranscodeFormat,
+    /// 目标码率(kbps)
+    pub bitrate: u32,
+}
+
+impl TranscodeProfile {
+    /// 根据请求参数和源文件信息决定是否需要转码
+    ///
+    /// 未指定参数、或源文件格式相同且码率不超过上限时返回 None，直接播放原文件
+    pub fn resolve(
+        format: Option<&str>,
+        max_bitrate: Option<u32>,
+        source_extension: &str,
+        source_bitrate: Option<u32>,
+    ) -> Result<Option<Self>, TranscodeError> {
+        let max_bitrate = max_bitrate.filter(|bitrate| *bitrate > 0);
+        let format = match format {
+            Some(format) if format.eq_ignore_ascii_case("raw") => return Ok(None),
+            Some(format) => Some(TranscodeFormat::parse(format)?),
+            None => None,
+        };
+        if format.is_none() && max_bitrate.is_none() {
+            return Ok(None);
+        }
+
+        let source_format = TranscodeFormat::parse(source_extension).ok();
+        let format = format.or(source_format).unwrap_or(TranscodeFormat::Mp3);
+        let bitrate = max_bitrate
+            .unwrap_or_else(|| format.default_bitrate())
+            .clamp(MIN_BITRATE, MAX_BITRATE);
+
+        let already_fits = source_format == Some(format)
+            && source_bitrate.is_some_and(|source| source <= bitrate);
+        if already_fits {
+            return Ok(None);
+        }
+        Ok(Some(TranscodeProfile { format, bitrate }))
+    }
+}
+
+/// 转码输出流
+pub type TranscodeStream = Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>>>>;
+
+/// 外部编码器（ffmpeg兼容命令行）包装，限制同时进行的转码数量
+#[derive(Clone)]
+pub struct Transcoder {
+    binary: String,
+    permits: Arc<Semaphore>,
+}
+
+impl Transcoder {
+    pub fn new(binary: String, max_concurrent: usize) -> Self {
+        Self {
+            binary,
+            permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
+        }
+    }
+
+    /// 启动转码进程并返回输出流
+    ///
+    /// 会先读取第一块输出，编码器启动失败或无输出时返回错误，调用方可回退到原文件
+    pub async fn start(&self, source: &Path, profile: &TranscodeProfile) -> Result<TranscodeStream, TranscodeError> {
+        let permit = self.permits.clone().try_acquire_owned().map_err(|_| TranscodeError::Busy)?;
+
+        let mut child = Command::new(&self.binary)
+            .args(["-nostdin", "-v", "error", "-i"])
+            .arg(source)
+            .args(["-map", "0:a:0", "-vn", "-map_metadata", "-1"])
+            .args(profile.format.encoder_args())
+            .args(["-b:a", &format!("{}k", profile.bitrate), "pipe:1"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(TranscodeError::EncoderUnavailable)?;
+
+        let mut stdout = child.stdout.take()
+            .ok_or_else(|| TranscodeError::EncoderFailed("No encoder output".to_string()))?;
+        let first = read_chunk(&mut stdout)
+            .await
+            .map_err(|e| TranscodeError::EncoderFailed(e.to_string()))?;
+        if first.is_empty() {
+            let status = child.wait().await.map_err(|e| TranscodeError::EncoderFailed(e.to_string()))?;
+            return Err(TranscodeError::EncoderFailed(format!("Encoder exited with {}", status)));
+        }
+
+        let state = EncoderOutput { child, stdout, _permit: permit, first: Some(first) };
+        Ok(Box::pin(stream::unfold(Some(state), next_chunk)))
+    }
+
+    /// 将源文件的一段转码为AAC编码的MPEG-TS分片并写入文件
+    ///
+    /// 设置输出时间戳偏移，保证相邻分片的时间轴连续
+    pub async fn transcode_segment(
+        &self,
+        source: &Path,
+        bitrate: u32,
+        start: u32,
+        length: u32,
+        output: &Path,
+    ) -> Result<(), TranscodeError> {
+        let _permit = self.permits.clone().try_acquire_owned().map_err(|_| TranscodeError::Busy)?;
+
+        let status = Command::new(&self.binary)
+            .args(["-nostdin", "-v", "error", "-ss", &start.to_string(), "-t", &length.to_string(), "-i"])
+            .arg(source)
+            .args(["-map", "0:a:0", "-vn", "-map_metadata", "-1", "-c:a", "aac"])
+            .args(["-b:a", &format!("{}k", bitrate), "-output_ts_offset", &start.to_string()])
+            .args(["-f", "mpegts", "-y"])
+            .arg(output)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .status()
+            .await
+            .map_err(TranscodeError::EncoderUnavailable)?;
+
+        if !status.success() {
+            let _ = tokio::fs::remove_file(output).await;
+            return Err(TranscodeError::EncoderFailed(format!("Encoder exited with {}", status)));
+        }
+        Ok(())
+    }
+}
+
+// 编码器输出状态，流被丢弃时子进程随之结束并释放并发许可
+struct EncoderOutput {
+    child: Child,
+    stdout: ChildStdout,
+    _permit: OwnedSemaphorePermit,
+    first: Option<Bytes>,
+}
+
+async fn next_chunk(state: Option<EncoderOutput>) -> Option<(Result<Bytes, io::Error>, Option<EncoderOutput>)> {
+    let mut state = state?;
+    if let Some(first) = state.first.take() {
+        return Some((Ok(first), Some(state)));
+    }
+
+    match read_chunk(&mut state.stdout).await {
+        // 编码器异常退出时返回错误，避免把不完整的输出当作成功结果
+        Ok(chunk) if chunk.is_empty() => match state.child.wait().await {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some((Err(io::Error::other(format!("Encoder exited with {}", status))), None)),
+            Err(e) => Some((Err(e), None)),
+        },
+        Ok(chunk) => Some((Ok(chunk), Some(state))),
+        Err(e) => Some((Err(e), None)),
+    }
+}
+
+async fn read_chunk(stdout: &mut ChildStdout) -> Result<Bytes, io::Error> {
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let read = stdout.read(&mut buffer).await?;
+    buffer.truncate(read);
+    Ok(Bytes::from(buffer))
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 转码缓存统计视图对象
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStatsViewObject {
+    pub entries: usize,
+    pub total_size: u64,
+    pub max_size: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+// 缓存清理结果视图对象
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachePurgeViewObject {
+    pub removed_entries: usize,
+    pub freed_size: u64,
+}
+
+// 缓存清理参数视图对象
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachePurgeQueryViewObject {
+    /// 只清理指定歌曲的缓存，为空时清理全部
+    pub song_id: Option<Uuid>,
+}
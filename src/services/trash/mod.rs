@@ -0,0 +1,125 @@
+pub mod model;
+use self::model::*;
+use chrono::Local;
+use crate::config::AppConfig;
+use crate::models::trash::{RestoreResult, TrashItemType};
+use std::fmt;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub enum TrashServiceError {
+    DatabaseError(sea_orm::DbErr),
+    InvalidItemType(String),
+    TrashItemNotFound,
+    // 所属的上级对象仍在回收站中
+    ParentDeleted(&'static str),
+    UsernameTaken,
+}
+
+impl fmt::Display for TrashServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrashServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
+            TrashServiceError::InvalidItemType(kind) => write!(f, "Invalid item type: {}", kind),
+            TrashServiceError::TrashItemNotFound => write!(f, "Deleted item not found"),
+            TrashServiceError::ParentDeleted(parent) => write!(f, "The {} of this item is deleted, restore it first", parent),
+            TrashServiceError::UsernameTaken => write!(f, "Username is taken by another user"),
+        }
+    }
+}
+
+impl From<sea_orm::DbErr> for TrashServiceError {
+    fn from(e: sea_orm::DbErr) -> Self {
+        TrashServiceError::DatabaseError(e)
+    }
+}
+
+fn parse_item_type(kind: &str) -> Result<TrashItemType, TrashServiceError> {
+    TrashItemType::parse(kind).ok_or_else(|| TrashServiceError::InvalidItemType(kind.to_string()))
+}
+
+/// 列出已软删除的用户、歌手、专辑和歌曲，按删除时间倒序
+pub async fn list_trash_service(
+    query: TrashQueryViewObject,
+    config: &AppConfig,
+) -> Result<Vec<TrashItemViewObject>, TrashServiceError> {
+    let item_type = query.kind.as_deref().map(parse_item_type).transpose()?;
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    let entries = config.trash_repo
+        .find_deleted(item_type, (page - 1) * page_size, page_size)
+        .await?;
+    Ok(entries.into_iter().map(TrashItemViewObject::from).collect())
+}
+
+/// 恢复回收站中的对象，同一次级联删除的下级对象一并恢复
+pub async fn restore_trash_item_service(
+    kind: &str,
+    id: Uuid,
+    restored_by: &str,
+    config: &AppConfig,
+) -> Result<RestoreViewObject, TrashServiceError> {
+    let item_type = parse_item_type(kind)?;
+    match config.trash_repo.restore(item_type, id, restored_by).await? {
+        RestoreResult::Restored(counts) => Ok(counts.into()),
+        RestoreResult::NotFound => Err(TrashServiceError::TrashItemNotFound),
+        RestoreResult::ParentDeleted(parent) => Err(TrashServiceError::ParentDeleted(parent.as_str())),
+        RestoreResult::UsernameTaken => Err(TrashServiceError::UsernameTaken),
+    }
+}
+
+/// 永久删除超过保留期限的已删除对象，并删除不再被引用的媒体文件
+pub async fn purge_trash_service(config: &AppConfig) -> Result<PurgeViewObject, TrashServiceError> {
+    let before = Local::now() - chrono::Duration::days(config.trash_retention_days as i64);
+    let result = config.trash_repo.purge(before).await?;
+
+    let mut removed_files = 0;
+    for file_path in &result.unreferenced_files {
+        if remove_media_file(&config.media_root, file_path).await {
+            removed_files += 1;
+        }
+    }
+    Ok(PurgeViewObject::new(&result, removed_files))
+}
+
+// 只删除媒体根目录内的文件，库外的路径和已不存在的文件被跳过
+async fn remove_media_file(media_root: &str, file_path: &str) -> bool {
+    let (Ok(root), Ok(path)) = (tokio::fs::canonicalize(media_root).await, tokio::fs::canonicalize(file_path).await) else {
+        return false;
+    };
+    if !path.starts_with(&root) || path == root {
+        return false;
+    }
+    match tokio::fs::remove_file(&path).await {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("Failed to remove media file {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// 启动回收站定时清理后台任务，保留天数为0时不自动清理
+pub fn start_trash_purge_scheduler(config: &AppConfig) {
+    if config.trash_retention_days == 0 {
+        return;
+    }
+    let config = config.clone();
+    let period = Duration::from_secs(config.trash_purge_minutes.max(1) * 60);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match purge_trash_service(&config).await {
+                Ok(purged) => log::info!(
+                    "Trash purged: {} users, {} artists, {} albums, {} songs, {} files",
+                    purged.users, purged.artists, purged.albums, purged.songs, purged.removed_files,
+                ),
+                Err(e) => log::error!("Failed to purge trash: {}", e),
+            }
+        }
+    });
+}
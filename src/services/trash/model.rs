@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+use crate::models::trash::{PurgeResult, RestoredCounts, TrashEntry};
+
+/// 回收站查询视图对象
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashQueryViewObject {
+    /// 对象类型：users、artists、albums、songs，为空时列出全部类型
+    pub kind: Option<String>,
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+/// 回收站条目视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItemViewObject {
+    /// 对象类型：user、artist、album、song
+    pub item_type: String,
+    pub id: Uuid,
+    /// 用户名、歌手名、专辑名或歌曲标题
+    pub name: String,
+    pub deleted_at: DateTime<Local>,
+    /// 删除人：用户ID或系统任务名称
+    pub deleted_by: Option<String>,
+    /// 删除人为用户时的用户名
+    pub deleted_by_username: Option<String>,
+}
+
+impl From<TrashEntry> for TrashItemViewObject {
+    fn from(entry: TrashEntry) -> Self {
+        TrashItemViewObject {
+            item_type: entry.item_type,
+            id: entry.id,
+            name: entry.name,
+            deleted_at: entry.deleted_at,
+            deleted_by: entry.deleted_by,
+            deleted_by_username: entry.deleted_by_username,
+        }
+    }
+}
+
+/// 恢复结果视图对象，包含随之恢复的下级对象数量
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreViewObject {
+    pub users: u64,
+    pub artists: u64,
+    pub albums: u64,
+    pub songs: u64,
+}
+
+impl From<RestoredCounts> for RestoreViewObject {
+    fn from(counts: RestoredCounts) -> Self {
+        RestoreViewObject {
+            users: counts.users,
+            artists: counts.artists,
+            albums: counts.albums,
+            songs: counts.songs,
+        }
+    }
+}
+
+/// 清理结果视图对象
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeViewObject {
+    pub users: u64,
+    pub artists: u64,
+    pub albums: u64,
+    pub songs: u64,
+    /// 删除的媒体文件数量
+    pub removed_files: u64,
+}
+
+impl PurgeViewObject {
+    pub fn new(result: &PurgeResult, removed_files: u64) -> Self {
+        PurgeViewObject {
+            users: result.users,
+            artists: result.artists,
+            albums: result.albums,
+            songs: result.songs,
+            removed_files,
+        }
+    }
+}
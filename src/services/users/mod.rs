@@ -1,11 +1,15 @@
 pub mod model;
-pub use model::{ UserQueryViewObject, UserResponseViewObject};
+pub use model::{ UserQueryViewObject, UserResponseViewObject, SubsonicPasswordViewObject};
 use std::{fmt, sync::Arc};
 use crate::models::{ user::UserRepository,QueryUserData};
+use crate::utils::crypto::encrypt_secret;
+use uuid::Uuid;
 #[derive(Debug)]
 pub enum UserServiceError {
     DatabaseError(sea_orm::DbErr),
     UserNotFound,
+    InvalidPassword,
+    EncryptionError,
 }
 
 impl fmt::Display for UserServiceError {
@@ -13,6 +17,8 @@ impl fmt::Display for UserServiceError {
         match self {
             UserServiceError::DatabaseError(e) => write!(f, "Database error: {:?}", e),
             UserServiceError::UserNotFound => write!(f, "User not found"),
+            UserServiceError::InvalidPassword => write!(f, "Password must not be empty"),
+            UserServiceError::EncryptionError => write!(f, "Failed to encrypt password"),
         }
     }
 }
@@ -42,4 +48,28 @@ pub async fn get_users_service(query: UserQueryViewObject, user_repo: Arc<dyn Us
         }
     }).collect::<Vec<_>>();
     Ok(user_list)
-}
\ No newline at end of file
+}
+
+/// 设置当前用户的Subsonic密码服务
+///
+/// Subsonic令牌认证需要用明文密码计算md5(password + salt)，因此单独保存一份加密的密码，
+/// 不影响登录密码的bcrypt哈希；传入空密码视为无效
+pub async fn set_subsonic_password_service(
+    user_id: Uuid,
+    data: SubsonicPasswordViewObject,
+    secret: &str,
+    user_repo: Arc<dyn UserRepository>,
+) -> Result<(), UserServiceError> {
+    if data.password.is_empty() {
+        return Err(UserServiceError::InvalidPassword);
+    }
+    user_repo.find_by_id(user_id)
+        .await
+        .map_err(UserServiceError::DatabaseError)?
+        .ok_or(UserServiceError::UserNotFound)?;
+
+    let encrypted = encrypt_secret(&data.password, secret).map_err(|_| UserServiceError::EncryptionError)?;
+    user_repo.update_subsonic_password(user_id, Some(encrypted))
+        .await
+        .map_err(UserServiceError::DatabaseError)
+}
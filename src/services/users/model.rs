@@ -34,4 +34,10 @@ pub struct UserResponseViewObject {
     pub role: Option<String>,
     pub page: Option<u64>,
     pub page_size: Option<u64>,
+}
+
+// 设置Subsonic密码视图对象
+#[derive(Debug, Deserialize)]
+pub struct SubsonicPasswordViewObject {
+    pub password: String,
 }
\ No newline at end of file
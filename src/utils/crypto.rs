@@ -0,0 +1,31 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use sha2::{Digest, Sha256};
+
+// AES-GCM随机数长度
+const NONCE_SIZE: usize = 12;
+
+// 由服务端密钥派生加密密钥
+fn cipher(secret: &str) -> Aes256Gcm {
+    let key = Sha256::digest(secret.as_bytes());
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+// 加密需要可还原的敏感字段，返回十六进制的 nonce+密文
+pub fn encrypt_secret(plain: &str, secret: &str) -> Result<String, aes_gcm::Error> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut data = nonce.to_vec();
+    data.extend(cipher(secret).encrypt(&nonce, plain.as_bytes())?);
+    Ok(hex::encode(data))
+}
+
+// 解密由encrypt_secret生成的字段
+pub fn decrypt_secret(encrypted: &str, secret: &str) -> Option<String> {
+    let data = hex::decode(encrypted).ok()?;
+    if data.len() <= NONCE_SIZE {
+        return None;
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+    let plain = cipher(secret).decrypt(Nonce::from_slice(nonce), ciphertext).ok()?;
+    String::from_utf8(plain).ok()
+}
@@ -53,3 +53,13 @@ where
    let formatted = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
     serializer.serialize_str(&formatted)
 }
+
+pub fn format_optional_datetime<S>(datetime: &Option<DateTime<Local>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match datetime {
+        Some(datetime) => format_datetime(datetime, serializer),
+        None => serializer.serialize_none(),
+    }
+}
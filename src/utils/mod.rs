@@ -1,4 +1,8 @@
+// 工具函数中有部分尚未在业务代码中使用
+#[allow(dead_code)]
 pub mod date_time;
+#[allow(dead_code)]
 pub mod auth;
+pub mod crypto;
+pub mod nullable;
 pub use auth::*;
-pub use date_time::*;
\ No newline at end of file
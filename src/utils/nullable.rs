@@ -0,0 +1,33 @@
+use serde::{Deserialize, Deserializer};
+
+// 用于PATCH请求的可清空字段：未传时为None（字段需同时标注#[serde(default)]），
+// 显式传入null时为Some(None)，传值时为Some(Some(value))
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Patch {
+        #[serde(default, deserialize_with = "super::deserialize")]
+        genre: Option<Option<String>>,
+    }
+
+    #[test]
+    fn distinguishes_missing_null_and_value() {
+        let missing: Patch = serde_json::from_str("{}").unwrap();
+        let null: Patch = serde_json::from_str(r#"{"genre":null}"#).unwrap();
+        let value: Patch = serde_json::from_str(r#"{"genre":"Jazz"}"#).unwrap();
+
+        assert_eq!(missing.genre, None);
+        assert_eq!(null.genre, Some(None));
+        assert_eq!(value.genre, Some(Some("Jazz".to_string())));
+    }
+}